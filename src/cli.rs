@@ -1,33 +1,334 @@
-#[derive(Debug)]
+use serde::{Deserialize, Serialize};
+
+// The same variants double as JSON-RPC methods for `serve`: the method name is the
+// kebab-case command name and `params` holds the fields.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "kebab-case")]
 pub enum Command {
-    CheckItemDownload { app_id: u32, item_id: u64 },
-    CollectionItems { app_id: u32, item_id: u64 },
-    WorkshopItems { app_id: u32, item_ids: Vec<u64> },
-    Subscribe { app_id: u32, item_ids: Vec<u64> },
-    Unsubscribe { app_id: u32, item_ids: Vec<u64> },
-    DownloadWorkshopItem { app_id: u32, item_id: u64 },
-    SubscribedItems { app_id: u32 },
+    CheckItemDownload {
+        app_id: u32,
+        item_id: u64,
+    },
+    CollectionItems {
+        app_id: u32,
+        item_id: u64,
+    },
+    WorkshopItems {
+        app_id: u32,
+        item_ids: Vec<u64>,
+        #[serde(default)]
+        with_user_state: bool,
+        #[serde(default)]
+        item_type: Option<String>,
+    },
+    Subscribe {
+        app_id: u32,
+        item_ids: Vec<u64>,
+    },
+    Unsubscribe {
+        app_id: u32,
+        item_ids: Vec<u64>,
+    },
+    DownloadWorkshopItem {
+        app_id: u32,
+        item_id: u64,
+    },
+    SubscribedItems {
+        app_id: u32,
+        #[serde(default)]
+        game_version: Option<String>,
+    },
+    CompatReport {
+        app_id: u32,
+        game_version: String,
+    },
     SearchWorkshop {
         app_id: u32,
+        #[serde(default)]
         query: String,
+        #[serde(default = "default_sort_by")]
         sort_by: String,
+        #[serde(default)]
         period: Option<String>,
+        #[serde(default = "default_page")]
         page: u32,
+        #[serde(default)]
         tags: Option<String>,
+        #[serde(default)]
+        exclude_tags: Option<String>,
+        #[serde(default)]
+        match_any_tags: bool,
+        #[serde(default)]
+        tag_groups: Vec<String>,
+        #[serde(default)]
+        created_since: Option<String>,
+        #[serde(default)]
+        created_until: Option<String>,
+        #[serde(default)]
+        updated_since: Option<String>,
+        #[serde(default)]
+        updated_until: Option<String>,
+        #[serde(default)]
+        item_type: Option<String>,
+        #[serde(default)]
+        game_version: Option<String>,
+        #[serde(default)]
+        page_info: bool,
+        #[serde(default)]
+        all_pages: bool,
+        #[serde(default)]
+        max_pages: Option<u32>,
+    },
+    UserItems {
+        app_id: u32,
+        list: String,
+        #[serde(default)]
+        order: Option<String>,
+        #[serde(default = "default_page")]
+        page: u32,
+        #[serde(default)]
+        user: Option<String>,
+    },
+    WorkshopPath {
+        app_id: u32,
     },
-    WorkshopPath { app_id: u32 },
-    SteamLibraryPaths,
+    SteamLibraryPaths {
+        #[serde(default)]
+        detailed: bool,
+    },
+    SteamInstallPaths,
     ClearCache,
-    DiscoverTags { app_id: u32 },
+    DiscoverTags {
+        app_id: u32,
+    },
+    Publish {
+        app_id: u32,
+        title: String,
+        #[serde(default)]
+        description: String,
+        content_folder: String,
+        preview_image: String,
+        #[serde(default = "default_visibility")]
+        visibility: String,
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(default)]
+        change_note: Option<String>,
+    },
+    PublishManifest {
+        manifest: String,
+        #[serde(default)]
+        version: Option<String>,
+        #[serde(default)]
+        change_note: Option<String>,
+    },
+    UpdateItem {
+        app_id: u32,
+        item_id: u64,
+        #[serde(flatten)]
+        changes: ItemChanges,
+    },
+    DeleteItem {
+        app_id: u32,
+        item_id: u64,
+        #[serde(default)]
+        yes: bool,
+    },
+    SetVisibility {
+        app_id: u32,
+        item_id: u64,
+        visibility: String,
+    },
+    AddDependency {
+        app_id: u32,
+        item_id: u64,
+        #[serde(default)]
+        dependency_ids: Vec<u64>,
+        #[serde(default)]
+        app_dependency_ids: Vec<u32>,
+    },
+    RemoveDependency {
+        app_id: u32,
+        item_id: u64,
+        #[serde(default)]
+        dependency_ids: Vec<u64>,
+        #[serde(default)]
+        app_dependency_ids: Vec<u32>,
+    },
+    ResolveDependencies {
+        app_id: u32,
+        item_ids: Vec<u64>,
+        #[serde(default)]
+        dot: bool,
+    },
+    Vote {
+        app_id: u32,
+        item_id: u64,
+        vote: String,
+    },
+    GetVote {
+        app_id: u32,
+        item_id: u64,
+    },
+    Favorite {
+        app_id: u32,
+        item_id: u64,
+        action: String,
+    },
+    CollectionCreate {
+        app_id: u32,
+        title: String,
+        #[serde(default)]
+        description: String,
+        #[serde(default = "default_visibility")]
+        visibility: String,
+        #[serde(default)]
+        preview_image: Option<String>,
+        #[serde(default)]
+        item_ids: Vec<u64>,
+    },
+    CollectionFromSubscriptions {
+        app_id: u32,
+        title: String,
+        #[serde(default)]
+        description: String,
+        #[serde(default = "default_visibility")]
+        visibility: String,
+        #[serde(default)]
+        preview_image: Option<String>,
+    },
+    CollectionAdd {
+        app_id: u32,
+        collection_id: u64,
+        item_ids: Vec<u64>,
+        #[serde(default)]
+        position: Option<usize>,
+    },
+    CollectionRemove {
+        app_id: u32,
+        collection_id: u64,
+        item_ids: Vec<u64>,
+    },
+    #[serde(skip)]
+    Serve(ServeOptions),
+    #[serde(skip)]
+    HttpServe {
+        port: u16,
+        allowed_origins: Vec<String>,
+        options: ServeOptions,
+    },
+}
+
+/// The item fields `update-item` changes. Fields left out keep their current value.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemChanges {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub content_folder: Option<String>,
+    pub preview_image: Option<String>,
+    pub visibility: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub metadata: Option<String>,
+    pub change_note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ServeOptions {
+    pub in_process: bool,
+    pub idle_timeout_secs: u64,
+}
+
+impl Default for ServeOptions {
+    fn default() -> Self {
+        Self {
+            in_process: false,
+            idle_timeout_secs: 600,
+        }
+    }
+}
+
+impl Command {
+    /// The app a command talks to Steam for, used to route it to that app's worker.
+    /// `publish-manifest` reads its app from the manifest, see `dispatch_with`.
+    pub fn app_id(&self) -> Option<u32> {
+        match self {
+            Command::CheckItemDownload { app_id, .. }
+            | Command::CollectionItems { app_id, .. }
+            | Command::WorkshopItems { app_id, .. }
+            | Command::Subscribe { app_id, .. }
+            | Command::Unsubscribe { app_id, .. }
+            | Command::DownloadWorkshopItem { app_id, .. }
+            | Command::SubscribedItems { app_id, .. }
+            | Command::CompatReport { app_id, .. }
+            | Command::SearchWorkshop { app_id, .. }
+            | Command::UserItems { app_id, .. }
+            | Command::DiscoverTags { app_id }
+            | Command::Publish { app_id, .. }
+            | Command::UpdateItem { app_id, .. }
+            | Command::DeleteItem { app_id, .. }
+            | Command::SetVisibility { app_id, .. }
+            | Command::AddDependency { app_id, .. }
+            | Command::RemoveDependency { app_id, .. }
+            | Command::ResolveDependencies { app_id, .. }
+            | Command::Vote { app_id, .. }
+            | Command::GetVote { app_id, .. }
+            | Command::Favorite { app_id, .. }
+            | Command::CollectionCreate { app_id, .. }
+            | Command::CollectionFromSubscriptions { app_id, .. }
+            | Command::CollectionAdd { app_id, .. }
+            | Command::CollectionRemove { app_id, .. } => Some(*app_id),
+            Command::WorkshopPath { .. }
+            | Command::SteamLibraryPaths { .. }
+            | Command::SteamInstallPaths
+            | Command::ClearCache
+            | Command::PublishManifest { .. }
+            | Command::Serve(_)
+            | Command::HttpServe { .. } => None,
+        }
+    }
+}
+
+fn default_sort_by() -> String {
+    "relevance".to_string()
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_visibility() -> String {
+    "public".to_string()
+}
+
+const JSON_ERRORS_FLAG: &str = "--json-errors";
+
+/// `--json-errors` is global and may appear anywhere on the command line.
+pub fn json_errors_requested() -> bool {
+    std::env::args_os()
+        .skip(1)
+        .any(|arg| arg == JSON_ERRORS_FLAG)
 }
 
 pub fn parse_args() -> Result<Command, lexopt::Error> {
     use lexopt::prelude::*;
 
-    let mut parser = lexopt::Parser::from_env();
-    
+    let mut parser = lexopt::Parser::from_args(
+        std::env::args_os()
+            .skip(1)
+            .filter(|arg| arg != JSON_ERRORS_FLAG),
+    );
+
     let command = match parser.next()? {
         Some(Value(cmd)) => cmd.to_string_lossy().to_string(),
+        Some(Long("help")) | Some(Short('h')) => {
+            print_main_help();
+            std::process::exit(0);
+        }
+        Some(Long("version")) | Some(Short('V')) => {
+            println!("s7forge {}", env!("CARGO_PKG_VERSION"));
+            std::process::exit(0);
+        }
         _ => return Err("Missing command".into()),
     };
 
@@ -35,7 +336,7 @@ pub fn parse_args() -> Result<Command, lexopt::Error> {
         "check-item-download" => {
             let mut app_id = None;
             let mut item_id = None;
-            
+
             while let Some(arg) = parser.next()? {
                 match arg {
                     Long("app-id") => app_id = Some(parser.value()?.parse()?),
@@ -47,17 +348,17 @@ pub fn parse_args() -> Result<Command, lexopt::Error> {
                     _ => return Err(arg.unexpected()),
                 }
             }
-            
+
             Ok(Command::CheckItemDownload {
                 app_id: app_id.ok_or("Missing --app-id")?,
                 item_id: item_id.ok_or("Missing --item-id")?,
             })
         }
-        
+
         "collection-items" => {
             let mut app_id = None;
             let mut item_id = None;
-            
+
             while let Some(arg) = parser.next()? {
                 match arg {
                     Long("app-id") => app_id = Some(parser.value()?.parse()?),
@@ -69,7 +370,7 @@ pub fn parse_args() -> Result<Command, lexopt::Error> {
                     _ => return Err(arg.unexpected()),
                 }
             }
-            
+
             Ok(Command::CollectionItems {
                 app_id: app_id.ok_or("Missing --app-id")?,
                 item_id: item_id.ok_or("Missing --item-id")?,
@@ -83,7 +384,19 @@ pub fn parse_args() -> Result<Command, lexopt::Error> {
             let mut period = None;
             let mut page = 1;
             let mut tags = None;
-            
+            let mut exclude_tags = None;
+            let mut match_any_tags = false;
+            let mut tag_groups = Vec::new();
+            let mut created_since = None;
+            let mut created_until = None;
+            let mut updated_since = None;
+            let mut updated_until = None;
+            let mut item_type = None;
+            let mut game_version = None;
+            let mut page_info = false;
+            let mut all_pages = false;
+            let mut max_pages = None;
+
             while let Some(arg) = parser.next()? {
                 match arg {
                     Long("app-id") => app_id = Some(parser.value()?.parse()?),
@@ -92,218 +405,877 @@ pub fn parse_args() -> Result<Command, lexopt::Error> {
                     Long("period") => period = Some(parser.value()?.to_string_lossy().to_string()),
                     Long("page") => page = parser.value()?.parse()?,
                     Long("tags") => tags = Some(parser.value()?.to_string_lossy().to_string()),
+                    Long("exclude-tags") => {
+                        exclude_tags = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("match-any-tags") => match_any_tags = true,
+                    Long("tag-group") => {
+                        tag_groups.push(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("created-since") => {
+                        created_since = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("created-until") => {
+                        created_until = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("updated-since") => {
+                        updated_since = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("updated-until") => {
+                        updated_until = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("type") => item_type = Some(parser.value()?.to_string_lossy().to_string()),
+                    Long("game-version") => {
+                        game_version = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("page-info") => page_info = true,
+                    Long("all-pages") => all_pages = true,
+                    Long("max-pages") => max_pages = Some(parser.value()?.parse()?),
+                    Long("help") | Short('h') => {
+                        print_search_workshop_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+
+            Ok(Command::SearchWorkshop {
+                app_id: app_id.ok_or("Missing --app-id")?,
+                query,
+                sort_by,
+                period,
+                page,
+                tags,
+                exclude_tags,
+                match_any_tags,
+                tag_groups,
+                created_since,
+                created_until,
+                updated_since,
+                updated_until,
+                item_type,
+                game_version,
+                page_info,
+                all_pages,
+                max_pages,
+            })
+        }
+
+        "user-items" => {
+            let mut app_id = None;
+            let mut list = None;
+            let mut order = None;
+            let mut page = 1;
+            let mut user = None;
+
+            while let Some(arg) = parser.next()? {
+                match arg {
+                    Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("list") => list = Some(parser.value()?.to_string_lossy().to_string()),
+                    Long("order") => order = Some(parser.value()?.to_string_lossy().to_string()),
+                    Long("page") => page = parser.value()?.parse()?,
+                    Long("user") => user = Some(parser.value()?.to_string_lossy().to_string()),
+                    Long("help") | Short('h') => {
+                        print_user_items_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+
+            Ok(Command::UserItems {
+                app_id: app_id.ok_or("Missing --app-id")?,
+                list: list.ok_or("Missing --list")?,
+                order,
+                page,
+                user,
+            })
+        }
+
+        "clear-cache" => {
+            while let Some(arg) = parser.next()? {
+                match arg {
+                    Long("help") | Short('h') => {
+                        print_clear_cache_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+            Ok(Command::ClearCache)
+        }
+
+        "steam-library-paths" => {
+            let mut detailed = false;
+
+            while let Some(arg) = parser.next()? {
+                match arg {
+                    Long("detailed") => detailed = true,
+                    Long("help") | Short('h') => {
+                        print_steam_library_paths_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+            Ok(Command::SteamLibraryPaths { detailed })
+        }
+
+        "steam-install-paths" => {
+            if let Some(arg) = parser.next()? {
+                match arg {
+                    Long("help") | Short('h') => {
+                        print_steam_install_paths_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+            Ok(Command::SteamInstallPaths)
+        }
+
+        "workshop-items" => {
+            let mut app_id = None;
+            let mut item_ids = Vec::new();
+            let mut with_user_state = false;
+            let mut item_type = None;
+
+            while let Some(arg) = parser.next()? {
+                match arg {
+                    Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("item-ids") => {
+                        let ids_value = parser.value()?;
+                        let ids_str = ids_value.to_string_lossy();
+                        item_ids = ids_str
+                            .split(',')
+                            .map(|s| {
+                                s.trim()
+                                    .parse()
+                                    .map_err(|_| format!("Invalid item ID: {}", s))
+                            })
+                            .collect::<Result<Vec<u64>, String>>()?;
+                    }
+                    Long("with-user-state") => with_user_state = true,
+                    Long("type") => item_type = Some(parser.value()?.to_string_lossy().to_string()),
+                    Long("help") | Short('h') => {
+                        print_workshop_items_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+
+            Ok(Command::WorkshopItems {
+                app_id: app_id.ok_or("Missing --app-id")?,
+                item_ids,
+                with_user_state,
+                item_type,
+            })
+        }
+
+        "subscribe" => {
+            let mut app_id = None;
+            let mut item_ids = Vec::new();
+
+            while let Some(arg) = parser.next()? {
+                match arg {
+                    Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("item-ids") => {
+                        let ids_value = parser.value()?;
+                        let ids_str = ids_value.to_string_lossy();
+                        item_ids = ids_str
+                            .split(',')
+                            .map(|s| {
+                                s.trim()
+                                    .parse()
+                                    .map_err(|_| format!("Invalid item ID: {}", s))
+                            })
+                            .collect::<Result<Vec<u64>, String>>()?;
+                    }
+                    Long("help") | Short('h') => {
+                        print_subscribe_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+
+            Ok(Command::Subscribe {
+                app_id: app_id.ok_or("Missing --app-id")?,
+                item_ids,
+            })
+        }
+
+        "unsubscribe" => {
+            let mut app_id = None;
+            let mut item_ids = Vec::new();
+
+            while let Some(arg) = parser.next()? {
+                match arg {
+                    Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("item-ids") => {
+                        let ids_value = parser.value()?;
+                        let ids_str = ids_value.to_string_lossy();
+                        item_ids = ids_str
+                            .split(',')
+                            .map(|s| {
+                                s.trim()
+                                    .parse()
+                                    .map_err(|_| format!("Invalid item ID: {}", s))
+                            })
+                            .collect::<Result<Vec<u64>, String>>()?;
+                    }
+                    Long("help") | Short('h') => {
+                        print_unsubscribe_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+
+            Ok(Command::Unsubscribe {
+                app_id: app_id.ok_or("Missing --app-id")?,
+                item_ids,
+            })
+        }
+
+        "download-workshop-item" => {
+            let mut app_id = None;
+            let mut item_id = None;
+
+            while let Some(arg) = parser.next()? {
+                match arg {
+                    Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("item-id") => item_id = Some(parser.value()?.parse()?),
+                    Long("help") | Short('h') => {
+                        print_download_workshop_item_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+
+            Ok(Command::DownloadWorkshopItem {
+                app_id: app_id.ok_or("Missing --app-id")?,
+                item_id: item_id.ok_or("Missing --item-id")?,
+            })
+        }
+
+        "subscribed-items" => {
+            let mut app_id = None;
+            let mut game_version = None;
+
+            while let Some(arg) = parser.next()? {
+                match arg {
+                    Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("game-version") => {
+                        game_version = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("help") | Short('h') => {
+                        print_subscribed_items_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+
+            Ok(Command::SubscribedItems {
+                app_id: app_id.ok_or("Missing --app-id")?,
+                game_version,
+            })
+        }
+
+        "compat-report" => {
+            let mut app_id = None;
+            let mut game_version = None;
+
+            while let Some(arg) = parser.next()? {
+                match arg {
+                    Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("game-version") => {
+                        game_version = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("help") | Short('h') => {
+                        print_compat_report_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+
+            Ok(Command::CompatReport {
+                app_id: app_id.ok_or("Missing --app-id")?,
+                game_version: game_version.ok_or("Missing --game-version")?,
+            })
+        }
+
+        "workshop-path" => {
+            let mut app_id = None;
+
+            while let Some(arg) = parser.next()? {
+                match arg {
+                    Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("help") | Short('h') => {
+                        print_workshop_path_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+
+            Ok(Command::WorkshopPath {
+                app_id: app_id.ok_or("Missing --app-id")?,
+            })
+        }
+
+        "discover-tags" => {
+            let mut app_id = None;
+
+            while let Some(arg) = parser.next()? {
+                match arg {
+                    Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("help") | Short('h') => {
+                        print_discover_tags_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+
+            Ok(Command::DiscoverTags {
+                app_id: app_id.ok_or("Missing --app-id")?,
+            })
+        }
+        "publish" => {
+            let mut app_id = None;
+            let mut title = None;
+            let mut description = None;
+            let mut content_folder = None;
+            let mut preview_image = None;
+            let mut visibility = None;
+            let mut tags = None;
+            let mut change_note = None;
+            let mut manifest = None;
+            let mut version = None;
+
+            while let Some(arg) = parser.next()? {
+                match arg {
+                    Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("title") => title = Some(parser.value()?.to_string_lossy().to_string()),
+                    Long("description") => {
+                        description = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("content-folder") => {
+                        content_folder = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("preview-image") => {
+                        preview_image = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("visibility") => {
+                        visibility = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("tags") => {
+                        tags = Some(
+                            parser
+                                .value()?
+                                .to_string_lossy()
+                                .split(',')
+                                .map(|tag| tag.trim().to_string())
+                                .filter(|tag| !tag.is_empty())
+                                .collect(),
+                        );
+                    }
+                    Long("change-note") => {
+                        change_note = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("manifest") => {
+                        manifest = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("version") => {
+                        version = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("help") | Short('h') => {
+                        print_publish_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+
+            if let Some(manifest) = manifest {
+                if app_id.is_some()
+                    || title.is_some()
+                    || description.is_some()
+                    || content_folder.is_some()
+                    || preview_image.is_some()
+                    || visibility.is_some()
+                    || tags.is_some()
+                {
+                    return Err(
+                        "--manifest cannot be combined with item options, set them in the manifest"
+                            .into(),
+                    );
+                }
+                return Ok(Command::PublishManifest {
+                    manifest,
+                    version,
+                    change_note,
+                });
+            }
+            if version.is_some() {
+                return Err("--version requires --manifest".into());
+            }
+
+            Ok(Command::Publish {
+                app_id: app_id.ok_or("Missing --app-id")?,
+                title: title.ok_or("Missing --title")?,
+                description: description.unwrap_or_default(),
+                content_folder: content_folder.ok_or("Missing --content-folder")?,
+                preview_image: preview_image.ok_or("Missing --preview-image")?,
+                visibility: visibility.unwrap_or_else(default_visibility),
+                tags: tags.unwrap_or_default(),
+                change_note,
+            })
+        }
+        "update-item" => {
+            let mut app_id = None;
+            let mut item_id = None;
+            let mut changes = ItemChanges::default();
+
+            while let Some(arg) = parser.next()? {
+                match arg {
+                    Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("item-id") => item_id = Some(parser.value()?.parse()?),
+                    Long("title") => {
+                        changes.title = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("description") => {
+                        changes.description = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("content-folder") => {
+                        changes.content_folder = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("preview-image") => {
+                        changes.preview_image = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("visibility") => {
+                        changes.visibility = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("tags") => {
+                        changes.tags = Some(
+                            parser
+                                .value()?
+                                .to_string_lossy()
+                                .split(',')
+                                .map(|tag| tag.trim().to_string())
+                                .filter(|tag| !tag.is_empty())
+                                .collect(),
+                        );
+                    }
+                    Long("metadata") => {
+                        changes.metadata = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("change-note") => {
+                        changes.change_note = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("help") | Short('h') => {
+                        print_update_item_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+
+            Ok(Command::UpdateItem {
+                app_id: app_id.ok_or("Missing --app-id")?,
+                item_id: item_id.ok_or("Missing --item-id")?,
+                changes,
+            })
+        }
+        "delete-item" => {
+            let mut app_id = None;
+            let mut item_id = None;
+            let mut yes = false;
+
+            while let Some(arg) = parser.next()? {
+                match arg {
+                    Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("item-id") => item_id = Some(parser.value()?.parse()?),
+                    Long("yes") => yes = true,
                     Long("help") | Short('h') => {
-                        print_search_workshop_help();
+                        print_delete_item_help();
                         std::process::exit(0);
                     }
                     _ => return Err(arg.unexpected()),
                 }
             }
-            
-            Ok(Command::SearchWorkshop {
+
+            Ok(Command::DeleteItem {
                 app_id: app_id.ok_or("Missing --app-id")?,
-                query,
-                sort_by,
-                period,
-                page,
-                tags,
+                item_id: item_id.ok_or("Missing --item-id")?,
+                yes,
             })
         }
+        "set-visibility" => {
+            let mut app_id = None;
+            let mut item_id = None;
+            let mut visibility = None;
 
-        "clear-cache" => {
             while let Some(arg) = parser.next()? {
                 match arg {
+                    Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("item-id") => item_id = Some(parser.value()?.parse()?),
+                    Long("visibility") => {
+                        visibility = Some(parser.value()?.to_string_lossy().to_string())
+                    }
                     Long("help") | Short('h') => {
-                        print_clear_cache_help();
+                        print_set_visibility_help();
                         std::process::exit(0);
                     }
                     _ => return Err(arg.unexpected()),
                 }
             }
-            Ok(Command::ClearCache)
+
+            Ok(Command::SetVisibility {
+                app_id: app_id.ok_or("Missing --app-id")?,
+                item_id: item_id.ok_or("Missing --item-id")?,
+                visibility: visibility.ok_or("Missing --visibility")?,
+            })
         }
+        "add-dependency" | "remove-dependency" => {
+            let mut app_id = None;
+            let mut item_id = None;
+            let mut dependency_ids = Vec::new();
+            let mut app_dependency_ids = Vec::new();
 
-        "steam-library-paths" => {
             while let Some(arg) = parser.next()? {
                 match arg {
+                    Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("item-id") => item_id = Some(parser.value()?.parse()?),
+                    Long("dependency-ids") => {
+                        let ids_value = parser.value()?;
+                        let ids_str = ids_value.to_string_lossy();
+                        dependency_ids = ids_str
+                            .split(',')
+                            .map(|s| {
+                                s.trim()
+                                    .parse()
+                                    .map_err(|_| format!("Invalid item ID: {}", s))
+                            })
+                            .collect::<Result<Vec<u64>, String>>()?;
+                    }
+                    Long("app-dependency-ids") => {
+                        let ids_value = parser.value()?;
+                        let ids_str = ids_value.to_string_lossy();
+                        app_dependency_ids = ids_str
+                            .split(',')
+                            .map(|s| {
+                                s.trim()
+                                    .parse()
+                                    .map_err(|_| format!("Invalid app ID: {}", s))
+                            })
+                            .collect::<Result<Vec<u32>, String>>()?;
+                    }
                     Long("help") | Short('h') => {
-                        print_steam_library_paths_help();
+                        print_dependency_help(&command);
                         std::process::exit(0);
                     }
                     _ => return Err(arg.unexpected()),
                 }
             }
-            Ok(Command::SteamLibraryPaths)
-        }
 
-        "workshop-items" => {
+            let app_id = app_id.ok_or("Missing --app-id")?;
+            let item_id = item_id.ok_or("Missing --item-id")?;
+            if dependency_ids.is_empty() && app_dependency_ids.is_empty() {
+                return Err("Missing --dependency-ids or --app-dependency-ids".into());
+            }
+
+            if command == "add-dependency" {
+                Ok(Command::AddDependency {
+                    app_id,
+                    item_id,
+                    dependency_ids,
+                    app_dependency_ids,
+                })
+            } else {
+                Ok(Command::RemoveDependency {
+                    app_id,
+                    item_id,
+                    dependency_ids,
+                    app_dependency_ids,
+                })
+            }
+        }
+        "resolve-dependencies" => {
             let mut app_id = None;
             let mut item_ids = Vec::new();
-            
-            while let Some(arg) = parser.next()? {                match arg {
+            let mut dot = false;
+
+            while let Some(arg) = parser.next()? {
+                match arg {
                     Long("app-id") => app_id = Some(parser.value()?.parse()?),
                     Long("item-ids") => {
                         let ids_value = parser.value()?;
                         let ids_str = ids_value.to_string_lossy();
-                        item_ids = ids_str.split(',')
-                            .map(|s| s.trim().parse().map_err(|_| format!("Invalid item ID: {}", s)))
+                        item_ids = ids_str
+                            .split(',')
+                            .map(|s| {
+                                s.trim()
+                                    .parse()
+                                    .map_err(|_| format!("Invalid item ID: {}", s))
+                            })
                             .collect::<Result<Vec<u64>, String>>()?;
                     }
+                    Long("dot") => dot = true,
                     Long("help") | Short('h') => {
-                        print_workshop_items_help();
+                        print_resolve_dependencies_help();
                         std::process::exit(0);
                     }
                     _ => return Err(arg.unexpected()),
                 }
             }
-            
-            Ok(Command::WorkshopItems {
+
+            if item_ids.is_empty() {
+                return Err("Missing --item-ids".into());
+            }
+
+            Ok(Command::ResolveDependencies {
                 app_id: app_id.ok_or("Missing --app-id")?,
                 item_ids,
+                dot,
             })
         }
-
-        "subscribe" => {
+        "vote" => {
             let mut app_id = None;
-            let mut item_ids = Vec::new();
-            
-            while let Some(arg) = parser.next()? {                match arg {
+            let mut item_id = None;
+            let mut vote = None;
+
+            while let Some(arg) = parser.next()? {
+                match arg {
                     Long("app-id") => app_id = Some(parser.value()?.parse()?),
-                    Long("item-ids") => {
-                        let ids_value = parser.value()?;
-                        let ids_str = ids_value.to_string_lossy();
-                        item_ids = ids_str.split(',')
-                            .map(|s| s.trim().parse().map_err(|_| format!("Invalid item ID: {}", s)))
-                            .collect::<Result<Vec<u64>, String>>()?;
+                    Long("item-id") => item_id = Some(parser.value()?.parse()?),
+                    Long("up") | Long("down") if vote.is_some() => {
+                        return Err("Pass only one of --up and --down".into());
                     }
+                    Long("up") => vote = Some("up".to_string()),
+                    Long("down") => vote = Some("down".to_string()),
                     Long("help") | Short('h') => {
-                        print_subscribe_help();
+                        print_vote_help();
                         std::process::exit(0);
                     }
                     _ => return Err(arg.unexpected()),
                 }
             }
-            
-            Ok(Command::Subscribe {
+
+            Ok(Command::Vote {
                 app_id: app_id.ok_or("Missing --app-id")?,
-                item_ids,
+                item_id: item_id.ok_or("Missing --item-id")?,
+                vote: vote.ok_or("Missing --up or --down")?,
             })
         }
-
-        "unsubscribe" => {
+        "get-vote" => {
             let mut app_id = None;
-            let mut item_ids = Vec::new();
-            
-            while let Some(arg) = parser.next()? {                match arg {
+            let mut item_id = None;
+
+            while let Some(arg) = parser.next()? {
+                match arg {
                     Long("app-id") => app_id = Some(parser.value()?.parse()?),
-                    Long("item-ids") => {
-                        let ids_value = parser.value()?;
-                        let ids_str = ids_value.to_string_lossy();
-                        item_ids = ids_str.split(',')
-                            .map(|s| s.trim().parse().map_err(|_| format!("Invalid item ID: {}", s)))
-                            .collect::<Result<Vec<u64>, String>>()?;
-                    }
+                    Long("item-id") => item_id = Some(parser.value()?.parse()?),
                     Long("help") | Short('h') => {
-                        print_unsubscribe_help();
+                        print_get_vote_help();
                         std::process::exit(0);
                     }
                     _ => return Err(arg.unexpected()),
                 }
             }
-            
-            Ok(Command::Unsubscribe {
+
+            Ok(Command::GetVote {
                 app_id: app_id.ok_or("Missing --app-id")?,
-                item_ids,
+                item_id: item_id.ok_or("Missing --item-id")?,
             })
         }
-
-        "download-workshop-item" => {
+        "favorite" => {
+            let mut action = None;
             let mut app_id = None;
             let mut item_id = None;
-            
+
             while let Some(arg) = parser.next()? {
                 match arg {
+                    Value(value) if action.is_none() => action = Some(value.string()?),
                     Long("app-id") => app_id = Some(parser.value()?.parse()?),
                     Long("item-id") => item_id = Some(parser.value()?.parse()?),
                     Long("help") | Short('h') => {
-                        print_download_workshop_item_help();
+                        print_favorite_help();
                         std::process::exit(0);
                     }
                     _ => return Err(arg.unexpected()),
                 }
             }
-            
-            Ok(Command::DownloadWorkshopItem {
+
+            Ok(Command::Favorite {
                 app_id: app_id.ok_or("Missing --app-id")?,
                 item_id: item_id.ok_or("Missing --item-id")?,
+                action: action.ok_or("Missing action: add or remove")?,
             })
         }
-
-        "subscribed-items" => {
+        "collection-create" | "collection-from-subscriptions" => {
             let mut app_id = None;
-            
+            let mut title = None;
+            let mut description = String::new();
+            let mut visibility = default_visibility();
+            let mut preview_image = None;
+            let mut item_ids = Vec::new();
+            let from_subscriptions = command == "collection-from-subscriptions";
+
             while let Some(arg) = parser.next()? {
                 match arg {
                     Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("title") => title = Some(parser.value()?.to_string_lossy().to_string()),
+                    Long("description") => {
+                        description = parser.value()?.to_string_lossy().to_string()
+                    }
+                    Long("visibility") => {
+                        visibility = parser.value()?.to_string_lossy().to_string()
+                    }
+                    Long("preview-image") => {
+                        preview_image = Some(parser.value()?.to_string_lossy().to_string())
+                    }
+                    Long("item-ids") if !from_subscriptions => {
+                        item_ids = parse_item_ids(&parser.value()?.to_string_lossy())?
+                    }
                     Long("help") | Short('h') => {
-                        print_subscribed_items_help();
+                        print_collection_create_help(&command);
                         std::process::exit(0);
                     }
                     _ => return Err(arg.unexpected()),
                 }
             }
-            
-            Ok(Command::SubscribedItems {
-                app_id: app_id.ok_or("Missing --app-id")?,
-            })
-        }
 
-        "workshop-path" => {
+            let app_id = app_id.ok_or("Missing --app-id")?;
+            let title = title.ok_or("Missing --title")?;
+            if from_subscriptions {
+                Ok(Command::CollectionFromSubscriptions {
+                    app_id,
+                    title,
+                    description,
+                    visibility,
+                    preview_image,
+                })
+            } else {
+                Ok(Command::CollectionCreate {
+                    app_id,
+                    title,
+                    description,
+                    visibility,
+                    preview_image,
+                    item_ids,
+                })
+            }
+        }
+        "collection-add" | "collection-remove" => {
             let mut app_id = None;
-            
+            let mut collection_id = None;
+            let mut item_ids = Vec::new();
+            let mut position = None;
+            let adding = command == "collection-add";
+
             while let Some(arg) = parser.next()? {
                 match arg {
                     Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("collection-id") => collection_id = Some(parser.value()?.parse()?),
+                    Long("item-ids") => {
+                        item_ids = parse_item_ids(&parser.value()?.to_string_lossy())?
+                    }
+                    Long("position") if adding => position = Some(parser.value()?.parse()?),
                     Long("help") | Short('h') => {
-                        print_workshop_path_help();
+                        print_collection_edit_help(&command);
                         std::process::exit(0);
                     }
                     _ => return Err(arg.unexpected()),
                 }
             }
-            
-            Ok(Command::WorkshopPath {
-                app_id: app_id.ok_or("Missing --app-id")?,
-            })
+
+            let app_id = app_id.ok_or("Missing --app-id")?;
+            let collection_id = collection_id.ok_or("Missing --collection-id")?;
+            if item_ids.is_empty() {
+                return Err("Missing --item-ids".into());
+            }
+
+            if adding {
+                Ok(Command::CollectionAdd {
+                    app_id,
+                    collection_id,
+                    item_ids,
+                    position,
+                })
+            } else {
+                Ok(Command::CollectionRemove {
+                    app_id,
+                    collection_id,
+                    item_ids,
+                })
+            }
         }
+        "serve" => {
+            let mut options = ServeOptions::default();
 
-        "discover-tags" => {
-            let mut app_id = None;
-            
             while let Some(arg) = parser.next()? {
                 match arg {
-                    Long("app-id") => app_id = Some(parser.value()?.parse()?),
+                    Long("in-process") => options.in_process = true,
+                    Long("idle-timeout") => options.idle_timeout_secs = parser.value()?.parse()?,
                     Long("help") | Short('h') => {
-                        print_discover_tags_help();
+                        print_serve_help();
                         std::process::exit(0);
                     }
                     _ => return Err(arg.unexpected()),
                 }
             }
-            
-            Ok(Command::DiscoverTags {
-                app_id: app_id.ok_or("Missing --app-id")?,
+            Ok(Command::Serve(options))
+        }
+        "http-serve" => {
+            let mut port = None;
+            let mut allowed_origins = Vec::new();
+            let mut options = ServeOptions::default();
+
+            while let Some(arg) = parser.next()? {
+                match arg {
+                    Long("port") => port = Some(parser.value()?.parse()?),
+                    Long("allow-origin") => allowed_origins.push(parser.value()?.string()?),
+                    Long("in-process") => options.in_process = true,
+                    Long("idle-timeout") => options.idle_timeout_secs = parser.value()?.parse()?,
+                    Long("help") | Short('h') => {
+                        print_http_serve_help();
+                        std::process::exit(0);
+                    }
+                    _ => return Err(arg.unexpected()),
+                }
+            }
+
+            Ok(Command::HttpServe {
+                port: port.ok_or("Missing --port")?,
+                allowed_origins,
+                options,
             })
         }
         "help" | "--help" | "-h" => {
             print_main_help();
             std::process::exit(0);
         }
-        
+
         _ => Err(format!("Unknown command: {}", command).into()),
     }
 }
 
+fn parse_item_ids(ids: &str) -> Result<Vec<u64>, String> {
+    ids.split(',')
+        .map(|s| {
+            s.trim()
+                .parse()
+                .map_err(|_| format!("Invalid item ID: {}", s))
+        })
+        .collect()
+}
+
 fn print_main_help() {
     println!("s7forge - Steam utility\n");
     println!("USAGE:");
@@ -316,12 +1288,50 @@ fn print_main_help() {
     println!("    unsubscribe             Unsubscribe from workshop items");
     println!("    download-workshop-item  Download a workshop item you own");
     println!("    subscribed-items        List all items you're subscribed to for a game");
+    println!("    compat-report           List subscribed items that don't declare a game version");
     println!("    search-workshop         Search workshop content by text query");
+    println!(
+        "    user-items              List a user's published, favorited, voted or subscribed items"
+    );
     println!("    workshop-path           Get the local workshop path for a game");
     println!("    steam-library-paths     List all Steam library folder paths");
+    println!(
+        "    steam-install-paths     List detected Steam installations and where they were found"
+    );
     println!("    clear-cache             Clear all cached data");
     println!("    discover-tags           Discover all available workshop tags for a game");
+    println!("    publish                 Create a new workshop item and upload its content");
+    println!("    update-item             Change the fields or content of a workshop item you own");
+    println!("    delete-item             Permanently delete a workshop item you own");
+    println!("    set-visibility          Change who can see a workshop item you own");
+    println!("    add-dependency          Make items required by a workshop item you own");
+    println!("    remove-dependency       Stop requiring items from a workshop item you own");
+    println!("    resolve-dependencies    List every item workshop items require, in load order");
+    println!("    vote                    Vote a workshop item up or down");
+    println!("    get-vote                Show your vote on a workshop item");
+    println!("    favorite                Add a workshop item to your favorites or remove it");
+    println!("    collection-create       Create a collection of workshop items");
+    println!(
+        "    collection-from-subscriptions  Create a collection of the items you're subscribed to"
+    );
+    println!("    collection-add          Add or move items in a collection you own");
+    println!("    collection-remove       Remove items from a collection you own");
+    println!(
+        "    serve                   Keep the Steam client alive and answer JSON-RPC requests on stdin"
+    );
+    println!("    http-serve              Expose the workshop commands as a REST API on localhost");
     println!("    help                    Print this message\n");
+    println!("OPTIONS:");
+    println!(
+        "    --json-errors           Print errors as JSON: {{\"error\": {{\"code\", \"message\", \"details\"}}}}"
+    );
+    println!("    -h, --help              Print help");
+    println!("    -V, --version           Print version\n");
+    println!("EXIT CODES:");
+    println!("    0  success            1  internal_error       2  invalid_argument");
+    println!("    3  steam_not_running  4  not_found            5  not_subscribed");
+    println!("    6  timeout            7  steam_api_error      8  io_error");
+    println!("    9  not_owner\n");
     println!("For more information on a specific command, use: s7forge <COMMAND> --help");
 }
 
@@ -356,15 +1366,106 @@ fn print_search_workshop_help() {
     println!("OPTIONS:");
     println!("    --app-id <APP_ID>        Steam App ID of the game");
     println!("    --query <QUERY>          Text to search for (optional for most sort methods)");
-    println!("    --sort-by <SORT>         Sort by: relevance, recent, popular, most-subscribed, recently-updated [default: relevance]");
-    println!("    --period <PERIOD>        Time period filter: today, one-week, three-months, six-months, one-year (only for 'popular' sort)");
+    println!("    --sort-by <SORT>         Sort order, see SORT ORDERS below [default: relevance]");
+    println!(
+        "    --period <PERIOD>        Time period filter: today, one-week, three-months, six-months, one-year (only for trend sorts)"
+    );
     println!("    --page <PAGE>            Page number for pagination [default: 1]");
     println!("    --tags <TAGS>            Filter by tags, comma-separated (e.g., 'mod,weapon')");
+    println!("    --match-any-tags         Match items with any of --tags instead of all of them");
+    println!(
+        "    --exclude-tags <TAGS>    Leave out items with any of these tags, comma-separated"
+    );
+    println!(
+        "    --tag-group <TAGS>       Require at least one of these tags, comma-separated (repeatable)"
+    );
+    println!(
+        "    --created-since <DATE>   Only items created on or after DATE (YYYY-MM-DD in UTC or a Unix timestamp)"
+    );
+    println!("    --created-until <DATE>   Only items created on or before DATE");
+    println!("    --updated-since <DATE>   Only items updated on or after DATE");
+    println!("    --updated-until <DATE>   Only items updated on or before DATE");
+    println!(
+        "    --type <TYPE>            items, collections, artwork, videos, screenshots, guides or all [default: items]"
+    );
+    println!(
+        "    --game-version <VER>     Only items with a version tag covering VER (e.g., 1.2 matches v1.2 and v1.x)"
+    );
+    println!(
+        "                             A plain tag is exact: v1.2 covers 1.2 and 1.2.0 but not 1.2.3, v1.2.x does"
+    );
+    println!(
+        "    --page-info              Print the items with paging details and filtered items instead of just the items"
+    );
+    println!(
+        "    --max-pages <N>          Fetch up to N pages starting at --page and merge their items"
+    );
+    println!(
+        "    --all-pages              Fetch every page starting at --page, up to 100 pages, and merge their items"
+    );
     println!("    -h, --help               Print help\n");
+    println!("With --page-info, page is the last page included, so the next one is page + 1.");
+    println!("--type and --game-version are applied after fetching each page, so they");
+    println!(
+        "need --page-info. total_results and returned_results count items before these filters and"
+    );
+    println!(
+        "matching_results after them, and filtered lists each item left out with its reason.\n"
+    );
+    println!("SORT ORDERS:");
+    println!("    relevance, recent, popular, most-subscribed, recently-updated, top-rated,");
+    println!(
+        "    most-votes-up, fewest-votes, not-yet-rated, accepted-for-use, favorited-by-friends,"
+    );
+    println!("    created-by-friends, created-by-followed, most-reported, playtime-trend,");
+    println!("    total-playtime, average-playtime-trend, lifetime-average-playtime,");
+    println!("    playtime-sessions-trend, lifetime-playtime-sessions");
+    println!(
+        "    Trend sorts: popular, playtime-trend, average-playtime-trend, playtime-sessions-trend\n"
+    );
     println!("EXAMPLES:");
     println!("    s7forge search-workshop --app-id 548430 --query \"tank\" --sort-by relevance");
     println!("    s7forge search-workshop --app-id 548430 --sort-by recent --tags \"mod,weapon\"");
     println!("    s7forge search-workshop --app-id 548430 --sort-by popular --period one-week");
+    println!(
+        "    s7forge search-workshop --app-id 548430 --tag-group \"Units,Maps\" --exclude-tags UI"
+    );
+    println!(
+        "    s7forge search-workshop --app-id 548430 --sort-by top-rated --created-since 2024-01-01"
+    );
+    println!(
+        "    s7forge search-workshop --app-id 548430 --sort-by recent --game-version 1.2 --page-info"
+    );
+    println!(
+        "    s7forge search-workshop --app-id 548430 --sort-by recent --max-pages 3 --page-info"
+    );
+}
+
+fn print_user_items_help() {
+    println!("List a user's published, favorited, voted, followed or subscribed items\n");
+    println!("USAGE:");
+    println!("    s7forge user-items --app-id <APP_ID> --list <LIST> [OPTIONS]\n");
+    println!("OPTIONS:");
+    println!("    --app-id <APP_ID>        Steam App ID of the game");
+    println!(
+        "    --list <LIST>            published, favorited, voted-on, voted-up, voted-down, followed or subscribed"
+    );
+    println!(
+        "    --order <ORDER>          newest, oldest, title, recently-updated, recently-subscribed, vote-score or for-moderation"
+    );
+    println!(
+        "                             [default: recently-subscribed for subscribed, newest otherwise]"
+    );
+    println!("    --page <PAGE>            Page number for pagination [default: 1]");
+    println!(
+        "    --user <USER>            SteamID64, SteamID (STEAM_0:1:123) or account ID [default: logged-in user]"
+    );
+    println!("    -h, --help               Print help\n");
+    println!("Steam only shows another user's lists when their profile allows it.\n");
+    println!("EXAMPLES:");
+    println!("    s7forge user-items --app-id 548430 --list published");
+    println!("    s7forge user-items --app-id 548430 --list favorited --order title --page 2");
+    println!("    s7forge user-items --app-id 548430 --list published --user 76561197960287930");
 }
 
 fn print_clear_cache_help() {
@@ -380,11 +1481,29 @@ fn print_clear_cache_help() {
 fn print_steam_library_paths_help() {
     println!("List all Steam library folder paths\n");
     println!("USAGE:");
-    println!("    s7forge steam-library-paths\n");
+    println!("    s7forge steam-library-paths [OPTIONS]\n");
     println!("OPTIONS:");
+    println!(
+        "    --detailed    Include label, content ID, total size and installed apps per library"
+    );
     println!("    -h, --help    Print help\n");
-    println!("EXAMPLE:");
+    println!("EXAMPLES:");
     println!("    s7forge steam-library-paths");
+    println!("    s7forge steam-library-paths --detailed");
+}
+
+fn print_steam_install_paths_help() {
+    println!("List detected Steam installations and where they were found\n");
+    println!("USAGE:");
+    println!("    s7forge steam-install-paths\n");
+    println!("OPTIONS:");
+    println!("    -h, --help    Print help\n");
+    println!("ENVIRONMENT:");
+    println!(
+        "    STEAM_ROOT    Steam installation directory to check before the platform defaults\n"
+    );
+    println!("EXAMPLE:");
+    println!("    s7forge steam-install-paths");
 }
 
 fn print_workshop_items_help() {
@@ -394,9 +1513,20 @@ fn print_workshop_items_help() {
     println!("OPTIONS:");
     println!("    --app-id <APP_ID>          Steam App ID of the game");
     println!("    --item-ids <ITEM_IDS>      Workshop item IDs (comma-separated)");
+    println!(
+        "    --with-user-state          Add your vote and favorite as user_state to each item"
+    );
+    println!(
+        "    --type <TYPE>              items, collections, artwork, videos, screenshots, guides or all"
+    );
     println!("    -h, --help                 Print help\n");
+    println!("Without --type items of every type are returned. With it the output is");
+    println!("{{items, filtered, not_found}}, where filtered lists found items of other");
+    println!("types and not_found the IDs Steam returned nothing for.\n");
     println!("EXAMPLE:");
     println!("    s7forge workshop-items --app-id 548430 --item-ids 123,456,789");
+    println!("    s7forge workshop-items --app-id 548430 --item-ids 123 --with-user-state");
+    println!("    s7forge workshop-items --app-id 548430 --item-ids 123,456 --type all");
 }
 
 fn print_subscribe_help() {
@@ -417,7 +1547,9 @@ fn print_unsubscribe_help() {
     println!("    s7forge unsubscribe --app-id <APP_ID> --item-ids <ITEM_IDS>\n");
     println!("OPTIONS:");
     println!("    --app-id <APP_ID>          Steam App ID of the game");
-    println!("    --item-ids <ITEM_IDS>      Workshop item IDs to unsubscribe from (comma-separated)");
+    println!(
+        "    --item-ids <ITEM_IDS>      Workshop item IDs to unsubscribe from (comma-separated)"
+    );
     println!("    -h, --help                 Print help\n");
     println!("EXAMPLE:");
     println!("    s7forge unsubscribe --app-id 548430 --item-ids 123,456,789");
@@ -438,12 +1570,38 @@ fn print_download_workshop_item_help() {
 fn print_subscribed_items_help() {
     println!("List all items you're subscribed to for a game\n");
     println!("USAGE:");
-    println!("    s7forge subscribed-items --app-id <APP_ID>\n");
+    println!("    s7forge subscribed-items --app-id <APP_ID> [OPTIONS]\n");
     println!("OPTIONS:");
-    println!("    --app-id <APP_ID>      Steam App ID of the game");
-    println!("    -h, --help             Print help\n");
-    println!("EXAMPLE:");
+    println!("    --app-id <APP_ID>          Steam App ID of the game");
+    println!(
+        "    --game-version <VERSION>   Only items with a version tag covering VERSION (e.g., 1.2)"
+    );
+    println!(
+        "                               A plain tag is exact: v1.2 covers 1.2 and 1.2.0 but not 1.2.3, v1.2.x does"
+    );
+    println!("    -h, --help                 Print help\n");
+    println!("EXAMPLES:");
     println!("    s7forge subscribed-items --app-id 548430");
+    println!("    s7forge subscribed-items --app-id 548430 --game-version 1.2");
+}
+
+fn print_compat_report_help() {
+    println!("List subscribed items that don't declare compatibility with a game version\n");
+    println!("USAGE:");
+    println!("    s7forge compat-report --app-id <APP_ID> --game-version <VERSION>\n");
+    println!("OPTIONS:");
+    println!("    --app-id <APP_ID>          Steam App ID of the game");
+    println!(
+        "    --game-version <VERSION>   The game version to check against (e.g., 1.2 or 1.2.3)"
+    );
+    println!("    -h, --help                 Print help\n");
+    println!(
+        "Items declare versions with tags like v1.2, v1.2.x or e3.4. A v1.2.x tag covers every"
+    );
+    println!("1.2 release, while a plain v1.2 tag only covers 1.2 and 1.2.0, not 1.2.3. Items");
+    println!("without version tags are always reported.\n");
+    println!("EXAMPLE:");
+    println!("    s7forge compat-report --app-id 548430 --game-version 1.2");
 }
 
 fn print_workshop_path_help() {
@@ -467,3 +1625,348 @@ fn print_discover_tags_help() {
     println!("EXAMPLE:");
     println!("    s7forge discover-tags --app-id 548430");
 }
+
+fn print_publish_help() {
+    println!("Create a new workshop item and upload its content\n");
+    println!("USAGE:");
+    println!(
+        "    s7forge publish --app-id <APP_ID> --title <TITLE> --content-folder <DIR> --preview-image <FILE> [OPTIONS]"
+    );
+    println!(
+        "    s7forge publish --manifest <PATH> [--version <VERSION>] [--change-note <TEXT>]\n"
+    );
+    println!("OPTIONS:");
+    println!("    --app-id <APP_ID>            Steam App ID of the game");
+    println!("    --title <TITLE>              Item title (up to 128 bytes)");
+    println!("    --description <TEXT>         Item description");
+    println!(
+        "    --content-folder <DIR>       Folder whose files are uploaded as the item content"
+    );
+    println!("    --preview-image <FILE>       Preview image shown on the workshop page");
+    println!(
+        "    --visibility <VISIBILITY>    public, friends-only, private or unlisted [default: public]"
+    );
+    println!("    --tags <TAGS>                Tags to set (comma-separated)");
+    println!("    --change-note <TEXT>         Change note for this version");
+    println!(
+        "    --manifest <PATH>            Publish from a workshop.toml or workshop.json (or a folder with one)"
+    );
+    println!("    --version <VERSION>          Fills {{version}} in the manifest change note");
+    println!("    -h, --help                   Print help\n");
+    println!("Prints the new published_file_id. needs_legal_agreement is true when the item stays");
+    println!("hidden until you accept the Steam Workshop legal agreement.\n");
+    println!("With --manifest the first publish creates the item and writes its published_file_id");
+    println!("back to the manifest, later ones update that item. The manifest is fully validated");
+    println!("before anything is uploaded.\n");
+    println!("EXAMPLE:");
+    println!(
+        "    s7forge publish --app-id 548430 --title \"Better Tanks\" --content-folder ./build --preview-image ./preview.png --tags Units,Balance"
+    );
+    println!("    s7forge publish --manifest ./workshop.toml --version 1.2.0");
+}
+
+fn print_update_item_help() {
+    println!("Change the fields or content of a workshop item you own\n");
+    println!("USAGE:");
+    println!("    s7forge update-item --app-id <APP_ID> --item-id <ITEM_ID> [OPTIONS]\n");
+    println!("OPTIONS:");
+    println!("    --app-id <APP_ID>            Steam App ID of the game");
+    println!("    --item-id <ITEM_ID>          Workshop item ID to update");
+    println!("    --title <TITLE>              New title (up to 128 bytes)");
+    println!("    --description <TEXT>         New description");
+    println!("    --content-folder <DIR>       Folder to upload as the new item content");
+    println!("    --preview-image <FILE>       New preview image");
+    println!("    --visibility <VISIBILITY>    public, friends-only, private or unlisted");
+    println!(
+        "    --tags <TAGS>                Replace the tags (comma-separated, empty clears them)"
+    );
+    println!("    --metadata <TEXT>            Developer metadata (up to 4999 bytes)");
+    println!("    --change-note <TEXT>         Change note for this version");
+    println!("    -h, --help                   Print help\n");
+    println!("Only the given fields change. Upload progress is printed as one JSON object per");
+    println!("line ({{\"event\":\"progress\",...}}), followed by a line with the result.\n");
+    println!("EXAMPLE:");
+    println!(
+        "    s7forge update-item --app-id 548430 --item-id 123456789 --content-folder ./build --change-note \"Fix tank speed\""
+    );
+}
+
+fn print_delete_item_help() {
+    println!("Permanently delete a workshop item you own\n");
+    println!("USAGE:");
+    println!("    s7forge delete-item --app-id <APP_ID> --item-id <ITEM_ID> --yes\n");
+    println!("OPTIONS:");
+    println!("    --app-id <APP_ID>      Steam App ID of the game");
+    println!("    --item-id <ITEM_ID>    Workshop item ID to delete");
+    println!("    --yes                  Confirm the deletion, which cannot be undone");
+    println!("    -h, --help             Print help\n");
+    println!("Items owned by another user are refused with exit code 9.\n");
+    println!("EXAMPLE:");
+    println!("    s7forge delete-item --app-id 548430 --item-id 123456789 --yes");
+}
+
+fn print_set_visibility_help() {
+    println!("Change who can see a workshop item you own\n");
+    println!("USAGE:");
+    println!(
+        "    s7forge set-visibility --app-id <APP_ID> --item-id <ITEM_ID> --visibility <VISIBILITY>\n"
+    );
+    println!("OPTIONS:");
+    println!("    --app-id <APP_ID>            Steam App ID of the game");
+    println!("    --item-id <ITEM_ID>          Workshop item ID to change");
+    println!("    --visibility <VISIBILITY>    public, friends-only, private or unlisted");
+    println!("    -h, --help                   Print help\n");
+    println!("Items owned by another user are refused with exit code 9.\n");
+    println!("EXAMPLE:");
+    println!("    s7forge set-visibility --app-id 548430 --item-id 123456789 --visibility private");
+}
+
+fn print_dependency_help(command: &str) {
+    if command == "add-dependency" {
+        println!("Make items required by a workshop item you own\n");
+    } else {
+        println!("Stop requiring items from a workshop item you own\n");
+    }
+    println!("USAGE:");
+    println!(
+        "    s7forge {} --app-id <APP_ID> --item-id <ITEM_ID> [OPTIONS]\n",
+        command
+    );
+    println!("OPTIONS:");
+    println!("    --app-id <APP_ID>              Steam App ID of the game");
+    println!("    --item-id <ITEM_ID>            Workshop item whose dependencies change");
+    println!("    --dependency-ids <IDS>         Required item IDs (comma-separated)");
+    println!("    --app-dependency-ids <IDS>     Required app IDs, such as DLC (comma-separated)");
+    println!("    -h, --help                     Print help\n");
+    println!("At least one of --dependency-ids and --app-dependency-ids is needed.");
+    if command == "add-dependency" {
+        println!("Every item dependency must be an existing workshop item. One that leads back to");
+        println!("the item through its own required items is still added, and reported in");
+        println!("warnings as a cycle.");
+    }
+    println!();
+    println!("EXAMPLE:");
+    println!(
+        "    s7forge {} --app-id 548430 --item-id 123456789 --dependency-ids 987654321",
+        command
+    );
+}
+
+fn print_resolve_dependencies_help() {
+    println!(
+        "List every item workshop items require, directly or through other items, in load order\n"
+    );
+    println!("USAGE:");
+    println!("    s7forge resolve-dependencies --app-id <APP_ID> --item-ids <IDS> [OPTIONS]\n");
+    println!("OPTIONS:");
+    println!("    --app-id <APP_ID>      Steam App ID of the game");
+    println!("    --item-ids <IDS>       Workshop item IDs to resolve (comma-separated)");
+    println!("    --dot                  Also render the graph in Graphviz DOT into a dot field");
+    println!("    -h, --help             Print help\n");
+    println!("The output is {{item_ids, nodes, load_order, cycles, missing, banned}}. load_order");
+    println!("lists each item after everything it requires and leaves out missing items. Items");
+    println!("that require each other are listed in cycles and placed next to each other.");
+    println!("With --dot the output also has a dot field holding the graph in Graphviz DOT.\n");
+    println!("EXAMPLES:");
+    println!("    s7forge resolve-dependencies --app-id 548430 --item-ids 123456789,987654321");
+    println!(
+        "    s7forge resolve-dependencies --app-id 548430 --item-ids 123456789 --dot | jq -r .dot | dot -Tsvg > deps.svg"
+    );
+}
+
+fn print_serve_help() {
+    println!(
+        "Keep the Steam client alive and answer newline-delimited JSON-RPC 2.0 requests on stdin\n"
+    );
+    println!("USAGE:");
+    println!("    s7forge serve [OPTIONS]\n");
+    println!("Each request names a command as its method and passes the command options as");
+    println!("params, using snake_case field names. Responses and notifications are written");
+    println!("to stdout, one JSON object per line. download-workshop-item additionally emits");
+    println!("download-progress and item-installed notifications, update-item emits");
+    println!("update-progress notifications. The status method lists the apps with an");
+    println!("initialized Steam client.\n");
+    println!("Commands for different apps run in one worker process per app, because Steam binds");
+    println!("a process to a single app.\n");
+    println!("OPTIONS:");
+    println!(
+        "    --in-process             Run every request in this process instead of per-app workers"
+    );
+    println!(
+        "    --idle-timeout <SECS>    Stop an app's worker after this many idle seconds [default: 600]"
+    );
+    println!("    -h, --help               Print help\n");
+    println!("EXAMPLE:");
+    println!(
+        "    echo '{{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"workshop-items\",\"params\":{{\"app_id\":548430,\"item_ids\":[123]}}}}' | s7forge serve"
+    );
+}
+
+fn print_http_serve_help() {
+    println!("Expose the workshop commands as a REST API on 127.0.0.1\n");
+    println!("USAGE:");
+    println!("    s7forge http-serve --port <PORT> [OPTIONS]\n");
+    println!("OPTIONS:");
+    println!("    --port <PORT>              Port to listen on, 0 picks a free port");
+    println!(
+        "    --allow-origin <ORIGIN>    Let pages from this origin call the API, e.g. http://localhost:3000"
+    );
+    println!("                               (repeatable)");
+    println!(
+        "    --in-process               Run every request in this process instead of per-app workers"
+    );
+    println!(
+        "    --idle-timeout <SECS>      Stop an app's worker after this many idle seconds [default: 600]"
+    );
+    println!("    -h, --help                 Print help\n");
+    println!("Requests must name 127.0.0.1 or localhost as their Host, and requests from a");
+    println!("browser page are refused unless its origin is allowed. POST, PUT and DELETE");
+    println!("requests need Content-Type: application/json.\n");
+    println!("ENDPOINTS:");
+    println!(
+        "    GET    /apps/{{app_id}}/search?q=&sort=&period=&page=&tags=&exclude_tags=&match_any_tags=&tag_group=&created_since=&created_until=&updated_since=&updated_until=&type="
+    );
+    println!("    GET    /apps/{{app_id}}/items?ids=<ID,ID,...>&user_state=&type=");
+    println!("    GET    /apps/{{app_id}}/items/{{item_id}}?user_state=&type=");
+    println!("    GET    /apps/{{app_id}}/items/{{item_id}}/download");
+    println!("    POST   /apps/{{app_id}}/items/{{item_id}}/download");
+    println!("    GET    /apps/{{app_id}}/collections/{{item_id}}");
+    println!("    GET    /apps/{{app_id}}/subscriptions");
+    println!("    POST   /apps/{{app_id}}/subscriptions          body: {{\"item_ids\": [...]}}");
+    println!("    DELETE /apps/{{app_id}}/subscriptions          body: {{\"item_ids\": [...]}}");
+    println!("    DELETE /apps/{{app_id}}/subscriptions/{{item_id}}");
+    println!("    GET    /apps/{{app_id}}/workshop-path");
+    println!("    GET    /apps/{{app_id}}/tags");
+    println!("    GET    /steam/library-paths?detailed=true");
+    println!("    GET    /steam/install-paths");
+    println!("    DELETE /cache");
+    println!("    GET    /status\n");
+    println!("EXAMPLE:");
+    println!("    s7forge http-serve --port 8420");
+}
+
+fn print_vote_help() {
+    println!("Vote a workshop item up or down\n");
+    println!("USAGE:");
+    println!("    s7forge vote --app-id <APP_ID> --item-id <ITEM_ID> (--up | --down)\n");
+    println!("OPTIONS:");
+    println!("    --app-id <APP_ID>      Steam App ID of the game");
+    println!("    --item-id <ITEM_ID>    Workshop item ID to vote on");
+    println!("    --up                   Vote the item up");
+    println!("    --down                 Vote the item down");
+    println!("    -h, --help             Print help\n");
+    println!("A new vote replaces your earlier one. Steam has no way to take a vote back.\n");
+    println!("EXAMPLE:");
+    println!("    s7forge vote --app-id 548430 --item-id 123456789 --up");
+}
+
+fn print_get_vote_help() {
+    println!("Show your vote on a workshop item\n");
+    println!("USAGE:");
+    println!("    s7forge get-vote --app-id <APP_ID> --item-id <ITEM_ID>\n");
+    println!("OPTIONS:");
+    println!("    --app-id <APP_ID>      Steam App ID of the game");
+    println!("    --item-id <ITEM_ID>    Workshop item ID");
+    println!("    -h, --help             Print help\n");
+    println!("Prints \"up\", \"down\" or null as vote.\n");
+    println!("EXAMPLE:");
+    println!("    s7forge get-vote --app-id 548430 --item-id 123456789");
+}
+
+fn print_favorite_help() {
+    println!("Add a workshop item to your favorites or remove it\n");
+    println!("USAGE:");
+    println!("    s7forge favorite <add|remove> --app-id <APP_ID> --item-id <ITEM_ID>\n");
+    println!("OPTIONS:");
+    println!("    --app-id <APP_ID>      Steam App ID of the game");
+    println!("    --item-id <ITEM_ID>    Workshop item ID");
+    println!("    -h, --help             Print help\n");
+    println!("changed is false when the item already was, or was not, a favorite.\n");
+    println!("EXAMPLE:");
+    println!("    s7forge favorite add --app-id 548430 --item-id 123456789");
+}
+
+fn print_collection_create_help(command: &str) {
+    let from_subscriptions = command == "collection-from-subscriptions";
+    if from_subscriptions {
+        println!("Create a collection of the items you're subscribed to\n");
+        println!("USAGE:");
+        println!(
+            "    s7forge collection-from-subscriptions --app-id <APP_ID> --title <TITLE> [OPTIONS]\n"
+        );
+    } else {
+        println!("Create a collection of workshop items\n");
+        println!("USAGE:");
+        println!("    s7forge collection-create --app-id <APP_ID> --title <TITLE> [OPTIONS]\n");
+    }
+    println!("OPTIONS:");
+    println!("    --app-id <APP_ID>            Steam App ID of the game");
+    println!("    --title <TITLE>              Collection title (up to 128 bytes)");
+    println!("    --description <TEXT>         Collection description");
+    println!(
+        "    --visibility <VISIBILITY>    public, friends-only, private or unlisted [default: public]"
+    );
+    println!("    --preview-image <FILE>       Preview image shown on the workshop page");
+    if !from_subscriptions {
+        println!(
+            "    --item-ids <ITEM_IDS>        Items in the collection, in display order (comma-separated)"
+        );
+    }
+    println!("    -h, --help                   Print help\n");
+    if from_subscriptions {
+        println!(
+            "The collection holds the items subscribed-items lists at the time it is created.\n"
+        );
+    }
+    println!("EXAMPLE:");
+    if from_subscriptions {
+        println!(
+            "    s7forge collection-from-subscriptions --app-id 548430 --title \"My mod list\" --visibility unlisted"
+        );
+    } else {
+        println!(
+            "    s7forge collection-create --app-id 548430 --title \"Tank mods\" --item-ids 123,456,789"
+        );
+    }
+}
+
+fn print_collection_edit_help(command: &str) {
+    let adding = command == "collection-add";
+    if adding {
+        println!("Add or move items in a collection you own\n");
+        println!("USAGE:");
+        println!(
+            "    s7forge collection-add --app-id <APP_ID> --collection-id <ID> --item-ids <ITEM_IDS> [--position <N>]\n"
+        );
+    } else {
+        println!("Remove items from a collection you own\n");
+        println!("USAGE:");
+        println!(
+            "    s7forge collection-remove --app-id <APP_ID> --collection-id <ID> --item-ids <ITEM_IDS>\n"
+        );
+    }
+    println!("OPTIONS:");
+    println!("    --app-id <APP_ID>            Steam App ID of the game");
+    println!("    --collection-id <ID>         Collection to change");
+    println!("    --item-ids <ITEM_IDS>        Workshop item IDs (comma-separated)");
+    if adding {
+        println!(
+            "    --position <N>               Place the items starting at this 1-based position [default: end]"
+        );
+    }
+    println!("    -h, --help                   Print help\n");
+    if adding {
+        println!("Items already in the collection are moved to the new position. Prints the");
+        println!("collection's items in their new order.\n");
+    }
+    println!("EXAMPLE:");
+    if adding {
+        println!(
+            "    s7forge collection-add --app-id 548430 --collection-id 987654321 --item-ids 123 --position 1"
+        );
+    } else {
+        println!(
+            "    s7forge collection-remove --app-id 548430 --collection-id 987654321 --item-ids 123,456"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
+use std::hash::Hash;
+
+use crate::commands::workshop_items::{fetch_items, forget_cached_items, workshop_items};
+use crate::core::steam_backend::SteamBackend;
+use crate::core::steam_manager;
+use crate::error::S7Error;
+use crate::utils::check_item_owner::check_item_owner;
+
+#[derive(Debug, Serialize)]
+pub struct AddDependencyResult {
+    pub item_id: u64,
+    pub added: Vec<u64>,
+    pub required_items: Vec<u64>,
+    pub added_apps: Vec<u32>,
+    pub required_apps: Vec<u32>,
+    pub warnings: Vec<String>,
+}
+
+pub async fn add_dependency(
+    steam_game_id: u32,
+    item_id: u64,
+    dependency_ids: Vec<u64>,
+    app_dependency_ids: Vec<u32>,
+) -> Result<AddDependencyResult, S7Error> {
+    let dependency_ids = unique_ids(dependency_ids);
+    let app_dependency_ids = unique_ids(app_dependency_ids);
+    if dependency_ids.is_empty() && app_dependency_ids.is_empty() {
+        return Err(S7Error::InvalidArgument(
+            "No dependency IDs given".to_string(),
+        ));
+    }
+    if app_dependency_ids.contains(&0) {
+        return Err(S7Error::InvalidArgument("0 is not an app ID".to_string()));
+    }
+    if dependency_ids.contains(&item_id) {
+        return Err(S7Error::InvalidArgument(format!(
+            "Item {} cannot depend on itself",
+            item_id
+        )));
+    }
+
+    let backend = steam_manager::backend(steam_game_id)?;
+    let item = check_item_owner(backend.as_ref(), item_id).await?;
+
+    let found: FxHashSet<u64> = if dependency_ids.is_empty() {
+        FxHashSet::default()
+    } else {
+        workshop_items(steam_game_id, dependency_ids.clone())
+            .await?
+            .iter()
+            .map(|item| item.workshop_item.published_file_id)
+            .collect()
+    };
+    let missing: Vec<String> = dependency_ids
+        .iter()
+        .filter(|id| !found.contains(id))
+        .map(u64::to_string)
+        .collect();
+    if !missing.is_empty() {
+        return Err(S7Error::NotFound(format!(
+            "Workshop items not found: {}",
+            missing.join(", ")
+        )));
+    }
+
+    let mut required_items = item.required_items;
+    let new_ids: Vec<u64> = dependency_ids
+        .into_iter()
+        .filter(|id| !required_items.contains(id))
+        .collect();
+
+    let mut warnings = Vec::new();
+    for &dependency_id in &new_ids {
+        if let Some(path) =
+            dependency_path(steam_game_id, backend.as_ref(), dependency_id, item_id).await?
+        {
+            let cycle: Vec<String> = std::iter::once(item_id)
+                .chain(path)
+                .map(|id| id.to_string())
+                .collect();
+            warnings.push(format!(
+                "Adding {} creates a dependency cycle: {}",
+                dependency_id,
+                cycle.join(" -> ")
+            ));
+        }
+    }
+
+    let changes = backend.add_dependencies(item_id, new_ids).await?;
+    if !changes.applied.is_empty() {
+        forget_cached_items(steam_game_id, &[item_id]);
+    }
+    if let Some((dependency_id, error)) = changes.failed {
+        return Err(error.context(format!(
+            "Failed to add {} as a dependency of {}{}",
+            dependency_id,
+            item_id,
+            changed_before("adding", &changes.applied)
+        )));
+    }
+    required_items.extend(&changes.applied);
+
+    let mut required_apps = backend.app_dependencies(item_id).await?;
+    let new_app_ids: Vec<u32> = app_dependency_ids
+        .into_iter()
+        .filter(|id| !required_apps.contains(id))
+        .collect();
+    let mut added_apps = Vec::new();
+    for &app_id in &new_app_ids {
+        if let Err(error) = backend.add_app_dependency(item_id, app_id).await {
+            return Err(error.context(format!(
+                "Failed to add app {} as a dependency of {}{}{}",
+                app_id,
+                item_id,
+                changed_before("adding", &changes.applied),
+                changed_before("adding app", &added_apps)
+            )));
+        }
+        added_apps.push(app_id);
+        required_apps.push(app_id);
+    }
+
+    Ok(AddDependencyResult {
+        item_id,
+        added: changes.applied,
+        required_items,
+        added_apps,
+        required_apps,
+        warnings,
+    })
+}
+
+/// Names the items a batch changed before it failed, for the failure's message.
+pub fn changed_before<T: ToString>(action: &str, changed: &[T]) -> String {
+    if changed.is_empty() {
+        return String::new();
+    }
+    let ids: Vec<String> = changed.iter().map(T::to_string).collect();
+    format!(", after {} {}", action, ids.join(", "))
+}
+
+pub fn unique_ids<T: Copy + Eq + Hash>(ids: Vec<T>) -> Vec<T> {
+    let mut seen = FxHashSet::default();
+    ids.into_iter().filter(|id| seen.insert(*id)).collect()
+}
+
+// Follows required items breadth-first from `from` and returns the chain of IDs that
+// leads to `to`, if there is one. Items are fetched fresh, since a cached item can
+// miss a dependency someone added since.
+async fn dependency_path(
+    steam_game_id: u32,
+    backend: &dyn SteamBackend,
+    from: u64,
+    to: u64,
+) -> Result<Option<Vec<u64>>, S7Error> {
+    let mut reached_from: FxHashMap<u64, u64> = FxHashMap::default();
+    let mut visited: FxHashSet<u64> = FxHashSet::from_iter([from]);
+    let mut frontier = vec![from];
+
+    while !frontier.is_empty() {
+        let items = fetch_items(steam_game_id, backend, &frontier).await?;
+        frontier = Vec::new();
+
+        for item in items {
+            let parent = item.published_file_id;
+            for &child in &item.required_items {
+                if child == to {
+                    let mut path = vec![child, parent];
+                    let mut current = parent;
+                    while let Some(&previous) = reached_from.get(&current) {
+                        path.push(previous);
+                        current = previous;
+                    }
+                    path.reverse();
+                    return Ok(Some(path));
+                }
+                if visited.insert(child) {
+                    reached_from.insert(child, parent);
+                    frontier.push(child);
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
@@ -1,9 +1,8 @@
-use futures_util::FutureExt;
 use serde::Serialize;
-use steamworks::PublishedFileId;
-use tokio::sync::mpsc;
+use steamworks::ItemState;
 
 use crate::core::steam_manager;
+use crate::error::S7Error;
 
 #[derive(Debug, Serialize)]
 pub struct DownloadInfo {
@@ -14,86 +13,55 @@ pub struct DownloadInfo {
     pub download_complete: bool,
 }
 
-pub async fn check_item_download(steam_game_id: u32, item_id: u64) -> Result<DownloadInfo, String> {
-    let steam_client = steam_manager::initialize_client(steam_game_id).await?;
-
-    let (tx, mut rx) = mpsc::channel(32);
-
-    let download_task = tokio::task::spawn_blocking(move || {
-        let ugc = steam_client.ugc();
-        let item = PublishedFileId(item_id);
-
-        let (tx_inner, rx_inner) = std::sync::mpsc::channel();
-
-        let start_time = std::time::Instant::now();
-        let timeout_duration = std::time::Duration::from_secs(30);
-
-        let state = ugc.item_state(item);
-        let is_installed = state.contains(steamworks::ItemState::INSTALLED);
-        let is_downloading = state.contains(steamworks::ItemState::DOWNLOADING);
-        let needs_update = state.contains(steamworks::ItemState::NEEDS_UPDATE);
-
-        if is_installed && !needs_update {
-            let _ = tx_inner.send(Ok(DownloadInfo {
-                is_downloading: false,
-                downloaded_bytes: ugc.item_install_info(item).unwrap().size_on_disk,
-                total_bytes: ugc.item_install_info(item).unwrap().size_on_disk,
-                progress_percentage: 100.0,
-                download_complete: true,
-            }));
-        } else if is_downloading {
-            if let Some((bytes_downloaded, bytes_total)) = ugc.item_download_info(item) {
-                let progress = if bytes_total > 0 {
-                    (bytes_downloaded as f32 / bytes_total as f32) * 100.0
-                } else {
-                    0.0
-                };
+pub async fn check_item_download(
+    steam_game_id: u32,
+    item_id: u64,
+) -> Result<DownloadInfo, S7Error> {
+    let backend = steam_manager::backend(steam_game_id)?;
+
+    let state = backend.item_state(item_id);
+    let is_installed = state.contains(ItemState::INSTALLED);
+    let is_downloading = state.contains(ItemState::DOWNLOADING);
+    let needs_update = state.contains(ItemState::NEEDS_UPDATE);
+
+    if is_installed && !needs_update {
+        let size_on_disk = backend
+            .item_install_info(item_id)
+            .map(|info| info.size_on_disk)
+            .unwrap_or_default();
+
+        return Ok(DownloadInfo {
+            is_downloading: false,
+            downloaded_bytes: size_on_disk,
+            total_bytes: size_on_disk,
+            progress_percentage: 100.0,
+            download_complete: true,
+        });
+    }
 
-                let _ = tx_inner.send(Ok(DownloadInfo {
-                    is_downloading: true,
-                    downloaded_bytes: bytes_downloaded,
-                    total_bytes: bytes_total,
-                    progress_percentage: progress,
-                    download_complete: false,
-                }));
-            }
+    if is_downloading
+        && let Some((bytes_downloaded, bytes_total)) = backend.item_download_info(item_id)
+    {
+        let progress = if bytes_total > 0 {
+            (bytes_downloaded as f32 / bytes_total as f32) * 100.0
         } else {
-            let _ = tx_inner.send(Ok(DownloadInfo {
-                is_downloading: false,
-                downloaded_bytes: 0,
-                total_bytes: 0,
-                progress_percentage: 0.0,
-                download_complete: false,
-            }));
-        }
-
-        loop {
-            let _ = tx.blocking_send(());
-            if let Ok(result) = rx_inner.try_recv() {
-                return result;
-            }
-
-            if start_time.elapsed() > timeout_duration {
-                return Err("Operation timed out waiting for Steam response".to_string());
-            }
-
-            std::thread::sleep(std::time::Duration::from_millis(10));
-        }
-    });
-
-    let mut download_result = None;
-    let mut fused_task = download_task.fuse();
-
-    while download_result.is_none() {
-        tokio::select! {
-            Some(_) = rx.recv() => {
-                steam_manager::run_callbacks(steam_game_id)?;
-            }
-            task_result = &mut fused_task => {
-                download_result = Some(task_result.map_err(|e| format!("Task error: {:?}", e))??);
-            }
-        }
+            0.0
+        };
+
+        return Ok(DownloadInfo {
+            is_downloading: true,
+            downloaded_bytes: bytes_downloaded,
+            total_bytes: bytes_total,
+            progress_percentage: progress,
+            download_complete: false,
+        });
     }
 
-    Ok(download_result.unwrap())
+    Ok(DownloadInfo {
+        is_downloading: false,
+        downloaded_bytes: 0,
+        total_bytes: 0,
+        progress_percentage: 0.0,
+        download_complete: false,
+    })
 }
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 
+use crate::error::S7Error;
 use crate::utils::get_cache_dir::get_cache_dir;
 
 #[derive(Serialize, Deserialize)]
@@ -11,7 +12,7 @@ pub struct ClearCacheResult {
     pub files: Vec<String>,
 }
 
-pub fn clear_cache() -> Result<ClearCacheResult, String> {
+pub fn clear_cache() -> Result<ClearCacheResult, S7Error> {
     let cache_dir = get_cache_dir()?;
 
     if !cache_dir.exists() {
@@ -26,8 +27,8 @@ pub fn clear_cache() -> Result<ClearCacheResult, String> {
     let mut cleared_files = Vec::new();
     let mut errors = Vec::new();
 
-    let entries =
-        fs::read_dir(&cache_dir).map_err(|e| format!("Failed to read cache directory: {:?}", e))?;
+    let entries = fs::read_dir(&cache_dir)
+        .map_err(|e| S7Error::Io(format!("Failed to read cache directory: {:?}", e)))?;
 
     for entry in entries {
         match entry {
@@ -53,10 +54,10 @@ pub fn clear_cache() -> Result<ClearCacheResult, String> {
     }
 
     if !errors.is_empty() {
-        return Err(format!(
+        return Err(S7Error::Io(format!(
             "Errors occurred while clearing cache: {}",
             errors.join(", ")
-        ));
+        )));
     }
 
     let result = if cleared_files.is_empty() {
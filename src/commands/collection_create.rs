@@ -0,0 +1,94 @@
+use serde::Serialize;
+use std::path::PathBuf;
+use steamworks::FileType;
+
+use crate::commands::add_dependency::{changed_before, unique_ids};
+use crate::commands::collection_edit::check_items_exist;
+use crate::commands::publish::{create_and_upload, validate_item_update};
+use crate::commands::subscribed_items::subscribed_items;
+use crate::core::steam_backend::ItemUpdate;
+use crate::core::steam_manager;
+use crate::core::workshop::UgcItemVisibility;
+use crate::error::S7Error;
+
+#[derive(Debug, Clone, Default)]
+pub struct CollectionOptions {
+    pub title: String,
+    pub description: String,
+    pub visibility: UgcItemVisibility,
+    pub preview_image: Option<PathBuf>,
+    /// The collection's items, in display order.
+    pub item_ids: Vec<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CollectionCreateResult {
+    pub collection_id: u64,
+    pub items: Vec<u64>,
+    pub needs_legal_agreement: bool,
+}
+
+pub async fn collection_create(
+    steam_game_id: u32,
+    options: CollectionOptions,
+) -> Result<CollectionCreateResult, S7Error> {
+    if options.title.trim().is_empty() {
+        return Err(S7Error::InvalidArgument(
+            "Title must not be empty".to_string(),
+        ));
+    }
+
+    let update = validate_item_update(ItemUpdate {
+        title: Some(options.title),
+        description: Some(options.description),
+        visibility: Some(options.visibility),
+        preview_image: options.preview_image,
+        ..ItemUpdate::default()
+    })?;
+
+    let item_ids = unique_ids(options.item_ids);
+    check_items_exist(steam_game_id, &item_ids).await?;
+
+    let created =
+        create_and_upload(steam_game_id, FileType::Collection, update, |_| Ok(())).await?;
+    let collection_id = created.published_file_id;
+
+    let backend = steam_manager::backend(steam_game_id)?;
+    let changes = backend
+        .add_dependencies(collection_id, item_ids.clone())
+        .await?;
+    if let Some((item_id, error)) = changes.failed {
+        return Err(error.context(format!(
+            "Collection {} was created but adding item {} failed{}",
+            collection_id,
+            item_id,
+            changed_before("adding", &changes.applied)
+        )));
+    }
+
+    Ok(CollectionCreateResult {
+        collection_id,
+        items: item_ids,
+        needs_legal_agreement: created.needs_legal_agreement,
+    })
+}
+
+/// Creates a collection of the items `subscribed-items` lists, replacing any
+/// `item_ids` in `options`.
+pub async fn collection_from_subscriptions(
+    steam_game_id: u32,
+    mut options: CollectionOptions,
+) -> Result<CollectionCreateResult, S7Error> {
+    options.item_ids = subscribed_items(steam_game_id, None)
+        .await?
+        .iter()
+        .map(|item| item.workshop_item.published_file_id)
+        .collect();
+    if options.item_ids.is_empty() {
+        return Err(S7Error::InvalidArgument(
+            "There are no subscribed items to put in a collection".to_string(),
+        ));
+    }
+
+    collection_create(steam_game_id, options).await
+}
@@ -0,0 +1,232 @@
+use rustc_hash::FxHashSet;
+use serde::Serialize;
+
+use crate::commands::add_dependency::{changed_before, unique_ids};
+use crate::commands::workshop_items::{forget_cached_items, workshop_items};
+use crate::core::steam_backend::{DependencyChanges, SteamBackend};
+use crate::core::steam_manager;
+use crate::core::workshop_item::workshop::WorkshopItem;
+use crate::error::S7Error;
+use crate::utils::check_item_owner::check_item_owner;
+
+#[derive(Debug, Serialize)]
+pub struct CollectionEditResult {
+    pub collection_id: u64,
+    /// The collection's items after the change, in display order.
+    pub items: Vec<u64>,
+    pub warnings: Vec<String>,
+}
+
+/// Adds items to a collection, or moves them if they are already in it. With a
+/// 1-based `position` they are placed there, otherwise at the end.
+pub async fn collection_add(
+    steam_game_id: u32,
+    collection_id: u64,
+    item_ids: Vec<u64>,
+    position: Option<usize>,
+) -> Result<CollectionEditResult, S7Error> {
+    let item_ids = unique_ids(item_ids);
+    if item_ids.is_empty() {
+        return Err(S7Error::InvalidArgument("No item IDs given".to_string()));
+    }
+    if position == Some(0) {
+        return Err(S7Error::InvalidArgument("Position starts at 1".to_string()));
+    }
+
+    let backend = steam_manager::backend(steam_game_id)?;
+    let collection = owned_collection(backend.as_ref(), collection_id).await?;
+    check_items_exist(steam_game_id, &item_ids).await?;
+
+    let current = collection.required_items;
+    let mut items: Vec<u64> = current
+        .iter()
+        .copied()
+        .filter(|id| !item_ids.contains(id))
+        .collect();
+    let index = position.map_or(items.len(), |position| (position - 1).min(items.len()));
+    items.splice(index..index, item_ids);
+
+    apply_order(
+        steam_game_id,
+        backend.as_ref(),
+        collection_id,
+        &current,
+        &items,
+    )
+    .await?;
+
+    Ok(CollectionEditResult {
+        collection_id,
+        items,
+        warnings: Vec::new(),
+    })
+}
+
+pub async fn collection_remove(
+    steam_game_id: u32,
+    collection_id: u64,
+    item_ids: Vec<u64>,
+) -> Result<CollectionEditResult, S7Error> {
+    let item_ids = unique_ids(item_ids);
+    if item_ids.is_empty() {
+        return Err(S7Error::InvalidArgument("No item IDs given".to_string()));
+    }
+
+    let backend = steam_manager::backend(steam_game_id)?;
+    let current = owned_collection(backend.as_ref(), collection_id)
+        .await?
+        .required_items;
+
+    let warnings = item_ids
+        .iter()
+        .filter(|id| !current.contains(id))
+        .map(|id| format!("{} is not in collection {}", id, collection_id))
+        .collect();
+    let items: Vec<u64> = current
+        .iter()
+        .copied()
+        .filter(|id| !item_ids.contains(id))
+        .collect();
+
+    apply_order(
+        steam_game_id,
+        backend.as_ref(),
+        collection_id,
+        &current,
+        &items,
+    )
+    .await?;
+
+    Ok(CollectionEditResult {
+        collection_id,
+        items,
+        warnings,
+    })
+}
+
+/// Fails with `NotFound` naming every ID that is not an existing workshop item.
+pub async fn check_items_exist(steam_game_id: u32, item_ids: &[u64]) -> Result<(), S7Error> {
+    let found: FxHashSet<u64> = workshop_items(steam_game_id, item_ids.to_vec())
+        .await?
+        .iter()
+        .map(|item| item.workshop_item.published_file_id)
+        .collect();
+
+    let missing: Vec<String> = item_ids
+        .iter()
+        .filter(|id| !found.contains(id))
+        .map(u64::to_string)
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(S7Error::NotFound(format!(
+            "Workshop items not found: {}",
+            missing.join(", ")
+        )))
+    }
+}
+
+async fn owned_collection(
+    backend: &dyn SteamBackend,
+    collection_id: u64,
+) -> Result<WorkshopItem, S7Error> {
+    let collection = check_item_owner(backend, collection_id).await?;
+    if collection.file_type != "Collection" {
+        return Err(S7Error::InvalidArgument(format!(
+            "Item {} is not a collection",
+            collection_id
+        )));
+    }
+    Ok(collection)
+}
+
+// Steam keeps a collection's children in the order they were added and has no call
+// to reorder them. The longest start of `wanted` that is already in order stays, and
+// only the other children are removed and added again after it. If a change fails,
+// children that were taken out but belong in the collection are added back at the
+// end, so a failure can leave the order wrong but doesn't drop items.
+async fn apply_order(
+    steam_game_id: u32,
+    backend: &dyn SteamBackend,
+    collection_id: u64,
+    current: &[u64],
+    wanted: &[u64],
+) -> Result<(), S7Error> {
+    let mut kept = 0;
+    for id in current {
+        if wanted.get(kept) == Some(id) {
+            kept += 1;
+        }
+    }
+    let to_remove: Vec<u64> = current
+        .iter()
+        .copied()
+        .filter(|id| !wanted[..kept].contains(id))
+        .collect();
+    let to_add = wanted[kept..].to_vec();
+    if to_remove.is_empty() && to_add.is_empty() {
+        return Ok(());
+    }
+
+    let removal = backend
+        .remove_dependencies(collection_id, to_remove)
+        .await?;
+    let addition = if removal.failed.is_none() {
+        backend.add_dependencies(collection_id, to_add).await?
+    } else {
+        DependencyChanges::default()
+    };
+
+    let failure = match (removal.failed, addition.failed) {
+        (Some((item_id, error)), _) => Some((
+            error,
+            format!(
+                "removing item {} failed{}",
+                item_id,
+                changed_before("removing", &removal.applied)
+            ),
+        )),
+        (None, Some((item_id, error))) => Some((
+            error,
+            format!(
+                "adding item {} failed{}{}",
+                item_id,
+                changed_before("removing", &removal.applied),
+                changed_before("adding", &addition.applied)
+            ),
+        )),
+        (None, None) => None,
+    };
+    let Some((error, what)) = failure else {
+        forget_cached_items(steam_game_id, &[collection_id]);
+        return Ok(());
+    };
+
+    let dropped: Vec<u64> = removal
+        .applied
+        .iter()
+        .copied()
+        .filter(|id| wanted.contains(id) && !addition.applied.contains(id))
+        .collect();
+    let restored = if dropped.is_empty() {
+        DependencyChanges::default()
+    } else {
+        backend
+            .add_dependencies(collection_id, dropped)
+            .await
+            .unwrap_or_default()
+    };
+    forget_cached_items(steam_game_id, &[collection_id]);
+
+    let restored_note = if restored.applied.is_empty() {
+        String::new()
+    } else {
+        let ids: Vec<String> = restored.applied.iter().map(u64::to_string).collect();
+        format!("; added {} back at the end", ids.join(", "))
+    };
+    Err(error.context(format!(
+        "Failed to update collection {}: {}{}",
+        collection_id, what, restored_note
+    )))
+}
@@ -0,0 +1,47 @@
+use serde::Serialize;
+
+use crate::commands::subscribed_items::subscribed_items;
+use crate::core::game_version::GameVersion;
+use crate::error::S7Error;
+
+#[derive(Debug, Serialize)]
+pub struct CompatReport {
+    pub game_version: GameVersion,
+    /// How many subscribed items were checked.
+    pub checked: usize,
+    pub incompatible: Vec<IncompatibleItem>,
+}
+
+/// A subscribed item none of whose version tags cover the game version.
+#[derive(Debug, Serialize)]
+pub struct IncompatibleItem {
+    pub published_file_id: u64,
+    pub title: String,
+    pub creator_name: String,
+    /// Empty when the item declares no versions at all.
+    pub compatible_versions: Vec<GameVersion>,
+}
+
+pub async fn compat_report(
+    steam_game_id: u32,
+    game_version: GameVersion,
+) -> Result<CompatReport, S7Error> {
+    let items = subscribed_items(steam_game_id, None).await?;
+
+    let incompatible = items
+        .iter()
+        .filter(|item| !item.workshop_item.supports_game_version(&game_version))
+        .map(|item| IncompatibleItem {
+            published_file_id: item.workshop_item.published_file_id,
+            title: item.workshop_item.title.clone(),
+            creator_name: item.creator_name.clone(),
+            compatible_versions: item.workshop_item.compatible_versions.clone(),
+        })
+        .collect();
+
+    Ok(CompatReport {
+        game_version,
+        checked: items.len(),
+        incompatible,
+    })
+}
@@ -0,0 +1,31 @@
+use serde::Serialize;
+
+use crate::commands::workshop_items::forget_cached_items;
+use crate::core::steam_manager;
+use crate::error::S7Error;
+use crate::utils::check_item_owner::check_item_owner;
+
+#[derive(Debug, Serialize)]
+pub struct DeleteItemResult {
+    pub item_id: u64,
+    pub title: String,
+    pub deleted: bool,
+}
+
+pub async fn delete_item(steam_game_id: u32, item_id: u64) -> Result<DeleteItemResult, S7Error> {
+    let backend = steam_manager::backend(steam_game_id)?;
+    let item = check_item_owner(backend.as_ref(), item_id).await?;
+
+    backend
+        .delete_item(item_id)
+        .await
+        .map_err(|error| error.context(format!("Failed to delete item {}", item_id)))?;
+
+    forget_cached_items(steam_game_id, &[item_id]);
+
+    Ok(DeleteItemResult {
+        item_id,
+        title: item.title,
+        deleted: true,
+    })
+}
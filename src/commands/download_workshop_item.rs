@@ -1,72 +1,83 @@
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use steamworks::{ItemState, PublishedFileId};
+use steamworks::ItemState;
 
+use crate::core::call_result::{default_timeout, pump_until};
 use crate::core::steam_manager;
+use crate::error::S7Error;
 
-pub async fn download_workshop_item(steam_game_id: u32, item_id: u64) -> Result<(), String> {
-    let steam_client = steam_manager::initialize_client(steam_game_id).await?;
-
-    let published_file_id = PublishedFileId(item_id);
-    {
-        let ugc = steam_client.ugc();
-        let state = ugc.item_state(published_file_id);
-        if !state.contains(ItemState::SUBSCRIBED) {
-            return Err("Workshop item is not subscribed".to_string());
-        }
-
-        ugc.download_item(published_file_id, true);
-    }
+enum DownloadStep {
+    Progressed,
+    Finished,
+}
 
-    let timeout = Duration::from_secs(10 * 60); // 10 minutes
-    let cancelled = Arc::new(Mutex::new(false));
-    let cancelled_clone = cancelled.clone();
+pub async fn download_workshop_item(steam_game_id: u32, item_id: u64) -> Result<(), S7Error> {
+    download_workshop_item_with_progress(steam_game_id, item_id, |_, _| {}).await
+}
 
-    let (tx, rx) = tokio::sync::oneshot::channel();
-    std::thread::spawn(move || {
-        let start_time = std::time::Instant::now();
-        loop {
-            if *cancelled_clone.lock().unwrap() {
-                let _ = tx.send(Err("Download cancelled".to_string()));
-                break;
-            }
+/// Downloads can take far longer than any one Steam call, so the timeout only applies
+/// while the download makes no progress.
+pub async fn download_workshop_item_with_progress<F>(
+    steam_game_id: u32,
+    item_id: u64,
+    on_progress: F,
+) -> Result<(), S7Error>
+where
+    F: Fn(u64, u64) + Send + 'static,
+{
+    let backend = steam_manager::backend(steam_game_id)?;
 
-            if start_time.elapsed() > timeout {
-                let _ = tx.send(Err(format!(
-                    "Download timeout after {} minutes",
-                    timeout.as_secs() / 60
-                )));
-                break;
-            }
+    let state = backend.item_state(item_id);
+    if !state.contains(ItemState::SUBSCRIBED) {
+        return Err(S7Error::not_subscribed(item_id));
+    }
 
-            let ugc = steam_client.ugc();
-            let state = ugc.item_state(published_file_id);
+    backend.download_item(item_id, true);
 
-            if let Some((downloaded, total)) = ugc.item_download_info(published_file_id) {
-                if downloaded == total && total > 0 {
-                    let _ = tx.send(Ok(()));
-                    break;
-                }
+    let timeout = default_timeout();
+    let mut last_progress = None;
+    loop {
+        let step = pump_until(steam_game_id, timeout, || {
+            let progress = backend.item_download_info(item_id);
+            if let Some((downloaded, total)) = progress
+                && downloaded == total
+                && total > 0
+            {
+                on_progress(downloaded, total);
+                return Some(DownloadStep::Finished);
             }
 
+            let state = backend.item_state(item_id);
             if state.contains(ItemState::INSTALLED)
                 && state.contains(ItemState::SUBSCRIBED)
                 && !state.contains(ItemState::DOWNLOADING)
                 && !state.contains(ItemState::DOWNLOAD_PENDING)
             {
-                let _ = tx.send(Ok(()));
-                break;
+                return Some(DownloadStep::Finished);
             }
 
-            std::thread::sleep(Duration::from_millis(500));
-        }
-    });
+            if let Some((downloaded, total)) = progress
+                && progress != last_progress
+            {
+                last_progress = progress;
+                on_progress(downloaded, total);
+                return Some(DownloadStep::Progressed);
+            }
+            None
+        })
+        .await?;
 
-    match rx.await {
-        Ok(result) => result,
-        Err(_) => {
-            *cancelled.lock().unwrap() = true;
-            Err("Download monitoring failed unexpectedly".to_string())
+        match step {
+            Some(DownloadStep::Finished) => return Ok(()),
+            Some(DownloadStep::Progressed) => {}
+            None => {
+                return Err(S7Error::Timeout {
+                    seconds: timeout.as_secs(),
+                    message: format!(
+                        "Download of item {} made no progress for {} seconds",
+                        item_id,
+                        timeout.as_secs()
+                    ),
+                });
+            }
         }
     }
 }
@@ -0,0 +1,52 @@
+use serde::Serialize;
+
+use crate::commands::workshop_items::forget_cached_items;
+use crate::core::steam_manager;
+use crate::core::workshop_item::workshop::UserListType;
+use crate::error::S7Error;
+use crate::utils::check_item_owner::find_item;
+use crate::utils::user_lists::user_list_ids;
+
+#[derive(Debug, Serialize)]
+pub struct FavoriteResult {
+    pub item_id: u64,
+    pub favorited: bool,
+    /// False when the item already was, or was not, a favorite.
+    pub changed: bool,
+}
+
+pub async fn add_favorite(steam_game_id: u32, item_id: u64) -> Result<FavoriteResult, S7Error> {
+    set_favorite(steam_game_id, item_id, true).await
+}
+
+pub async fn remove_favorite(steam_game_id: u32, item_id: u64) -> Result<FavoriteResult, S7Error> {
+    set_favorite(steam_game_id, item_id, false).await
+}
+
+async fn set_favorite(
+    steam_game_id: u32,
+    item_id: u64,
+    favorite: bool,
+) -> Result<FavoriteResult, S7Error> {
+    let backend = steam_manager::backend(steam_game_id)?;
+    find_item(backend.as_ref(), item_id).await?;
+
+    let favorited = user_list_ids(backend.as_ref(), UserListType::Favorited)
+        .await?
+        .contains(&item_id);
+    let changed = favorited != favorite;
+    if changed {
+        if favorite {
+            backend.add_favorite(item_id).await?;
+        } else {
+            backend.remove_favorite(item_id).await?;
+        }
+        forget_cached_items(steam_game_id, &[item_id]);
+    }
+
+    Ok(FavoriteResult {
+        item_id,
+        favorited: favorite,
+        changed,
+    })
+}
@@ -1,12 +1,28 @@
+pub mod add_dependency;
 pub mod check_item_download;
 pub mod clear_cache;
+pub mod collection_create;
+pub mod collection_edit;
 pub mod collection_items;
+pub mod compat_report;
+pub mod delete_item;
 pub mod discover_tags;
 pub mod download_workshop_item;
+pub mod favorite;
+pub mod publish;
+#[cfg(feature = "manifest")]
+pub mod publish_manifest;
+pub mod remove_dependency;
+pub mod resolve_dependencies;
 pub mod search_workshop;
+pub mod set_visibility;
+pub mod steam_install_paths;
 pub mod steam_library_paths;
 pub mod subscribe;
 pub mod subscribed_items;
 pub mod unsubscribe;
+pub mod update_item;
+pub mod user_items;
+pub mod vote;
 pub mod workshop_items;
 pub mod workshop_path;
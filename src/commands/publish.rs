@@ -0,0 +1,171 @@
+use std::path::{Path, PathBuf};
+use steamworks::FileType;
+
+use crate::commands::workshop_items::forget_cached_items;
+use crate::core::steam_backend::{ItemUpdate, SubmittedItem};
+use crate::core::steam_manager;
+use crate::core::workshop::UgcItemVisibility;
+use crate::error::S7Error;
+
+// Limits from the Steamworks headers. The SDK rejects longer values, which the
+// steamworks crate turns into a panic, so they are checked before any call.
+const TITLE_MAX_BYTES: usize = 128;
+const DESCRIPTION_MAX_BYTES: usize = 7999;
+const TAG_MAX_CHARS: usize = 255;
+const METADATA_MAX_BYTES: usize = 4999;
+
+#[derive(Debug, Clone, Default)]
+pub struct PublishOptions {
+    pub title: String,
+    pub description: String,
+    pub content_folder: PathBuf,
+    pub preview_image: PathBuf,
+    pub visibility: UgcItemVisibility,
+    pub tags: Vec<String>,
+    pub change_note: Option<String>,
+}
+
+pub async fn publish(
+    steam_game_id: u32,
+    options: PublishOptions,
+) -> Result<SubmittedItem, S7Error> {
+    if options.title.trim().is_empty() {
+        return Err(S7Error::InvalidArgument(
+            "Title must not be empty".to_string(),
+        ));
+    }
+
+    let update = validate_item_update(ItemUpdate {
+        title: Some(options.title),
+        description: Some(options.description),
+        visibility: Some(options.visibility),
+        tags: Some(options.tags),
+        content_folder: Some(options.content_folder),
+        preview_image: Some(options.preview_image),
+        metadata: None,
+        change_note: options.change_note,
+    })?;
+
+    create_and_upload(steam_game_id, FileType::Community, update, |_| Ok(())).await
+}
+
+/// Creates an item of `file_type` and uploads `update` to it. `on_created` runs with the new ID
+/// before the upload starts, so callers can record it even if the upload fails.
+pub async fn create_and_upload<F>(
+    steam_game_id: u32,
+    file_type: FileType,
+    update: ItemUpdate,
+    on_created: F,
+) -> Result<SubmittedItem, S7Error>
+where
+    F: FnOnce(u64) -> Result<(), S7Error>,
+{
+    let backend = steam_manager::backend(steam_game_id)?;
+    let created = backend.create_item(file_type).await?;
+
+    on_created(created.published_file_id).map_err(|error| {
+        error.context(format!(
+            "Item {} was created but recording its ID failed",
+            created.published_file_id
+        ))
+    })?;
+
+    let submitted = backend
+        .update_item(created.published_file_id, update, Box::new(|_| {}))
+        .await
+        .map_err(|error| {
+            error.context(format!(
+                "Item {} was created but uploading its content failed",
+                created.published_file_id
+            ))
+        })?;
+
+    forget_cached_items(steam_game_id, &[created.published_file_id]);
+
+    Ok(SubmittedItem {
+        published_file_id: created.published_file_id,
+        needs_legal_agreement: created.needs_legal_agreement || submitted.needs_legal_agreement,
+    })
+}
+
+/// Checks an update against Steam's limits and resolves its paths to absolute ones,
+/// which Steam requires for uploads.
+pub fn validate_item_update(mut update: ItemUpdate) -> Result<ItemUpdate, S7Error> {
+    if let Some(title) = &update.title {
+        check_text("Title", title, TITLE_MAX_BYTES)?;
+    }
+    if let Some(description) = &update.description {
+        check_text("Description", description, DESCRIPTION_MAX_BYTES)?;
+    }
+    if let Some(metadata) = &update.metadata {
+        check_text("Metadata", metadata, METADATA_MAX_BYTES)?;
+    }
+    if let Some(change_note) = &update.change_note {
+        check_text("Change note", change_note, DESCRIPTION_MAX_BYTES)?;
+    }
+
+    if let Some(tags) = &update.tags {
+        for tag in tags {
+            if tag.is_empty()
+                || tag.chars().count() > TAG_MAX_CHARS
+                || tag.contains(',')
+                || tag.chars().any(char::is_control)
+            {
+                return Err(S7Error::InvalidArgument(format!(
+                    "Invalid tag '{}': tags must be 1 to {} printable characters without commas",
+                    tag, TAG_MAX_CHARS
+                )));
+            }
+        }
+    }
+
+    if let Some(content_folder) = update.content_folder.take() {
+        update.content_folder = Some(resolve_path("Content folder", &content_folder, true)?);
+    }
+    if let Some(preview_image) = update.preview_image.take() {
+        update.preview_image = Some(resolve_path("Preview image", &preview_image, false)?);
+    }
+
+    Ok(update)
+}
+
+fn check_text(name: &str, value: &str, max_bytes: usize) -> Result<(), S7Error> {
+    if value.len() > max_bytes {
+        return Err(S7Error::InvalidArgument(format!(
+            "{} is {} bytes long, the limit is {}",
+            name,
+            value.len(),
+            max_bytes
+        )));
+    }
+    if value.contains('\0') {
+        return Err(S7Error::InvalidArgument(format!(
+            "{} must not contain NUL characters",
+            name
+        )));
+    }
+    Ok(())
+}
+
+fn resolve_path(name: &str, path: &Path, is_dir: bool) -> Result<PathBuf, S7Error> {
+    let resolved = path.canonicalize().map_err(|e| {
+        S7Error::InvalidArgument(format!(
+            "{} {} is not accessible: {}",
+            name,
+            path.display(),
+            e
+        ))
+    })?;
+
+    if resolved.is_dir() != is_dir {
+        let expected = if is_dir { "a directory" } else { "a file" };
+        return Err(S7Error::InvalidArgument(format!(
+            "{} {} is not {}",
+            name,
+            path.display(),
+            expected
+        )));
+    }
+
+    Ok(resolved)
+}
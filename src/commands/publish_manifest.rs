@@ -0,0 +1,493 @@
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use steamworks::FileType;
+
+use crate::commands::publish::{create_and_upload, validate_item_update};
+use crate::commands::update_item::update_item;
+use crate::core::steam_backend::ItemUpdate;
+use crate::core::workshop::UgcItemVisibility;
+use crate::error::S7Error;
+use crate::utils::parse_date::format_date;
+
+/// The file names looked up when a directory is given instead of a manifest file.
+pub const MANIFEST_FILE_NAMES: [&str; 2] = ["workshop.toml", "workshop.json"];
+
+const TEMPLATE_VARIABLES: [&str; 2] = ["{version}", "{date}"];
+
+// The manifest as written on disk. Paths are relative to the manifest's directory.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestFile {
+    app_id: u32,
+    published_file_id: Option<u64>,
+    title: String,
+    description_file: Option<PathBuf>,
+    #[serde(default)]
+    tags: Vec<String>,
+    visibility: Option<String>,
+    preview_image: PathBuf,
+    content_dir: PathBuf,
+    #[serde(default)]
+    ignore: Vec<String>,
+    change_note: Option<String>,
+}
+
+/// A `workshop.toml` or `workshop.json` manifest that describes one workshop item.
+/// Loading it validates every field, so a manifest that loads can be published.
+#[derive(Debug, Clone)]
+pub struct WorkshopManifest {
+    /// The manifest file. `published_file_id` is written back to it after the first publish.
+    pub path: PathBuf,
+    pub app_id: u32,
+    pub published_file_id: Option<u64>,
+    pub title: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub visibility: UgcItemVisibility,
+    pub preview_image: PathBuf,
+    pub content_dir: PathBuf,
+    /// Glob patterns, relative to `content_dir`, of files that are not uploaded.
+    pub ignore: Vec<String>,
+    /// Change note with optional `{version}` and `{date}` placeholders.
+    pub change_note: Option<String>,
+}
+
+/// Per-release input that does not belong in the manifest.
+#[derive(Debug, Clone, Default)]
+pub struct ManifestRelease {
+    /// Replaces `{version}` in the manifest's change note.
+    pub version: Option<String>,
+    /// Used instead of the manifest's change note.
+    pub change_note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ManifestPublishResult {
+    pub published_file_id: u64,
+    /// True when this publish created the item and wrote its ID back to the manifest.
+    pub created: bool,
+    pub needs_legal_agreement: bool,
+}
+
+impl WorkshopManifest {
+    /// Reads and validates a manifest. `path` may also be a directory containing a
+    /// `workshop.toml` or `workshop.json`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, S7Error> {
+        let path = find_manifest(path.as_ref())?;
+        let text = fs::read_to_string(&path).map_err(|e| {
+            S7Error::InvalidArgument(format!(
+                "Manifest {} is not readable: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        let file: ManifestFile = if is_json(&path) {
+            serde_json::from_str(&text).map_err(|e| e.to_string())
+        } else {
+            toml::from_str(&text).map_err(|e| e.to_string())
+        }
+        .map_err(|e| {
+            S7Error::InvalidArgument(format!("Invalid manifest {}: {}", path.display(), e))
+        })?;
+
+        let base = path.parent().unwrap_or(Path::new("."));
+        let description = match &file.description_file {
+            Some(description_file) => {
+                let description_file = base.join(description_file);
+                fs::read_to_string(&description_file).map_err(|e| {
+                    S7Error::InvalidArgument(format!(
+                        "Description file {} is not readable: {}",
+                        description_file.display(),
+                        e
+                    ))
+                })?
+            }
+            None => String::new(),
+        };
+
+        let manifest = WorkshopManifest {
+            app_id: file.app_id,
+            published_file_id: file.published_file_id,
+            title: file.title,
+            description,
+            tags: file.tags,
+            visibility: match &file.visibility {
+                Some(visibility) => visibility.parse()?,
+                None => UgcItemVisibility::default(),
+            },
+            preview_image: base.join(file.preview_image),
+            content_dir: base.join(file.content_dir),
+            ignore: file.ignore,
+            change_note: file.change_note,
+            path,
+        };
+
+        manifest.validate().map_err(|error| {
+            error.context(format!("Invalid manifest {}", manifest.path.display()))
+        })?;
+        Ok(manifest)
+    }
+
+    fn validate(&self) -> Result<(), S7Error> {
+        if self.title.trim().is_empty() {
+            return Err(S7Error::InvalidArgument(
+                "title must not be empty".to_string(),
+            ));
+        }
+        if let Some(change_note) = &self.change_note {
+            check_template(change_note)?;
+        }
+        self.ignore_patterns()?;
+
+        let update = validate_item_update(self.item_update(None))?;
+        if let Some(content_dir) = &update.content_folder
+            && staged_files(content_dir, &self.ignore_patterns()?)?.is_empty()
+        {
+            return Err(S7Error::InvalidArgument(format!(
+                "content_dir {} has no files left to upload",
+                self.content_dir.display()
+            )));
+        }
+        Ok(())
+    }
+
+    /// The change note for a release: the release's own note if it has one, otherwise
+    /// the manifest's template with its placeholders filled in.
+    pub fn change_note_for(&self, release: &ManifestRelease) -> Result<Option<String>, S7Error> {
+        if release.change_note.is_some() {
+            return Ok(release.change_note.clone());
+        }
+        let Some(template) = &self.change_note else {
+            return Ok(None);
+        };
+
+        if template.contains("{version}") && release.version.is_none() {
+            return Err(S7Error::InvalidArgument(
+                "The manifest change note uses {version}, pass a version for this release"
+                    .to_string(),
+            ));
+        }
+
+        let note = template
+            .replace("{version}", release.version.as_deref().unwrap_or_default())
+            .replace("{date}", &utc_date());
+        Ok(Some(note))
+    }
+
+    fn item_update(&self, change_note: Option<String>) -> ItemUpdate {
+        ItemUpdate {
+            title: Some(self.title.clone()),
+            description: Some(self.description.clone()),
+            visibility: Some(self.visibility.clone()),
+            tags: Some(self.tags.clone()),
+            content_folder: Some(self.content_dir.clone()),
+            preview_image: Some(self.preview_image.clone()),
+            metadata: None,
+            change_note,
+        }
+    }
+
+    fn ignore_patterns(&self) -> Result<Vec<Pattern>, S7Error> {
+        self.ignore
+            .iter()
+            .map(|pattern| {
+                Pattern::new(pattern).map_err(|e| {
+                    S7Error::InvalidArgument(format!("Invalid ignore pattern '{}': {}", pattern, e))
+                })
+            })
+            .collect()
+    }
+
+    /// Stores `published_file_id` in the manifest file, keeping the rest of the file as
+    /// it is written.
+    pub fn record_published_file_id(&mut self, published_file_id: u64) -> Result<(), S7Error> {
+        let text = fs::read_to_string(&self.path)?;
+        let text = if is_json(&self.path) {
+            with_json_id(&text, published_file_id)?
+        } else {
+            with_toml_id(&text, published_file_id)?
+        };
+
+        write_replacing(&self.path, &text)?;
+        self.published_file_id = Some(published_file_id);
+        Ok(())
+    }
+}
+
+// Writes `text` to a new file next to `path` and renames it over `path`, so an
+// interrupted write never leaves a truncated manifest behind.
+fn write_replacing(path: &Path, text: &str) -> Result<(), S7Error> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+
+    let written = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(text.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&temp_path, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    Ok(written?)
+}
+
+/// Publishes the item a manifest describes. The first publish creates the item and
+/// records its ID in the manifest, later ones update that item.
+pub async fn publish_manifest(
+    manifest: &mut WorkshopManifest,
+    release: ManifestRelease,
+) -> Result<ManifestPublishResult, S7Error> {
+    let change_note = manifest.change_note_for(&release)?;
+    let mut update = validate_item_update(manifest.item_update(change_note))?;
+
+    let ignore = manifest.ignore_patterns()?;
+    let staging = match &update.content_folder {
+        Some(content_dir) if !ignore.is_empty() => Some(StagingDir::copy(content_dir, &ignore)?),
+        _ => None,
+    };
+    if let Some(staging) = &staging {
+        update.content_folder = Some(staging.path.clone());
+    }
+
+    match manifest.published_file_id {
+        Some(item_id) => {
+            let submitted = update_item(manifest.app_id, item_id, update, |_| {}).await?;
+            Ok(ManifestPublishResult {
+                published_file_id: item_id,
+                created: false,
+                needs_legal_agreement: submitted.needs_legal_agreement,
+            })
+        }
+        None => {
+            let submitted =
+                create_and_upload(manifest.app_id, FileType::Community, update, |item_id| {
+                    manifest.record_published_file_id(item_id)
+                })
+                .await?;
+            Ok(ManifestPublishResult {
+                published_file_id: submitted.published_file_id,
+                created: true,
+                needs_legal_agreement: submitted.needs_legal_agreement,
+            })
+        }
+    }
+}
+
+fn find_manifest(path: &Path) -> Result<PathBuf, S7Error> {
+    if !path.is_dir() {
+        return Ok(path.to_path_buf());
+    }
+
+    MANIFEST_FILE_NAMES
+        .iter()
+        .map(|name| path.join(name))
+        .find(|candidate| candidate.is_file())
+        .ok_or_else(|| {
+            S7Error::InvalidArgument(format!(
+                "No {} found in {}",
+                MANIFEST_FILE_NAMES.join(" or "),
+                path.display()
+            ))
+        })
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "json")
+}
+
+fn check_template(template: &str) -> Result<(), S7Error> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let placeholder = rest[start..]
+            .find('}')
+            .map(|end| &rest[start..start + end + 1])
+            .unwrap_or(&rest[start..]);
+        if !TEMPLATE_VARIABLES.contains(&placeholder) {
+            return Err(S7Error::InvalidArgument(format!(
+                "Unknown placeholder {} in change_note, supported ones are {}",
+                placeholder,
+                TEMPLATE_VARIABLES.join(", ")
+            )));
+        }
+        rest = &rest[start + placeholder.len()..];
+    }
+    Ok(())
+}
+
+// Replaces a top-level `published_file_id` line or adds one after `app_id`, so
+// comments and formatting in the rest of the file survive. Only plain `key = value`
+// lines before the first table are recognized, so the result is parsed again and a
+// key written any other way, like `"published_file_id" = 1`, is reported instead of
+// being duplicated.
+fn with_toml_id(text: &str, published_file_id: u64) -> Result<String, S7Error> {
+    let id_line = format!("published_file_id = {}", published_file_id);
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    let top_level = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with('['))
+        .unwrap_or(lines.len());
+    let key_line = |key: &str| {
+        lines[..top_level].iter().position(|line| {
+            line.trim_start()
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        })
+    };
+
+    match (key_line("published_file_id"), key_line("app_id")) {
+        (Some(index), _) => lines[index] = id_line,
+        (None, Some(index)) => lines.insert(index + 1, id_line),
+        (None, None) => lines.insert(0, id_line),
+    }
+
+    let updated = lines.join("\n") + "\n";
+    let recorded = toml::from_str::<toml::Value>(&updated)
+        .ok()
+        .and_then(|manifest| manifest.get("published_file_id")?.as_integer());
+    if recorded != i64::try_from(published_file_id).ok() {
+        return Err(S7Error::InvalidArgument(format!(
+            "Could not record the published_file_id in the manifest, add `published_file_id = {}` to it by hand",
+            published_file_id
+        )));
+    }
+    Ok(updated)
+}
+
+fn with_json_id(text: &str, published_file_id: u64) -> Result<String, S7Error> {
+    let Value::Object(fields) = serde_json::from_str(text)
+        .map_err(|e| S7Error::InvalidArgument(format!("Invalid manifest: {}", e)))?
+    else {
+        return Err(S7Error::InvalidArgument(
+            "Manifest must be a JSON object".to_string(),
+        ));
+    };
+
+    let mut updated = serde_json::Map::new();
+    for (key, value) in fields {
+        if key == "published_file_id" {
+            continue;
+        }
+        let is_app_id = key == "app_id";
+        updated.insert(key, value);
+        if is_app_id {
+            updated.insert("published_file_id".to_string(), published_file_id.into());
+        }
+    }
+    updated
+        .entry("published_file_id")
+        .or_insert(published_file_id.into());
+
+    Ok(serde_json::to_string_pretty(&updated).unwrap() + "\n")
+}
+
+// The files under `content_dir` that no ignore pattern matches, relative to it. A
+// pattern that matches a directory skips everything below it. Symlinked directories are
+// followed, but a directory already walked is not walked again, so links that loop
+// back don't recurse forever.
+fn staged_files(content_dir: &Path, ignore: &[Pattern]) -> Result<Vec<PathBuf>, S7Error> {
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    let mut visited = HashSet::from([fs::canonicalize(content_dir)?]);
+
+    while let Some(relative_dir) = pending.pop() {
+        for entry in fs::read_dir(content_dir.join(&relative_dir))? {
+            let entry = entry?;
+            let relative = relative_dir.join(entry.file_name());
+            let relative_str = relative.to_string_lossy().replace('\\', "/");
+            if ignore.iter().any(|pattern| pattern.matches(&relative_str)) {
+                continue;
+            }
+
+            let path = entry.path();
+            if path.is_dir() {
+                if visited.insert(fs::canonicalize(&path)?) {
+                    pending.push(relative);
+                }
+            } else {
+                files.push(relative);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+// Steam uploads a whole folder, so content with ignored files is copied to a
+// temporary folder first. It is removed again once the upload finished, or when
+// copying fails part way.
+struct StagingDir {
+    path: PathBuf,
+}
+
+impl StagingDir {
+    const MAX_ATTEMPTS: u32 = 100;
+
+    fn copy(content_dir: &Path, ignore: &[Pattern]) -> Result<Self, S7Error> {
+        let staging = Self::create()?;
+
+        for file in staged_files(content_dir, ignore)? {
+            let target = staging.path.join(&file);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(content_dir.join(&file), &target)?;
+        }
+
+        Ok(staging)
+    }
+
+    // The folder is created with `create_dir`, which fails if it exists, so it is never
+    // shared with another upload or a folder someone else placed in the temp directory.
+    fn create() -> Result<Self, S7Error> {
+        let temp_dir = std::env::temp_dir();
+        for attempt in 0..Self::MAX_ATTEMPTS {
+            let path = temp_dir.join(format!("s7forge-upload-{}-{}", std::process::id(), attempt));
+            match fs::create_dir(&path) {
+                Ok(()) => return Ok(StagingDir { path }),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => {
+                    return Err(S7Error::Io(format!(
+                        "Failed to create staging folder {}: {}",
+                        path.display(),
+                        e
+                    )));
+                }
+            }
+        }
+        Err(S7Error::Io(format!(
+            "Failed to create a staging folder in {}",
+            temp_dir.display()
+        )))
+    }
+}
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+// Today's UTC date as YYYY-MM-DD.
+fn utc_date() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    format_date(now)
+}
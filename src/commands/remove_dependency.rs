@@ -0,0 +1,95 @@
+use serde::Serialize;
+
+use crate::commands::add_dependency::{changed_before, unique_ids};
+use crate::commands::workshop_items::forget_cached_items;
+use crate::core::steam_manager;
+use crate::error::S7Error;
+use crate::utils::check_item_owner::check_item_owner;
+
+#[derive(Debug, Serialize)]
+pub struct RemoveDependencyResult {
+    pub item_id: u64,
+    pub removed: Vec<u64>,
+    pub required_items: Vec<u64>,
+    pub removed_apps: Vec<u32>,
+    pub required_apps: Vec<u32>,
+    pub warnings: Vec<String>,
+}
+
+pub async fn remove_dependency(
+    steam_game_id: u32,
+    item_id: u64,
+    dependency_ids: Vec<u64>,
+    app_dependency_ids: Vec<u32>,
+) -> Result<RemoveDependencyResult, S7Error> {
+    let dependency_ids = unique_ids(dependency_ids);
+    let app_dependency_ids = unique_ids(app_dependency_ids);
+    if dependency_ids.is_empty() && app_dependency_ids.is_empty() {
+        return Err(S7Error::InvalidArgument(
+            "No dependency IDs given".to_string(),
+        ));
+    }
+
+    let backend = steam_manager::backend(steam_game_id)?;
+    let mut required_items = check_item_owner(backend.as_ref(), item_id)
+        .await?
+        .required_items;
+
+    let mut warnings = Vec::new();
+    let mut to_remove = Vec::new();
+    for dependency_id in dependency_ids {
+        if required_items.contains(&dependency_id) {
+            to_remove.push(dependency_id);
+        } else {
+            warnings.push(format!(
+                "{} is not a dependency of {}",
+                dependency_id, item_id
+            ));
+        }
+    }
+
+    let changes = backend.remove_dependencies(item_id, to_remove).await?;
+    if !changes.applied.is_empty() {
+        forget_cached_items(steam_game_id, &[item_id]);
+    }
+    if let Some((dependency_id, error)) = changes.failed {
+        return Err(error.context(format!(
+            "Failed to remove {} as a dependency of {}{}",
+            dependency_id,
+            item_id,
+            changed_before("removing", &changes.applied)
+        )));
+    }
+    let removed = changes.applied;
+    required_items.retain(|id| !removed.contains(id));
+
+    let mut required_apps = backend.app_dependencies(item_id).await?;
+    let mut removed_apps = Vec::new();
+    for app_id in app_dependency_ids {
+        if !required_apps.contains(&app_id) {
+            warnings.push(format!("App {} is not a dependency of {}", app_id, item_id));
+            continue;
+        }
+
+        backend
+            .remove_app_dependency(item_id, app_id)
+            .await
+            .map_err(|error| {
+                error.context(format!(
+                    "Failed to remove app {} as a dependency of {}",
+                    app_id, item_id
+                ))
+            })?;
+        required_apps.retain(|&id| id != app_id);
+        removed_apps.push(app_id);
+    }
+
+    Ok(RemoveDependencyResult {
+        item_id,
+        removed,
+        required_items,
+        removed_apps,
+        required_apps,
+        warnings,
+    })
+}
@@ -0,0 +1,285 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
+use std::fmt::Write;
+
+use crate::commands::add_dependency::unique_ids;
+use crate::commands::workshop_items::{EnhancedWorkshopItem, workshop_items_of_type};
+use crate::core::workshop_item::workshop::UGCType;
+use crate::error::S7Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DependencyStatus {
+    Available,
+    /// Steam returned nothing for the ID, usually because the item was deleted or
+    /// made private.
+    Missing,
+    Banned,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DependencyNode {
+    pub published_file_id: u64,
+    pub title: Option<String>,
+    pub creator_name: Option<String>,
+    pub status: DependencyStatus,
+    pub required_items: Vec<u64>,
+    pub required_by: Vec<u64>,
+}
+
+/// The items `item_ids` require, directly or through other items.
+#[derive(Debug, Serialize)]
+pub struct DependencyGraph {
+    pub item_ids: Vec<u64>,
+    /// Every item reached, the requested ones first, then in the order they were found.
+    pub nodes: Vec<DependencyNode>,
+    /// The items to load, each after everything it requires. Items in a cycle are
+    /// placed next to each other, and missing items are left out.
+    pub load_order: Vec<u64>,
+    /// Groups of items that require each other, directly or through other items.
+    pub cycles: Vec<Vec<u64>>,
+    pub missing: Vec<u64>,
+    pub banned: Vec<u64>,
+    /// The graph in Graphviz DOT, when it was asked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dot: Option<String>,
+}
+
+impl DependencyGraph {
+    /// The graph in Graphviz DOT, with an edge from each item to the items it requires.
+    /// Requested items are boxes, missing ones dashed and banned ones red.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph dependencies {\n");
+        for node in &self.nodes {
+            let title = node
+                .title
+                .clone()
+                .unwrap_or_else(|| node.published_file_id.to_string());
+            let mut attributes = Vec::new();
+            match node.status {
+                DependencyStatus::Available => {
+                    attributes.push(format!("label=\"{}\"", escape_dot(&title)))
+                }
+                DependencyStatus::Missing => {
+                    attributes.push(format!("label=\"{} (missing)\"", escape_dot(&title)));
+                    attributes.push("style=dashed".to_string());
+                }
+                DependencyStatus::Banned => {
+                    attributes.push(format!("label=\"{} (banned)\"", escape_dot(&title)));
+                    attributes.push("color=red".to_string());
+                }
+            }
+            if self.item_ids.contains(&node.published_file_id) {
+                attributes.push("shape=box".to_string());
+            }
+            let _ = writeln!(
+                dot,
+                "    \"{}\" [{}];",
+                node.published_file_id,
+                attributes.join(", ")
+            );
+        }
+        for node in &self.nodes {
+            for required_id in &node.required_items {
+                let _ = writeln!(
+                    dot,
+                    "    \"{}\" -> \"{}\";",
+                    node.published_file_id, required_id
+                );
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Follows `required_items` from `item_ids` until every reachable item is known. Each
+/// round looks up all newly found dependencies together, through the item cache, which
+/// splits them into queries Steam accepts.
+/// With `include_dot`, the graph is also rendered into `dot`.
+pub async fn resolve_dependencies(
+    steam_game_id: u32,
+    item_ids: Vec<u64>,
+    include_dot: bool,
+) -> Result<DependencyGraph, S7Error> {
+    let item_ids = unique_ids(item_ids);
+    if item_ids.is_empty() {
+        return Err(S7Error::InvalidArgument("No item IDs given".to_string()));
+    }
+
+    let mut found: FxHashMap<u64, EnhancedWorkshopItem> = FxHashMap::default();
+    let mut order = Vec::new();
+    let mut visited: FxHashSet<u64> = item_ids.iter().copied().collect();
+    let mut frontier = item_ids.clone();
+
+    while !frontier.is_empty() {
+        let items = workshop_items_of_type(steam_game_id, frontier.clone(), UGCType::All)
+            .await?
+            .items;
+        for item in items {
+            found.insert(item.workshop_item.published_file_id, item);
+        }
+
+        let next: Vec<u64> = frontier
+            .iter()
+            .filter_map(|id| found.get(id))
+            .flat_map(|item| item.workshop_item.required_items.iter().copied())
+            .filter(|id| visited.insert(*id))
+            .collect();
+        order.append(&mut frontier);
+        frontier = next;
+    }
+
+    let edges: FxHashMap<u64, Vec<u64>> = found
+        .iter()
+        .map(|(id, item)| (*id, item.workshop_item.required_items.clone()))
+        .collect();
+    let mut required_by: FxHashMap<u64, Vec<u64>> = FxHashMap::default();
+    for id in &order {
+        for required_id in edges.get(id).into_iter().flatten() {
+            required_by.entry(*required_id).or_default().push(*id);
+        }
+    }
+
+    let nodes: Vec<DependencyNode> = order
+        .iter()
+        .map(|id| {
+            let item = found.get(id);
+            DependencyNode {
+                published_file_id: *id,
+                title: item.map(|item| item.workshop_item.title.clone()),
+                creator_name: item.map(|item| item.creator_name.clone()),
+                status: match item {
+                    None => DependencyStatus::Missing,
+                    Some(item) if item.workshop_item.banned => DependencyStatus::Banned,
+                    Some(_) => DependencyStatus::Available,
+                },
+                required_items: edges.get(id).cloned().unwrap_or_default(),
+                required_by: required_by.remove(id).unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    let components = strongly_connected_components(&item_ids, &edges);
+    let cycles = components
+        .iter()
+        .filter(|component| {
+            component.len() > 1
+                || edges
+                    .get(&component[0])
+                    .is_some_and(|edges| edges.contains(&component[0]))
+        })
+        .cloned()
+        .collect();
+    let ids_with_status = |status: DependencyStatus| -> Vec<u64> {
+        nodes
+            .iter()
+            .filter(|node| node.status == status)
+            .map(|node| node.published_file_id)
+            .collect()
+    };
+
+    let mut graph = DependencyGraph {
+        load_order: components
+            .into_iter()
+            .flatten()
+            .filter(|id| found.contains_key(id))
+            .collect(),
+        cycles,
+        missing: ids_with_status(DependencyStatus::Missing),
+        banned: ids_with_status(DependencyStatus::Banned),
+        item_ids,
+        nodes,
+        dot: None,
+    };
+    if include_dot {
+        graph.dot = Some(graph.to_dot());
+    }
+    Ok(graph)
+}
+
+// Tarjan's algorithm. A component is only finished after every component it requires,
+// so they come out in load order.
+fn strongly_connected_components(roots: &[u64], edges: &FxHashMap<u64, Vec<u64>>) -> Vec<Vec<u64>> {
+    let mut search = ComponentSearch {
+        edges,
+        index: FxHashMap::default(),
+        low_link: FxHashMap::default(),
+        stack: Vec::new(),
+        on_stack: FxHashSet::default(),
+        components: Vec::new(),
+    };
+    for &root in roots {
+        if !search.index.contains_key(&root) {
+            search.visit(root);
+        }
+    }
+    search.components
+}
+
+struct ComponentSearch<'a> {
+    edges: &'a FxHashMap<u64, Vec<u64>>,
+    index: FxHashMap<u64, usize>,
+    low_link: FxHashMap<u64, usize>,
+    stack: Vec<u64>,
+    on_stack: FxHashSet<u64>,
+    components: Vec<Vec<u64>>,
+}
+
+impl<'a> ComponentSearch<'a> {
+    // Walks depth first from `root` with its own stack of frames, each an item and the
+    // requirements not looked at yet, so a long chain of dependencies can't overflow
+    // the call stack.
+    fn visit(&mut self, root: u64) {
+        let mut frames = vec![(root, self.enter(root))];
+        while let Some((id, required)) = frames.last_mut() {
+            let id = *id;
+            if let Some(&required_id) = required.next() {
+                if !self.index.contains_key(&required_id) {
+                    frames.push((required_id, self.enter(required_id)));
+                } else if self.on_stack.contains(&required_id) {
+                    self.lower(id, self.index[&required_id]);
+                }
+                continue;
+            }
+
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                self.lower(parent, self.low_link[&id]);
+            }
+            if self.low_link[&id] == self.index[&id] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(&member);
+                    component.push(member);
+                    if member == id {
+                        break;
+                    }
+                }
+                component.reverse();
+                self.components.push(component);
+            }
+        }
+    }
+
+    // Numbers `id`, puts it on the stack and returns the items it requires.
+    fn enter(&mut self, id: u64) -> std::slice::Iter<'a, u64> {
+        let index = self.index.len();
+        self.index.insert(id, index);
+        self.low_link.insert(id, index);
+        self.stack.push(id);
+        self.on_stack.insert(id);
+        let edges = self.edges;
+        edges.get(&id).map(Vec::as_slice).unwrap_or_default().iter()
+    }
+
+    fn lower(&mut self, id: u64, reached: usize) {
+        if reached < self.low_link[&id] {
+            self.low_link.insert(id, reached);
+        }
+    }
+}
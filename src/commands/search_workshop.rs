@@ -1,36 +1,218 @@
-use bincode::{Decode, Encode};
-use futures_util::FutureExt;
+use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs;
-use std::path::PathBuf;
+use std::str::FromStr;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use steamworks::{AppIDs, AppId, UGCQueryType, UGCType};
-use tokio::sync::mpsc;
 
-use crate::commands::workshop_items::EnhancedWorkshopItem;
+use crate::commands::workshop_items::{
+    EnhancedWorkshopItem, FilterReason, FilteredItem, with_creator_names,
+};
+use crate::core::game_version::GameVersion;
+use crate::core::steam_backend::UgcQuery;
 use crate::core::steam_manager;
-use crate::core::workshop_item::workshop::{WorkshopItem, WorkshopItemsResult};
-use crate::utils::fetch_creator_names::fetch_creator_names;
-use crate::utils::get_cache_dir::get_cache_dir;
+use crate::core::workshop_item::workshop::{UGCQueryType, UGCType, WorkshopItem};
+use crate::error::S7Error;
+use crate::utils::disk_cache;
+use crate::utils::parse_date::DateRange;
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
 struct SearchCacheKey {
     steam_game_id: u32,
     search_text: String,
-    sort_by: String,
+    query_type: UGCQueryType,
     period: Option<String>,
     page: u32,
-    tags: Option<String>,
+    filter: SearchFilter,
 }
 
-#[derive(Debug, Clone, Serialize, Encode, Decode)]
+/// Everything besides text, sort order and paging that narrows a search.
+#[derive(Debug, Clone, Default, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
+pub struct SearchFilter {
+    pub ugc_type: UGCType,
+    pub tags: TagFilter,
+    pub created: DateRange,
+    pub updated: DateRange,
+    /// Keeps only items whose version tags cover this version.
+    pub game_version: Option<GameVersion>,
+}
+
+impl SearchFilter {
+    /// Why `item` fails one of the filters checked after Steam returns a page, if it
+    /// does. Steam knows nothing about game versions, and can return items of other
+    /// types than the one asked for.
+    pub fn rejection(&self, item: &WorkshopItem) -> Option<FilterReason> {
+        if !self.ugc_type.matches_file_type(&item.file_type) {
+            Some(FilterReason::Type)
+        } else if self
+            .game_version
+            .as_ref()
+            .is_some_and(|version| !item.supports_game_version(version))
+        {
+            Some(FilterReason::GameVersion)
+        } else {
+            None
+        }
+    }
+}
+
+/// Tag filters for a search. Items need all `required` tags, or any one of them with
+/// `match_any`, at least one tag from every group, and none of the `excluded` tags.
+#[derive(Debug, Clone, Default, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
+pub struct TagFilter {
+    pub required: Vec<String>,
+    pub excluded: Vec<String>,
+    pub match_any: bool,
+    pub groups: Vec<Vec<String>>,
+}
+
+impl TagFilter {
+    /// Builds a filter from comma-separated tag lists, with one list per group.
+    pub fn parse(
+        required: Option<&str>,
+        excluded: Option<&str>,
+        match_any: bool,
+        groups: &[String],
+    ) -> Self {
+        Self {
+            required: required.map(split_tags).unwrap_or_default(),
+            excluded: excluded.map(split_tags).unwrap_or_default(),
+            match_any,
+            groups: groups
+                .iter()
+                .map(|group| split_tags(group))
+                .filter(|group| !group.is_empty())
+                .collect(),
+        }
+    }
+}
+
+/// Sort names, with the Steam query each one runs.
+pub const SORT_ORDERS: &[(&str, UGCQueryType)] = &[
+    ("relevance", UGCQueryType::RankedByTextSearch),
+    ("recent", UGCQueryType::RankedByPublicationDate),
+    ("popular", UGCQueryType::RankedByTrend),
+    (
+        "most-subscribed",
+        UGCQueryType::RankedByTotalUniqueSubscriptions,
+    ),
+    ("recently-updated", UGCQueryType::RankedByLastUpdatedDate),
+    ("top-rated", UGCQueryType::RankedByVote),
+    ("most-votes-up", UGCQueryType::RankedByVotesUp),
+    ("fewest-votes", UGCQueryType::RankedByTotalVotesAsc),
+    ("not-yet-rated", UGCQueryType::NotYetRated),
+    (
+        "accepted-for-use",
+        UGCQueryType::AcceptedForGameRankedByAcceptanceDate,
+    ),
+    (
+        "favorited-by-friends",
+        UGCQueryType::FavoritedByFriendsRankedByPublicationDate,
+    ),
+    (
+        "created-by-friends",
+        UGCQueryType::CreatedByFriendsRankedByPublicationDate,
+    ),
+    (
+        "created-by-followed",
+        UGCQueryType::CreatedByFollowedUsersRankedByPublicationDate,
+    ),
+    ("most-reported", UGCQueryType::RankedByNumTimesReported),
+    ("playtime-trend", UGCQueryType::RankedByPlaytimeTrend),
+    ("total-playtime", UGCQueryType::RankedByTotalPlaytime),
+    (
+        "average-playtime-trend",
+        UGCQueryType::RankedByAveragePlaytimeTrend,
+    ),
+    (
+        "lifetime-average-playtime",
+        UGCQueryType::RankedByLifetimeAveragePlaytime,
+    ),
+    (
+        "playtime-sessions-trend",
+        UGCQueryType::RankedByPlaytimeSessionsTrend,
+    ),
+    (
+        "lifetime-playtime-sessions",
+        UGCQueryType::RankedByLifetimePlaytimeSessions,
+    ),
+];
+
+impl FromStr for UGCQueryType {
+    type Err = S7Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        SORT_ORDERS
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|(_, query_type)| *query_type)
+            .ok_or_else(|| {
+                S7Error::InvalidArgument(format!(
+                    "Invalid sort '{}'. Valid options: {}",
+                    value,
+                    option_names(SORT_ORDERS)
+                ))
+            })
+    }
+}
+
+const PERIODS: &[(&str, u32)] = &[
+    ("today", 1),
+    ("one-week", 7),
+    ("three-months", 90),
+    ("six-months", 180),
+    ("one-year", 365),
+];
+
+fn split_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(|s| s.trim())
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| tag.to_string())
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
 struct CachedSearchResult {
-    items: Vec<EnhancedWorkshopItem>,
+    // Raw items only: creator names and display tags are added on every search.
+    items: Vec<WorkshopItem>,
+    filtered: Vec<FilteredItem>,
+    total_results: u32,
+    returned_results: u32,
     timestamp: u64,
 }
 
-#[derive(Debug, Default, Serialize, Encode, Decode)]
+/// Steam returns up to this many items per page of a UGC query.
+pub const SEARCH_PAGE_SIZE: u32 = 50;
+
+/// How many pages a search fetches at most when asked for all of them. `has_more` stays
+/// set when the limit cuts a search short, so it can be continued from the next page.
+pub const ALL_PAGES_LIMIT: u32 = 100;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchPage {
+    /// How many items Steam matches across all pages, before the filters it cannot
+    /// apply itself. `has_more` and paging follow this count.
+    pub total_results: u32,
+    /// How many items Steam returned on the pages included, before those filters.
+    pub returned_results: u32,
+    /// How many of them passed the filters and are in `items`.
+    pub matching_results: u32,
+    /// The last page included, so the next one to ask for is `page + 1`.
+    pub page: u32,
+    pub page_size: u32,
+    pub has_more: bool,
+    pub items: Vec<EnhancedWorkshopItem>,
+    /// Items Steam returned that were left out by the type or game version filters,
+    /// each with the reason.
+    pub filtered: Vec<FilteredItem>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
 struct SearchCache {
     entries: HashMap<SearchCacheKey, CachedSearchResult>,
 }
@@ -38,52 +220,18 @@ struct SearchCache {
 impl SearchCache {
     const CACHE_DURATION_MINUTES: u64 = 10;
 
-    fn load_from_disk() -> Self {
-        match Self::get_cache_file_path() {
-            Ok(cache_path) => {
-                if cache_path.exists() {
-                    match fs::read(&cache_path) {
-                        Ok(data) => {
-                            let config = bincode::config::standard();
-                            match bincode::decode_from_slice(&data, config) {
-                                Ok((cache, _)) => {
-                                    let mut cleaned_cache: SearchCache = cache;
-                                    cleaned_cache.clean_expired_entries();
-                                    return cleaned_cache;
-                                }
-                                Err(e) => {
-                                    eprintln!("Failed to decode search cache: {}", e);
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("Failed to read search cache file: {}", e);
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                eprintln!("Failed to get cache file path: {}", e);
-            }
-        }
-        Self::default()
-    }
-
-    fn save_to_disk(&self) -> Result<(), String> {
-        let cache_path = Self::get_cache_file_path()?;
-        let config = bincode::config::standard();
-        let encoded = bincode::encode_to_vec(self, config)
-            .map_err(|e| format!("Failed to encode search cache: {}", e))?;
-
-        fs::write(&cache_path, encoded)
-            .map_err(|e| format!("Failed to write search cache to disk: {}", e))?;
-
-        Ok(())
-    }
+    // Renamed when the cached entries change shape, so old files are not misread.
+    const CACHE_FILE: &str = "search_workshop_cache_v15.bin";
 
-    fn get_cache_file_path() -> Result<PathBuf, String> {
-        let cache_dir = get_cache_dir()?;
-        Ok(cache_dir.join("search_workshop_cache.bin"))
+    // The cache only saves Steam calls, so an unreadable file is treated as empty, as
+    // the item and creator name caches do.
+    fn load_from_disk() -> Self {
+        let mut cache = disk_cache::load::<SearchCache>(Self::CACHE_FILE)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        cache.clean_expired_entries();
+        cache
     }
 
     fn clean_expired_entries(&mut self) {
@@ -102,7 +250,7 @@ impl SearchCache {
             .as_secs()
     }
 
-    fn get(&mut self, key: &SearchCacheKey) -> Option<Vec<EnhancedWorkshopItem>> {
+    fn get(&mut self, key: &SearchCacheKey) -> Option<CachedSearchResult> {
         self.clean_expired_entries();
 
         if let Some(cached_result) = self.entries.get(key) {
@@ -110,7 +258,7 @@ impl SearchCache {
             let expiry_duration_secs = Self::CACHE_DURATION_MINUTES * 60;
 
             if now.saturating_sub(cached_result.timestamp) < expiry_duration_secs {
-                return Some(cached_result.items.clone());
+                return Some(cached_result.clone());
             } else {
                 self.entries.remove(key);
             }
@@ -118,176 +266,189 @@ impl SearchCache {
         None
     }
 
-    fn insert(&mut self, key: SearchCacheKey, items: Vec<EnhancedWorkshopItem>) {
-        let cached_result = CachedSearchResult {
-            items,
-            timestamp: Self::current_timestamp(),
-        };
-        self.entries.insert(key, cached_result);
+    // Other processes may have cached pages since this cache was loaded, so the entry is
+    // added to the file as it is now.
+    fn insert(&mut self, key: SearchCacheKey, cached_result: CachedSearchResult) {
+        self.entries.insert(key.clone(), cached_result.clone());
 
-        self.clean_expired_entries();
-
-        if let Err(e) = self.save_to_disk() {
-            eprintln!("Warning: Failed to save search cache to disk: {}", e);
-        }
+        let _ = disk_cache::update(Self::CACHE_FILE, |cache: &mut SearchCache| {
+            cache.entries.insert(key, cached_result);
+            cache.clean_expired_entries();
+            true
+        });
     }
 }
 
+/// Searches from `page` on, fetching up to `max_pages` pages (all remaining pages, up to
+/// [`ALL_PAGES_LIMIT`], when `None`) and merging their items. Fetching stops early at the
+/// last page or at a page Steam returns empty. Each page is cached separately.
 pub async fn search_workshop(
     steam_game_id: u32,
     search_text: String,
-    sort_by: String,
+    query_type: UGCQueryType,
     period: Option<String>,
     page: u32,
-    tags: Option<String>,
-) -> Result<Vec<EnhancedWorkshopItem>, String> {
+    filter: SearchFilter,
+    max_pages: Option<u32>,
+) -> Result<SearchPage, S7Error> {
     if page == 0 {
-        return Err("Page number must be at least 1".to_string());
+        return Err(S7Error::InvalidArgument(
+            "Page number must be at least 1".to_string(),
+        ));
     }
-    let cache_key = SearchCacheKey {
-        steam_game_id,
-        search_text: search_text.clone(),
-        sort_by: sort_by.clone(),
-        period: period.clone(),
-        page,
-        tags: tags.clone(),
-    };
-
-    let mut cache = SearchCache::load_from_disk();
-    if let Some(cached_result) = cache.get(&cache_key) {
-        return Ok(cached_result);
+    if max_pages == Some(0) {
+        return Err(S7Error::InvalidArgument(
+            "Max pages must be at least 1".to_string(),
+        ));
+    }
+    if filter.tags.match_any && filter.tags.required.is_empty() {
+        return Err(S7Error::InvalidArgument(
+            "Matching any tag needs at least one required tag".to_string(),
+        ));
     }
 
-    let steam_client = steam_manager::initialize_client(steam_game_id).await?;
+    let mut query = UgcQuery::new(query_type, page);
+    query.ugc_type = filter.ugc_type;
+
+    if !search_text.trim().is_empty() {
+        query.search_text = Some(search_text.clone());
+    }
+    if is_trend(query_type) {
+        let period_str = period.as_deref().unwrap_or("one-week");
+        let trend_days = PERIODS
+            .iter()
+            .find(|(name, _)| *name == period_str)
+            .map(|(_, days)| *days)
+            .ok_or_else(|| {
+                S7Error::InvalidArgument(format!(
+                    "Invalid period '{}'. Valid options: {}",
+                    period_str,
+                    option_names(PERIODS)
+                ))
+            })?;
+        query.trend_days = Some(trend_days);
+    } else if period.is_some() {
+        return Err(S7Error::InvalidArgument(
+            "Period filter is only applicable to trend sort types".to_string(),
+        ));
+    }
 
-    let (tx, mut rx) = mpsc::channel(32);
+    query.required_tags = filter.tags.required.clone();
+    query.excluded_tags = filter.tags.excluded.clone();
+    query.match_any_tag = filter.tags.match_any;
+    query.required_tag_groups = filter.tags.groups.clone();
+    query.created = filter.created;
+    query.updated = filter.updated;
 
-    let search_task = tokio::task::spawn_blocking(move || {
-        let ugc = steam_client.ugc();
-        let (tx_inner, rx_inner) = std::sync::mpsc::channel();
-        let app_ids = AppIDs::Both {
-            creator: AppId(steam_game_id),
-            consumer: AppId(steam_game_id),
+    let mut cache = SearchCache::load_from_disk();
+    let mut result = SearchPage {
+        total_results: 0,
+        returned_results: 0,
+        matching_results: 0,
+        page,
+        page_size: SEARCH_PAGE_SIZE,
+        has_more: false,
+        items: Vec::new(),
+        filtered: Vec::new(),
+    };
+    let mut items = Vec::new();
+    let mut seen = FxHashSet::default();
+    let last_page = page.saturating_add(max_pages.unwrap_or(ALL_PAGES_LIMIT) - 1);
+
+    for current_page in page..=last_page {
+        let cache_key = SearchCacheKey {
+            steam_game_id,
+            search_text: search_text.clone(),
+            query_type,
+            period: period.clone(),
+            page: current_page,
+            filter: filter.clone(),
         };
-        let query_type = match sort_by.as_str() {
-            "relevance" => UGCQueryType::RankedByTextSearch,
-            "recent" => UGCQueryType::RankedByPublicationDate,
-            "popular" => UGCQueryType::RankedByTrend,
-            "most-subscribed" => UGCQueryType::RankedByTotalUniqueSubscriptions,
-            "recently-updated" => UGCQueryType::RankedByLastUpdatedDate,
-            _ => UGCQueryType::RankedByTextSearch,
+        query.page = current_page;
+        let (page_result, page_is_empty) = match cache.get(&cache_key) {
+            Some(cached_result) => (cached_result, false),
+            None => fetch_page(&mut cache, steam_game_id, cache_key, query.clone()).await?,
         };
 
-        let query_handle = ugc
-            .query_all(query_type, UGCType::Items, app_ids, page)
-            .map_err(|e| format!("Failed to create search query: {:?}", e))?;
-
-        let mut configured_query = query_handle
-            .set_return_metadata(true)
-            .set_return_children(true)
-            .set_return_additional_previews(true)
-            .set_return_key_value_tags(true);
-
-        if !search_text.trim().is_empty() {
-            configured_query = configured_query.set_search_text(&search_text);
+        result.total_results = page_result.total_results;
+        result.returned_results += page_result.returned_results;
+        result.page = current_page;
+        result.has_more = u64::from(current_page) * u64::from(SEARCH_PAGE_SIZE)
+            < u64::from(page_result.total_results);
+        // Rankings can shift between requests, so an item may show up on two pages.
+        items.extend(
+            page_result
+                .items
+                .into_iter()
+                .filter(|item| seen.insert(item.published_file_id)),
+        );
+        result.filtered.extend(
+            page_result
+                .filtered
+                .into_iter()
+                .filter(|item| seen.insert(item.published_file_id)),
+        );
+
+        if !result.has_more || page_is_empty {
+            break;
         }
-        if query_type == UGCQueryType::RankedByTrend {
-            let period_str = period.as_deref().unwrap_or("one-week");
-            let trend_days = match period_str {
-                "today" => 1,
-                "one-week" => 7,
-                "three-months" => 90,
-                "six-months" => 180,
-                "one-year" => 365,
-                _ => 7,
-            };
-            configured_query = configured_query.set_ranked_by_trend_days(trend_days);
-        } else if period.is_some() {
-            return Err("Period filter is only applicable for popular sort type".to_string());
-        }
-
-        if let Some(ref tag_filter) = tags {
-            let tag_list: Vec<&str> = tag_filter.split(',').map(|s| s.trim()).collect();
-            for tag in tag_list {
-                if !tag.is_empty() {
-                    configured_query = configured_query.add_required_tag(tag);
-                }
-            }
-        }
-
-        configured_query.fetch(move |fetch_result| {
-            let _ = tx_inner.send(
-                fetch_result
-                    .map(|query_results| WorkshopItemsResult::from_query_results(query_results))
-                    .map_err(|e| format!("Steam API error: {:?}", e)),
-            );
-        });
-
-        let start_time = std::time::Instant::now();
-        let timeout_duration = std::time::Duration::from_secs(30);
-
-        loop {
-            let _ = tx.blocking_send(());
-            if let Ok(result) = rx_inner.try_recv() {
-                return result;
-            }
+    }
 
-            if start_time.elapsed() > timeout_duration {
-                return Err("Search operation timed out waiting for Steam response".to_string());
-            }
+    let backend = steam_manager::backend(steam_game_id)?;
+    result.items = with_creator_names(steam_game_id, backend.as_ref(), items).await?;
+    result.matching_results = result.items.len() as u32;
+    Ok(result)
+}
 
-            std::thread::sleep(std::time::Duration::from_millis(10));
-        }
-    });
+// Steam only applies the trend period to the sorts ranked over recent days.
+fn is_trend(query_type: UGCQueryType) -> bool {
+    matches!(
+        query_type,
+        UGCQueryType::RankedByTrend
+            | UGCQueryType::RankedByPlaytimeTrend
+            | UGCQueryType::RankedByAveragePlaytimeTrend
+            | UGCQueryType::RankedByPlaytimeSessionsTrend
+    )
+}
 
-    let mut search_result = None;
-    let mut fused_task = search_task.fuse();
+fn option_names<T>(options: &[(&str, T)]) -> String {
+    options
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
-    while search_result.is_none() {
-        tokio::select! {
-            Some(_) = rx.recv() => {
-                steam_manager::run_callbacks(steam_game_id)?;
-            }
-            task_result = &mut fused_task => {
-                search_result = Some(task_result.map_err(|e| format!("Task error: {:?}", e))??);
-                break;
-            }
+// Also returns whether Steam had no items at all for the page, which is not cached.
+async fn fetch_page(
+    cache: &mut SearchCache,
+    steam_game_id: u32,
+    cache_key: SearchCacheKey,
+    query: UgcQuery,
+) -> Result<(CachedSearchResult, bool), S7Error> {
+    let backend = steam_manager::backend(steam_game_id)?;
+    let items_result = backend.query_all(query).await?;
+    let page_is_empty = items_result.items.is_empty();
+
+    let mut workshop_items = Vec::new();
+    let mut filtered = Vec::new();
+    for item in items_result.items.into_iter().flatten() {
+        match cache_key.filter.rejection(&item) {
+            Some(reason) => filtered.push(FilteredItem::new(&item, reason)),
+            None => workshop_items.push(item),
         }
     }
 
-    let items_result = search_result.unwrap();
-    let workshop_items = items_result
-        .items
-        .into_iter()
-        .filter_map(|item| match item {
-            Some(it) if it.file_type == "Community" => Some(it),
-            _ => None,
-        })
-        .collect::<Vec<WorkshopItem>>();
-
-    if workshop_items.is_empty() {
-        return Ok(Vec::new());
+    let page_result = CachedSearchResult {
+        returned_results: (workshop_items.len() + filtered.len()) as u32,
+        items: workshop_items,
+        filtered,
+        total_results: items_result.total_results,
+        timestamp: SearchCache::current_timestamp(),
+    };
+    if !page_is_empty {
+        cache.insert(cache_key, page_result.clone());
     }
 
-    let creator_ids: Vec<steamworks::SteamId> = workshop_items
-        .iter()
-        .map(|item| steamworks::SteamId::from_raw(item.owner.steam_id64))
-        .collect();
-
-    let creator_names = fetch_creator_names(creator_ids, steam_game_id).await?;
-
-    let result: Vec<EnhancedWorkshopItem> = workshop_items
-        .into_iter()
-        .map(|item| {
-            let creator_name = creator_names
-                .get(&item.owner.steam_id64)
-                .cloned()
-                .unwrap_or_else(|| "[unknown]".to_string());
-            EnhancedWorkshopItem::new(item, creator_name)
-        })
-        .collect();
-
-    cache.insert(cache_key, result.clone());
-
-    Ok(result)
+    Ok((page_result, page_is_empty))
 }
@@ -0,0 +1,45 @@
+use serde::Serialize;
+
+use crate::commands::workshop_items::forget_cached_items;
+use crate::core::steam_backend::ItemUpdate;
+use crate::core::steam_manager;
+use crate::core::workshop::UgcItemVisibility;
+use crate::error::S7Error;
+use crate::utils::check_item_owner::check_item_owner;
+
+#[derive(Debug, Serialize)]
+pub struct SetVisibilityResult {
+    pub item_id: u64,
+    pub previous_visibility: UgcItemVisibility,
+    pub visibility: UgcItemVisibility,
+}
+
+pub async fn set_visibility(
+    steam_game_id: u32,
+    item_id: u64,
+    visibility: UgcItemVisibility,
+) -> Result<SetVisibilityResult, S7Error> {
+    let backend = steam_manager::backend(steam_game_id)?;
+    let item = check_item_owner(backend.as_ref(), item_id).await?;
+
+    if item.visibility != visibility {
+        let update = ItemUpdate {
+            visibility: Some(visibility.clone()),
+            ..ItemUpdate::default()
+        };
+        backend
+            .update_item(item_id, update, Box::new(|_| {}))
+            .await
+            .map_err(|error| {
+                error.context(format!("Failed to change visibility of item {}", item_id))
+            })?;
+
+        forget_cached_items(steam_game_id, &[item_id]);
+    }
+
+    Ok(SetVisibilityResult {
+        item_id,
+        previous_visibility: item.visibility,
+        visibility,
+    })
+}
@@ -0,0 +1,16 @@
+use crate::core::steam_install_paths::{
+    SteamInstallPath, steam_install_paths as probe_install_paths,
+};
+use crate::error::S7Error;
+
+pub fn steam_install_paths() -> Result<Vec<SteamInstallPath>, S7Error> {
+    let paths = probe_install_paths()?;
+    if paths.is_empty() {
+        return Err(S7Error::NotFound(
+            "No Steam installation found. Set STEAM_ROOT to point at your Steam directory"
+                .to_string(),
+        ));
+    }
+
+    Ok(paths)
+}
@@ -1,23 +1,23 @@
-use steamworks::PublishedFileId;
-use tokio::task;
-
 use crate::commands::workshop_items::{EnhancedWorkshopItem, workshop_items};
+use crate::core::game_version::GameVersion;
 use crate::core::steam_manager;
+use crate::error::S7Error;
 
-pub async fn subscribed_items(steam_game_id: u32) -> Result<Vec<EnhancedWorkshopItem>, String> {
-    let steam_client = steam_manager::initialize_client(steam_game_id).await?;
-
-    let subscribed_items: Vec<PublishedFileId> = task::spawn_blocking({
-        let steam_client = steam_client.clone();
-        move || steam_client.ugc().subscribed_items()
-    })
-    .await
-    .map_err(|e| format!("Failed to fetch subscribed items: {:?}", e))?;
+/// The subscribed items, only those whose version tags cover `game_version` when set.
+pub async fn subscribed_items(
+    steam_game_id: u32,
+    game_version: Option<&GameVersion>,
+) -> Result<Vec<EnhancedWorkshopItem>, S7Error> {
+    let backend = steam_manager::backend(steam_game_id)?;
 
-    let item_ids: Vec<u64> = subscribed_items.iter().map(|id| id.0).collect();
+    let item_ids: Vec<u64> = backend.subscribed_items();
     if item_ids.is_empty() {
         return Ok(Vec::new());
     }
 
-    workshop_items(steam_game_id, item_ids).await
+    let mut items = workshop_items(steam_game_id, item_ids).await?;
+    if let Some(game_version) = game_version {
+        items.retain(|item| item.workshop_item.supports_game_version(game_version));
+    }
+    Ok(items)
 }
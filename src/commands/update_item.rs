@@ -0,0 +1,48 @@
+use crate::commands::publish::validate_item_update;
+use crate::commands::workshop_items::forget_cached_items;
+use crate::core::steam_backend::{ItemUpdate, SubmittedItem};
+use crate::core::steam_manager;
+use crate::core::workshop::UpdateProgress;
+use crate::error::S7Error;
+use crate::utils::check_item_owner::check_item_owner;
+
+pub async fn update_item<F>(
+    steam_game_id: u32,
+    item_id: u64,
+    update: ItemUpdate,
+    on_progress: F,
+) -> Result<SubmittedItem, S7Error>
+where
+    F: Fn(UpdateProgress) + Send + 'static,
+{
+    let update = validate_item_update(update)?;
+    if is_empty(&update) {
+        return Err(S7Error::InvalidArgument(
+            "Nothing to update: pass at least one field to change".to_string(),
+        ));
+    }
+
+    let backend = steam_manager::backend(steam_game_id)?;
+    check_item_owner(backend.as_ref(), item_id).await?;
+
+    let submitted = backend
+        .update_item(item_id, update, Box::new(on_progress))
+        .await
+        .map_err(|error| error.context(format!("Failed to update item {}", item_id)))?;
+
+    forget_cached_items(steam_game_id, &[item_id]);
+
+    Ok(submitted)
+}
+
+// A change note alone is still a valid update: it records a new version entry.
+fn is_empty(update: &ItemUpdate) -> bool {
+    update.title.is_none()
+        && update.description.is_none()
+        && update.visibility.is_none()
+        && update.tags.is_none()
+        && update.content_folder.is_none()
+        && update.preview_image.is_none()
+        && update.metadata.is_none()
+        && update.change_note.is_none()
+}
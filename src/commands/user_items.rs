@@ -0,0 +1,128 @@
+use steamworks::SteamId;
+
+use crate::commands::workshop_items::{EnhancedWorkshopItem, with_creator_names};
+use crate::core::steam_backend::UserQuery;
+use crate::core::steam_manager;
+use crate::core::workshop_item::workshop::{UGCType, UserListOrder, UserListType, WorkshopItem};
+use crate::error::S7Error;
+
+pub async fn user_items(
+    steam_game_id: u32,
+    list: String,
+    order: Option<String>,
+    page: u32,
+    user: Option<String>,
+) -> Result<Vec<EnhancedWorkshopItem>, S7Error> {
+    if page == 0 {
+        return Err(S7Error::InvalidArgument(
+            "Page number must be at least 1".to_string(),
+        ));
+    }
+
+    let list_type = match list.as_str() {
+        "published" => UserListType::Published,
+        "favorited" => UserListType::Favorited,
+        "voted-on" => UserListType::VotedOn,
+        "voted-up" => UserListType::VotedUp,
+        "voted-down" => UserListType::VotedDown,
+        "followed" => UserListType::Followed,
+        "subscribed" => UserListType::Subscribed,
+        _ => {
+            return Err(S7Error::InvalidArgument(format!(
+                "Unknown list: {}. Expected published, favorited, voted-on, voted-up, voted-down, followed or subscribed",
+                list
+            )));
+        }
+    };
+
+    let default_order = if list_type == UserListType::Subscribed {
+        "recently-subscribed"
+    } else {
+        "newest"
+    };
+    let order = order.as_deref().unwrap_or(default_order);
+    let order = match order {
+        "newest" => UserListOrder::CreationOrderDesc,
+        "oldest" => UserListOrder::CreationOrderAsc,
+        "title" => UserListOrder::TitleAsc,
+        "recently-updated" => UserListOrder::LastUpdatedDesc,
+        "recently-subscribed" if list_type == UserListType::Subscribed => {
+            UserListOrder::SubscriptionDateDesc
+        }
+        "recently-subscribed" => {
+            return Err(S7Error::InvalidArgument(
+                "Order recently-subscribed only applies to the subscribed list".to_string(),
+            ));
+        }
+        "vote-score" => UserListOrder::VoteScoreDesc,
+        "for-moderation" => UserListOrder::ForModeration,
+        _ => {
+            return Err(S7Error::InvalidArgument(format!(
+                "Unknown order: {}. Expected newest, oldest, title, recently-updated, recently-subscribed, vote-score or for-moderation",
+                order
+            )));
+        }
+    };
+
+    let backend = steam_manager::backend(steam_game_id)?;
+    let account_id = match user {
+        Some(user) => parse_account_id(&user)?,
+        None => backend.local_user().account_id,
+    };
+
+    let items_result = backend
+        .query_user(UserQuery {
+            account_id,
+            list: list_type,
+            order,
+            ugc_type: UGCType::Items,
+            page,
+        })
+        .await?;
+    let workshop_items = items_result
+        .items
+        .into_iter()
+        .filter_map(|item| match item {
+            Some(it) if it.file_type == "Community" => Some(it),
+            _ => None,
+        })
+        .collect::<Vec<WorkshopItem>>();
+
+    if workshop_items.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    with_creator_names(steam_game_id, backend.as_ref(), workshop_items).await
+}
+
+/// Accepts a SteamID64, `STEAM_X:Y:Z`, `[U:1:N]` or a bare account ID.
+fn parse_account_id(user: &str) -> Result<u32, S7Error> {
+    let invalid = || S7Error::InvalidArgument(format!("Invalid Steam user: {}", user));
+    let user = user.trim();
+
+    if let Some(steam_id32) = user.strip_prefix("STEAM_") {
+        let parts: Vec<&str> = steam_id32.split(':').collect();
+        let [_, low_bit, high_bits] = parts.as_slice() else {
+            return Err(invalid());
+        };
+        let low_bit: u32 = low_bit.parse().map_err(|_| invalid())?;
+        let high_bits: u32 = high_bits.parse().map_err(|_| invalid())?;
+        if low_bit > 1 {
+            return Err(invalid());
+        }
+        return high_bits
+            .checked_mul(2)
+            .map(|account_id| account_id + low_bit)
+            .ok_or_else(invalid);
+    }
+
+    if let Some(steam_id3) = user
+        .strip_prefix("[U:1:")
+        .and_then(|id| id.strip_suffix(']'))
+    {
+        return steam_id3.parse().map_err(|_| invalid());
+    }
+
+    let id: u64 = user.parse().map_err(|_| invalid())?;
+    Ok(u32::try_from(id).unwrap_or_else(|_| SteamId::from_raw(id).account_id().raw()))
+}
@@ -0,0 +1,47 @@
+use serde::Serialize;
+
+use crate::commands::workshop_items::forget_cached_items;
+use crate::core::steam_backend::SteamBackend;
+use crate::core::steam_manager;
+use crate::core::workshop::Vote;
+use crate::error::S7Error;
+use crate::utils::check_item_owner::find_item;
+
+#[derive(Debug, Serialize)]
+pub struct ItemVote {
+    pub item_id: u64,
+    /// `None` when the logged-in user has not voted on the item.
+    pub vote: Option<Vote>,
+}
+
+pub async fn vote(steam_game_id: u32, item_id: u64, vote: Vote) -> Result<ItemVote, S7Error> {
+    let backend = steam_manager::backend(steam_game_id)?;
+    find_item(backend.as_ref(), item_id).await?;
+
+    backend.set_item_vote(item_id, vote == Vote::Up).await?;
+    // The cached vote counts are stale now.
+    forget_cached_items(steam_game_id, &[item_id]);
+
+    Ok(ItemVote {
+        item_id,
+        vote: Some(vote),
+    })
+}
+
+pub async fn get_vote(steam_game_id: u32, item_id: u64) -> Result<ItemVote, S7Error> {
+    let backend = steam_manager::backend(steam_game_id)?;
+    find_item(backend.as_ref(), item_id).await?;
+
+    Ok(ItemVote {
+        item_id,
+        vote: user_vote(backend.as_ref(), item_id).await?,
+    })
+}
+
+/// The logged-in user's vote on an item.
+pub async fn user_vote(backend: &dyn SteamBackend, item_id: u64) -> Result<Option<Vote>, S7Error> {
+    Ok(backend
+        .item_vote(item_id)
+        .await?
+        .map(|vote_up| if vote_up { Vote::Up } else { Vote::Down }))
+}
@@ -1,188 +1,275 @@
-use bincode::{Decode, Encode};
-use std::fs;
-
-use futures_util::FutureExt;
 use rustc_hash::FxHashMap;
 use serde::Serialize;
-use steamworks::{PublishedFileId, SteamId};
 
+use crate::commands::vote::user_vote;
+use crate::core::steam_backend::SteamBackend;
 use crate::core::steam_manager;
-use crate::core::workshop_item::workshop::{WorkshopItem, WorkshopItemsResult};
+use crate::core::tag_rules::TagRules;
+use crate::core::workshop::Vote;
+use crate::core::workshop_item::workshop::{UGCType, UserListType, WorkshopItem};
+use crate::error::S7Error;
+use crate::utils::disk_cache;
 use crate::utils::fetch_creator_names::fetch_creator_names;
-use crate::utils::get_cache_dir::get_cache_dir;
+use crate::utils::user_lists::user_list_ids;
+
+// Items are cached per app. The version is bumped when `WorkshopItem` changes shape, so
+// old files are not misread.
+fn cache_file(steam_game_id: u32) -> String {
+    format!("workshop_items_cache_v7_{}.bin", steam_game_id)
+}
+
+// Steam returns at most 50 results for one details query.
+const ITEMS_PER_QUERY: usize = 50;
 
-#[derive(Debug, Encode, Decode)]
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
 pub struct WorkshopItemCache {
     pub items: FxHashMap<u64, WorkshopItem>,
 }
 
-#[derive(Debug, Clone, Serialize, Encode, Decode)]
+/// An item as it is shown: with its creator's name and its tags after the app's tag
+/// rules. Built fresh for every output and never cached, so rule changes apply at once.
+#[derive(Debug, Clone, Serialize)]
 pub struct EnhancedWorkshopItem {
     #[serde(flatten)]
     pub workshop_item: WorkshopItem,
     pub creator_name: String,
+    /// `tags` after the app's tag rules, for showing to users.
+    pub display_tags: Vec<String>,
 }
 
 impl EnhancedWorkshopItem {
-    pub fn new(workshop_item: WorkshopItem, creator_name: String) -> Self {
+    pub fn new(workshop_item: WorkshopItem, creator_name: String, tag_rules: &TagRules) -> Self {
         Self {
+            display_tags: tag_rules.display_tags(&workshop_item.tags),
             workshop_item,
             creator_name,
         }
     }
 }
 
+/// The logged-in user's vote and favorite on an item.
+#[derive(Debug, Clone, Serialize)]
+pub struct UserItemState {
+    pub vote: Option<Vote>,
+    pub favorited: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemWithUserState {
+    #[serde(flatten)]
+    pub item: EnhancedWorkshopItem,
+    pub user_state: UserItemState,
+}
+
+/// Why a found item was left out of the results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
+pub enum FilterReason {
+    /// Not of the requested type.
+    Type,
+    /// Has no version tag covering the requested game version.
+    GameVersion,
+}
+
+/// An item that was found but left out of the results.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
+pub struct FilteredItem {
+    pub published_file_id: u64,
+    pub file_type: String,
+    pub reason: FilterReason,
+}
+
+impl FilteredItem {
+    pub fn new(item: &WorkshopItem, reason: FilterReason) -> Self {
+        Self {
+            published_file_id: item.published_file_id,
+            file_type: item.file_type.clone(),
+            reason,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemsOfType<T> {
+    pub items: Vec<T>,
+    pub filtered: Vec<FilteredItem>,
+    /// IDs Steam returned no item for, usually because the item was deleted or made
+    /// private.
+    pub not_found: Vec<u64>,
+}
+
 pub async fn workshop_items(
     steam_game_id: u32,
     item_ids: Vec<u64>,
-) -> Result<Vec<EnhancedWorkshopItem>, String> {
+) -> Result<Vec<EnhancedWorkshopItem>, S7Error> {
+    workshop_items_of_type(steam_game_id, item_ids, UGCType::Items)
+        .await
+        .map(|result| result.items)
+}
+
+/// Looks up items by ID, keeping those of `ugc_type` and listing the others in
+/// `filtered` and the unknown IDs in `not_found`.
+pub async fn workshop_items_of_type(
+    steam_game_id: u32,
+    item_ids: Vec<u64>,
+    ugc_type: UGCType,
+) -> Result<ItemsOfType<EnhancedWorkshopItem>, S7Error> {
     if item_ids.is_empty() {
-        return Ok(Vec::new());
+        return Ok(ItemsOfType {
+            items: Vec::new(),
+            filtered: Vec::new(),
+            not_found: Vec::new(),
+        });
     }
 
-    let cache_dir = get_cache_dir()?;
-    fs::create_dir_all(&cache_dir)
-        .map_err(|e| format!("Failed to create cache directory: {:?}", e))?;
-
-    let cache_path = cache_dir.join("workshop_items_cache.bin");
-    let bincode_config = bincode::config::standard();
-
-    let mut cached_items: FxHashMap<u64, WorkshopItem> = FxHashMap::default();
-    if cache_path.exists() {
-        if let Ok(cache_content) = fs::read(&cache_path) {
-            if let Ok((cache_entry, _)) =
-                bincode::decode_from_slice::<WorkshopItemCache, _>(&cache_content, bincode_config)
-            {
-                cached_items = cache_entry.items;
-            }
-        }
-    }
+    let mut cached_items = disk_cache::load::<WorkshopItemCache>(&cache_file(steam_game_id))
+        .ok()
+        .flatten()
+        .map(|cache| cache.items)
+        .unwrap_or_default();
+
+    let backend = steam_manager::backend(steam_game_id)?;
 
     let ids_to_fetch: Vec<u64> = item_ids
         .iter()
         .filter(|id| !cached_items.contains_key(id))
         .cloned()
         .collect();
-    if ids_to_fetch.is_empty() {
-        let workshop_items: Vec<WorkshopItem> = item_ids
-            .iter()
-            .filter_map(|id| cached_items.get(id).cloned())
-            .collect();
-        let creator_ids: Vec<SteamId> = workshop_items
-            .iter()
-            .map(|item| SteamId::from_raw(item.owner.steam_id64))
-            .collect();
-
-        let creator_names = fetch_creator_names(creator_ids, steam_game_id).await?;
-
-        return Ok(workshop_items
+    let fetched = fetch_items(steam_game_id, backend.as_ref(), &ids_to_fetch).await?;
+    cached_items.extend(
+        fetched
             .into_iter()
-            .map(|item| {
-                let creator_name = creator_names
-                    .get(&item.owner.steam_id64)
-                    .cloned()
-                    .unwrap_or_else(|| "[unknown]".to_string());
-                EnhancedWorkshopItem::new(item, creator_name)
-            })
-            .collect());
-    }
+            .map(|item| (item.published_file_id, item)),
+    );
 
-    let steam_client = steam_manager::initialize_client(steam_game_id).await?;
-
-    let (tx, mut rx) = tokio::sync::mpsc::channel(32);
-    let items_task = tokio::task::spawn_blocking(move || {
-        let ugc = steam_client.ugc();
-        let (tx_inner, rx_inner) = std::sync::mpsc::channel();
-        let query_handle = ugc
-            .query_items(ids_to_fetch.iter().map(|id| PublishedFileId(*id)).collect())
-            .map_err(|e| format!("Failed to create query handle: {:?}", e))?;
-
-        query_handle
-            .include_children(true)
-            .fetch(move |fetch_result| {
-                let _ = tx_inner.send(
-                    fetch_result
-                        .map(|query_results| WorkshopItemsResult::from_query_results(query_results))
-                        .map_err(|e| format!("Steam API error: {:?}", e)),
-                );
-            });
+    let not_found = item_ids
+        .iter()
+        .copied()
+        .filter(|id| !cached_items.contains_key(id))
+        .collect();
+    let (final_items, filtered): (Vec<WorkshopItem>, Vec<WorkshopItem>) = item_ids
+        .iter()
+        .filter_map(|id| cached_items.get(id).cloned())
+        .partition(|item| ugc_type.matches_file_type(&item.file_type));
 
-        let start_time = std::time::Instant::now();
-        let timeout_duration = std::time::Duration::from_secs(30);
+    Ok(ItemsOfType {
+        items: with_creator_names(steam_game_id, backend.as_ref(), final_items).await?,
+        filtered: filtered
+            .iter()
+            .map(|item| FilteredItem::new(item, FilterReason::Type))
+            .collect(),
+        not_found,
+    })
+}
 
-        loop {
-            let _ = tx.blocking_send(());
-            if let Ok(result) = rx_inner.try_recv() {
-                return result;
-            }
+/// Like `workshop_items_of_type`, adding the logged-in user's vote and favorite to each
+/// item. These are never cached.
+pub async fn workshop_items_with_user_state(
+    steam_game_id: u32,
+    item_ids: Vec<u64>,
+    ugc_type: UGCType,
+) -> Result<ItemsOfType<ItemWithUserState>, S7Error> {
+    let ItemsOfType {
+        items,
+        filtered,
+        not_found,
+    } = workshop_items_of_type(steam_game_id, item_ids, ugc_type).await?;
+    if items.is_empty() {
+        return Ok(ItemsOfType {
+            items: Vec::new(),
+            filtered,
+            not_found,
+        });
+    }
 
-            if start_time.elapsed() > timeout_duration {
-                return Err("Operation timed out waiting for Steam response".to_string());
-            }
+    let backend = steam_manager::backend(steam_game_id)?;
+    // Steam looks votes up per item, but favorites only as the whole list.
+    let favorites = user_list_ids(backend.as_ref(), UserListType::Favorited).await?;
 
-            std::thread::sleep(std::time::Duration::from_millis(10));
-        }
-    });
-
-    let mut items_result = None;
-    let mut fused_task = items_task.fuse();
-
-    while items_result.is_none() {
-        tokio::select! {
-            Some(_) = rx.recv() => {
-                steam_manager::run_callbacks(steam_game_id)?;
-            }
-            task_result = &mut fused_task => {
-                items_result = Some(
-                    task_result.map_err(|e| format!("Task error: {:?}", e))?
-                );
-                break;
-            }
-        }
+    let mut items_with_state = Vec::with_capacity(items.len());
+    for item in items {
+        let item_id = item.workshop_item.published_file_id;
+        items_with_state.push(ItemWithUserState {
+            user_state: UserItemState {
+                vote: user_vote(backend.as_ref(), item_id).await?,
+                favorited: favorites.contains(&item_id),
+            },
+            item,
+        });
     }
+    Ok(ItemsOfType {
+        items: items_with_state,
+        filtered,
+        not_found,
+    })
+}
 
-    let items_result = items_result.unwrap()?;
-    let fetched_items = items_result
-        .items
-        .into_iter()
-        .filter_map(|item| match item {
-            Some(it) if it.file_type == "Community" => Some(it),
-            _ => None,
-        })
-        .collect::<Vec<WorkshopItem>>();
-
-    for item in &fetched_items {
-        cached_items.insert(item.published_file_id, item.clone());
+/// Looks items up on Steam without reading the cache, 50 IDs per query, and caches what
+/// was found. IDs Steam has no item for are left out.
+pub async fn fetch_items(
+    steam_game_id: u32,
+    backend: &dyn SteamBackend,
+    item_ids: &[u64],
+) -> Result<Vec<WorkshopItem>, S7Error> {
+    let mut fetched = Vec::new();
+    for batch in item_ids.chunks(ITEMS_PER_QUERY) {
+        let items_result = backend.query_items(batch.to_vec()).await?;
+        fetched.extend(items_result.items.into_iter().flatten());
     }
-    let cache_struct = WorkshopItemCache {
-        items: cached_items.clone(),
-    };
-    let serialized_cache = bincode::encode_to_vec(&cache_struct, bincode_config)
-        .map_err(|e| format!("Failed to serialize cache: {:?}", e))?;
-    let _ = fs::write(&cache_path, serialized_cache);
-
-    let final_items: Vec<WorkshopItem> = item_ids
-        .iter()
-        .filter_map(|id| cached_items.get(id).cloned())
-        .collect();
+    if !fetched.is_empty() {
+        let _ = disk_cache::update(
+            &cache_file(steam_game_id),
+            |cache: &mut WorkshopItemCache| {
+                cache.items.extend(
+                    fetched
+                        .iter()
+                        .map(|item| (item.published_file_id, item.clone())),
+                );
+                true
+            },
+        );
+    }
+    Ok(fetched)
+}
+
+/// Drops items from the cache after they were changed, so the next lookup fetches them
+/// from Steam again.
+pub fn forget_cached_items(steam_game_id: u32, item_ids: &[u64]) {
+    let _ = disk_cache::update(
+        &cache_file(steam_game_id),
+        |cache: &mut WorkshopItemCache| {
+            let cached_count = cache.items.len();
+            cache.items.retain(|id, _| !item_ids.contains(id));
+            cache.items.len() != cached_count
+        },
+    );
+}
 
-    let creator_ids: Vec<SteamId> = final_items
+/// Adds the creator names and the app's display tags to `workshop_items`.
+pub async fn with_creator_names(
+    steam_game_id: u32,
+    backend: &dyn SteamBackend,
+    workshop_items: Vec<WorkshopItem>,
+) -> Result<Vec<EnhancedWorkshopItem>, S7Error> {
+    let tag_rules = TagRules::for_app(steam_game_id)?;
+    let creator_ids: Vec<u64> = workshop_items
         .iter()
-        .map(|item| SteamId::from_raw(item.owner.steam_id64))
+        .map(|item| item.owner.steam_id64)
         .collect();
 
-    let creator_names = fetch_creator_names(creator_ids, steam_game_id).await?;
+    let creator_names = fetch_creator_names(backend, creator_ids).await?;
 
-    let result = final_items
+    Ok(workshop_items
         .into_iter()
         .map(|item| {
             let creator_name = creator_names
                 .get(&item.owner.steam_id64)
                 .cloned()
                 .unwrap_or_else(|| "[unknown]".to_string());
-            EnhancedWorkshopItem::new(item, creator_name)
+            EnhancedWorkshopItem::new(item, creator_name, &tag_rules)
         })
-        .collect();
-
-    Ok(result)
+        .collect())
 }
@@ -0,0 +1,278 @@
+use once_cell::sync::Lazy;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use steamworks::{SteamError, sys};
+use tokio::sync::oneshot;
+
+use crate::core::steam_manager;
+use crate::error::S7Error;
+
+pub const TIMEOUT_ENV: &str = "S7FORGE_STEAM_TIMEOUT";
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const CALLBACK_INTERVAL: Duration = Duration::from_millis(10);
+const CONFIRM_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long to wait for a Steam call result. Defaults to 30 seconds and can be
+/// overridden in seconds through `S7FORGE_STEAM_TIMEOUT`.
+pub fn default_timeout() -> Duration {
+    std::env::var(TIMEOUT_ENV)
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_TIMEOUT)
+}
+
+/// The sending half handed to a steamworks callback. Sending after the waiting
+/// future was dropped or timed out is a no-op.
+pub struct CallResultSender<T>(oneshot::Sender<Result<T, S7Error>>);
+
+impl<T> CallResultSender<T> {
+    pub fn send(self, result: Result<T, S7Error>) {
+        let _ = self.0.send(result);
+    }
+}
+
+/// Turns a callback-based steamworks call into a future. `start` issues the call and
+/// passes the sender to the steamworks callback; Steam callbacks for `app_id` are
+/// pumped until the result arrives or `timeout` passes. Dropping the future cancels
+/// the wait.
+pub async fn call_result<T, F>(app_id: u32, timeout: Duration, start: F) -> Result<T, S7Error>
+where
+    F: FnOnce(CallResultSender<T>) -> Result<(), S7Error>,
+{
+    watch_call_result(app_id, timeout, start, |_| {}).await
+}
+
+/// Like `call_result`, but `start` returns a handle that `watch` is called with after
+/// every callback pump, e.g. to read upload progress while waiting.
+pub async fn watch_call_result<T, H, F, W>(
+    app_id: u32,
+    timeout: Duration,
+    start: F,
+    mut watch: W,
+) -> Result<T, S7Error>
+where
+    F: FnOnce(CallResultSender<T>) -> Result<H, S7Error>,
+    W: FnMut(&H),
+{
+    let (tx, mut rx) = oneshot::channel();
+    let handle = start(CallResultSender(tx))?;
+
+    let result = pump_until(app_id, timeout, || {
+        watch(&handle);
+        match rx.try_recv() {
+            Ok(result) => Some(result),
+            Err(oneshot::error::TryRecvError::Empty) => None,
+            Err(oneshot::error::TryRecvError::Closed) => Some(Err(S7Error::Internal(
+                "Steam dropped the call without a result".to_string(),
+            ))),
+        }
+    })
+    .await?;
+
+    result.unwrap_or_else(|| Err(timed_out(timeout)))
+}
+
+fn timed_out(timeout: Duration) -> S7Error {
+    S7Error::Timeout {
+        seconds: timeout.as_secs(),
+        message: format!(
+            "Operation timed out waiting for Steam response after {} seconds",
+            timeout.as_secs()
+        ),
+    }
+}
+
+/// What became of a call made through the raw bindings.
+pub enum RawCallResult<C> {
+    Completed(C),
+    /// Steamworks took the result off the callback queue before it could be read. The
+    /// call ran, but whether it succeeded has to be checked another way.
+    Unread,
+}
+
+impl<C> RawCallResult<C> {
+    pub fn map<D>(self, f: impl FnOnce(C) -> D) -> RawCallResult<D> {
+        match self {
+            RawCallResult::Completed(result) => RawCallResult::Completed(f(result)),
+            RawCallResult::Unread => RawCallResult::Unread,
+        }
+    }
+}
+
+enum RawCallOutcome {
+    Completed(Vec<u8>),
+    Failed,
+    Unread,
+}
+
+struct PendingRawCall {
+    api_call: sys::SteamAPICall_t,
+    callback_id: i32,
+    size: usize,
+    sender: oneshot::Sender<RawCallOutcome>,
+}
+
+static RAW_CALLS: Lazy<Mutex<Vec<PendingRawCall>>> = Lazy::new(Mutex::default);
+
+/// For Steam calls steamworks does not wrap, or wraps wrongly: `start` makes the call
+/// through the raw bindings and returns its handle, and the result struct `C`, which
+/// Steam identifies by `callback_id`, is read once the call completes. Callbacks for
+/// `app_id` are pumped meanwhile.
+pub async fn raw_call_result<C, F>(
+    app_id: u32,
+    timeout: Duration,
+    callback_id: i32,
+    start: F,
+) -> Result<RawCallResult<C>, S7Error>
+where
+    C: Copy,
+    F: FnOnce() -> sys::SteamAPICall_t,
+{
+    let api_call = start();
+    if api_call == sys::k_uAPICallInvalid {
+        return Err(S7Error::steam_api(SteamError::InvalidParameter));
+    }
+
+    let (sender, mut receiver) = oneshot::channel();
+    RAW_CALLS.lock().unwrap().push(PendingRawCall {
+        api_call,
+        callback_id,
+        size: std::mem::size_of::<C>(),
+        sender,
+    });
+
+    let outcome = pump_until(app_id, timeout, || match receiver.try_recv() {
+        Ok(outcome) => Some(outcome),
+        Err(oneshot::error::TryRecvError::Empty) => None,
+        Err(oneshot::error::TryRecvError::Closed) => Some(RawCallOutcome::Unread),
+    })
+    .await;
+    let Some(outcome) = outcome? else {
+        RAW_CALLS
+            .lock()
+            .unwrap()
+            .retain(|call| call.api_call != api_call);
+        return Err(timed_out(timeout));
+    };
+
+    match outcome {
+        // The buffer was sized for `C` and filled by Steam with a `C`.
+        RawCallOutcome::Completed(buffer) => Ok(RawCallResult::Completed(unsafe {
+            std::ptr::read_unaligned(buffer.as_ptr().cast::<C>())
+        })),
+        RawCallOutcome::Failed => Err(S7Error::steam_api(SteamError::IOFailure)),
+        RawCallOutcome::Unread => Ok(RawCallResult::Unread),
+    }
+}
+
+/// Reads the results of finished raw calls. Steamworks takes every finished call off the
+/// callback queue when it pumps callbacks, including calls it did not make, so this runs
+/// Steam's frame first and reads the results before steamworks gets to them.
+pub(crate) fn read_raw_call_results() {
+    let mut calls = RAW_CALLS.lock().unwrap();
+    if calls.is_empty() {
+        return;
+    }
+
+    let pipe = unsafe { sys::SteamAPI_GetHSteamPipe() };
+    let utils = unsafe { sys::SteamAPI_SteamUtils_v010() };
+    unsafe { sys::SteamAPI_ManualDispatch_RunFrame(pipe) };
+    for call in std::mem::take(&mut *calls) {
+        match unsafe { raw_call_outcome(pipe, utils, &call) } {
+            Some(outcome) => {
+                let _ = call.sender.send(outcome);
+            }
+            None => calls.push(call),
+        }
+    }
+}
+
+unsafe fn raw_call_outcome(
+    pipe: sys::HSteamPipe,
+    utils: *mut sys::ISteamUtils,
+    call: &PendingRawCall,
+) -> Option<RawCallOutcome> {
+    let mut failed = false;
+    unsafe {
+        if !sys::SteamAPI_ISteamUtils_IsAPICallCompleted(utils, call.api_call, &mut failed) {
+            // Once steamworks has taken the result, Steam no longer knows the call.
+            let reason = sys::SteamAPI_ISteamUtils_GetAPICallFailureReason(utils, call.api_call);
+            return (reason == sys::ESteamAPICallFailure::k_ESteamAPICallFailureInvalidHandle)
+                .then_some(RawCallOutcome::Unread);
+        }
+
+        let mut buffer = vec![0u8; call.size];
+        let read = sys::SteamAPI_ManualDispatch_GetAPICallResult(
+            pipe,
+            call.api_call,
+            buffer.as_mut_ptr().cast(),
+            call.size as i32,
+            call.callback_id,
+            &mut failed,
+        );
+        Some(match (read, failed) {
+            (true, false) => RawCallOutcome::Completed(buffer),
+            (true, true) => RawCallOutcome::Failed,
+            (false, _) => RawCallOutcome::Unread,
+        })
+    }
+}
+
+/// Turns the result code of a raw call into an error unless it reports success.
+pub fn check_result(result: sys::EResult) -> Result<(), S7Error> {
+    match result {
+        sys::EResult::k_EResultOK | sys::EResult::k_EResultNone => Ok(()),
+        result => Err(S7Error::steam_api(result.into())),
+    }
+}
+
+/// Pumps Steam callbacks for `app_id` until `poll` returns a value, or returns
+/// `Ok(None)` once `timeout` passes.
+pub async fn pump_until<T, P>(
+    app_id: u32,
+    timeout: Duration,
+    mut poll: P,
+) -> Result<Option<T>, S7Error>
+where
+    P: FnMut() -> Option<T>,
+{
+    let deadline = Instant::now() + timeout;
+    let mut interval = tokio::time::interval(CALLBACK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        steam_manager::run_callbacks(app_id)?;
+
+        if let Some(value) = poll() {
+            return Ok(Some(value));
+        }
+
+        if Instant::now() >= deadline {
+            return Ok(None);
+        }
+    }
+}
+
+/// For Steam calls whose call result could not be read: re-runs `confirmed`, which
+/// typically queries Steam itself, until it reports the call took effect. Returns
+/// `Ok(false)` once `timeout` passes.
+pub async fn confirm_effect<F, Fut>(timeout: Duration, mut confirmed: F) -> Result<bool, S7Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<bool, S7Error>>,
+{
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        if confirmed().await? {
+            return Ok(true);
+        }
+        if Instant::now() >= deadline {
+            return Ok(false);
+        }
+        tokio::time::sleep(CONFIRM_INTERVAL).await;
+    }
+}
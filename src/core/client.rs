@@ -1,6 +1,9 @@
 use std::sync::{Arc, Mutex};
 use steamworks::{Client, SingleClient};
 
+use crate::core::call_result::read_raw_call_results;
+use crate::error::S7Error;
+
 #[derive(Default)]
 pub struct SteamState {
     client: Arc<Mutex<Option<(u32, Client)>>>,
@@ -23,6 +26,14 @@ impl SteamState {
         }
     }
 
+    pub fn app_id(&self) -> Option<u32> {
+        self.client
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|(steam_game_id, _)| *steam_game_id)
+    }
+
     pub fn get_client(&self, steam_game_id: u32) -> Option<Client> {
         let state = self.client.lock().unwrap();
         if let Some((current_steam_game_id, ref client)) = *state {
@@ -33,15 +44,18 @@ impl SteamState {
         None
     }
 
-    pub fn run_callbacks(&self, steam_game_id: u32) -> Result<(), String> {
+    pub fn run_callbacks(&self, steam_game_id: u32) -> Result<(), S7Error> {
         let mut state = self.single_client.lock().unwrap();
         if let Some((current_steam_game_id, ref mut single_client)) = *state {
             if current_steam_game_id == steam_game_id {
+                read_raw_call_results();
                 single_client.run_callbacks();
                 return Ok(());
             }
         }
-        Err("Single client not found for given steam_game_id".to_string())
+        Err(S7Error::SteamNotRunning(
+            "Single client not found for given steam_game_id".to_string(),
+        ))
     }
 
     pub fn set_clients(&self, steam_game_id: u32, client: Client, single_client: SingleClient) {
@@ -0,0 +1,742 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use steamworks::{FileType, ItemState, SteamError, SteamId};
+
+use crate::core::game_version::compatible_versions;
+use crate::core::localplayer::PlayerSteamId;
+use crate::core::steam_backend::{
+    BackendFuture, DependencyChanges, ItemUpdate, SteamBackend, SubmittedItem, UgcQuery,
+    UpdateProgressSink, UserQuery,
+};
+use crate::core::workshop::{ItemInstallInfo, UgcItemVisibility, UpdateProgress, UpdateStatus};
+use crate::core::workshop_item::file_type_name;
+use crate::core::workshop_item::workshop::{
+    AdditionalPreview, UGCQueryType, UserListOrder, UserListType, WorkshopItem,
+    WorkshopItemStatistic, WorkshopItemsResult,
+};
+use crate::error::S7Error;
+use crate::utils::parse_date::DateRange;
+
+pub const FAKE_CATALOG_ENV: &str = "S7FORGE_FAKE_STEAM_CATALOG";
+
+const PAGE_SIZE: usize = 50;
+
+#[derive(Debug, Deserialize)]
+struct FakeCatalog {
+    #[serde(default)]
+    items: Vec<FakeCatalogItem>,
+    #[serde(default)]
+    subscribed: Vec<u64>,
+    #[serde(default)]
+    installed: Vec<u64>,
+    #[serde(default)]
+    persona_names: FxHashMap<u64, String>,
+    #[serde(default)]
+    local_user: u64,
+    #[serde(default)]
+    favorited: Vec<u64>,
+    /// The local user's votes, `true` for up and `false` for down.
+    #[serde(default)]
+    votes: FxHashMap<u64, bool>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct FakeCatalogItem {
+    published_file_id: u64,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    owner: u64,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default = "default_file_type")]
+    file_type: String,
+    #[serde(default)]
+    time_created: u32,
+    #[serde(default)]
+    time_updated: u32,
+    #[serde(default = "default_visibility")]
+    visibility: UgcItemVisibility,
+    #[serde(default)]
+    banned: bool,
+    #[serde(default)]
+    num_upvotes: u32,
+    #[serde(default)]
+    num_downvotes: u32,
+    #[serde(default)]
+    num_subscriptions: u64,
+    #[serde(default)]
+    children: Vec<u64>,
+    #[serde(default)]
+    app_dependencies: Vec<u32>,
+    /// Adding or removing these dependencies fails, for testing partial changes.
+    #[serde(default)]
+    failing_dependencies: Vec<u64>,
+    #[serde(default)]
+    failing_app_dependencies: Vec<u32>,
+    #[serde(default)]
+    preview_url: Option<String>,
+    #[serde(default)]
+    file_size: u32,
+    #[serde(default)]
+    metadata: String,
+    #[serde(default)]
+    key_value_tags: Vec<(String, String)>,
+    #[serde(default)]
+    additional_previews: Vec<AdditionalPreview>,
+}
+
+fn default_file_type() -> String {
+    "Community".to_string()
+}
+
+fn default_visibility() -> UgcItemVisibility {
+    UgcItemVisibility::Public
+}
+
+impl FakeCatalogItem {
+    fn to_workshop_item(&self, app_id: u32) -> WorkshopItem {
+        WorkshopItem {
+            published_file_id: self.published_file_id,
+            creator_steam_game_id: Some(app_id),
+            consumer_steam_game_id: Some(app_id),
+            title: self.title.clone(),
+            description: self.description.clone(),
+            owner: PlayerSteamId::from_steamid(SteamId::from_raw(self.owner)),
+            time_created: self.time_created as u128 * 1000,
+            time_updated: self.time_updated as u128 * 1000,
+            time_added_to_user_list: 0,
+            visibility: self.visibility.clone(),
+            banned: self.banned,
+            accepted_for_use: false,
+            tags: self.tags.clone(),
+            compatible_versions: compatible_versions(&self.tags),
+            tags_truncated: false,
+            url: String::new(),
+            num_upvotes: self.num_upvotes,
+            num_downvotes: self.num_downvotes,
+            num_children: self.children.len() as u32,
+            preview_url: self.preview_url.clone(),
+            statistics: WorkshopItemStatistic {
+                num_subscriptions: Some(self.num_subscriptions),
+                ..Default::default()
+            },
+            required_items: self.children.clone(),
+            file_type: self.file_type.clone(),
+            file_size: self.file_size,
+            metadata: Some(self.metadata.clone()).filter(|metadata| !metadata.is_empty()),
+            key_value_tags: self.key_value_tags.clone(),
+            additional_previews: self.additional_previews.clone(),
+        }
+    }
+
+    fn matches_query(&self, query: &UgcQuery) -> bool {
+        if !query.ugc_type.matches_file_type(&self.file_type) {
+            return false;
+        }
+
+        if let Some(search_text) = &query.search_text {
+            let needle = search_text.to_lowercase();
+            if !self.title.to_lowercase().contains(&needle)
+                && !self.description.to_lowercase().contains(&needle)
+            {
+                return false;
+            }
+        }
+
+        let has_tag =
+            |wanted: &String| self.tags.iter().any(|tag| tag.eq_ignore_ascii_case(wanted));
+        let required = if query.match_any_tag && !query.required_tags.is_empty() {
+            query.required_tags.iter().any(has_tag)
+        } else {
+            query.required_tags.iter().all(has_tag)
+        };
+
+        required
+            && in_range(query.created, self.time_created)
+            && in_range(query.updated, self.time_updated)
+            && !query.excluded_tags.iter().any(has_tag)
+            && query
+                .required_tag_groups
+                .iter()
+                .all(|group| group.iter().any(has_tag))
+    }
+}
+
+fn in_range(range: DateRange, time: u32) -> bool {
+    range.since.is_none_or(|since| time >= since) && range.until.is_none_or(|until| time <= until)
+}
+
+/// An in-memory Steam backend backed by a JSON catalog file, selected by setting
+/// `S7FORGE_FAKE_STEAM_CATALOG`. Subscriptions and installs only live for the process.
+pub struct FakeBackend {
+    app_id: u32,
+    items: Mutex<Vec<FakeCatalogItem>>,
+    persona_names: FxHashMap<u64, String>,
+    local_user: u64,
+    subscribed: Mutex<Vec<u64>>,
+    installed: Mutex<FxHashSet<u64>>,
+    favorited: Mutex<Vec<u64>>,
+    votes: Mutex<FxHashMap<u64, bool>>,
+}
+
+impl FakeBackend {
+    pub fn load(app_id: u32, catalog_path: &Path) -> Result<Self, S7Error> {
+        let content = fs::read_to_string(catalog_path)
+            .map_err(|e| S7Error::Io(format!("Failed to read fake Steam catalog: {}", e)))?;
+        let catalog: FakeCatalog = serde_json::from_str(&content)
+            .map_err(|e| S7Error::Io(format!("Failed to parse fake Steam catalog: {}", e)))?;
+
+        Ok(Self {
+            app_id,
+            items: Mutex::new(catalog.items),
+            persona_names: catalog.persona_names,
+            local_user: catalog.local_user,
+            subscribed: Mutex::new(catalog.subscribed),
+            installed: Mutex::new(catalog.installed.into_iter().collect()),
+            favorited: Mutex::new(catalog.favorited),
+            votes: Mutex::new(catalog.votes),
+        })
+    }
+
+    fn update_owned_item<F>(&self, item_id: u64, change: F) -> Result<(), S7Error>
+    where
+        F: FnOnce(&mut FakeCatalogItem),
+    {
+        let mut items = self.items.lock().unwrap();
+        let Some(item) = items
+            .iter_mut()
+            .find(|item| item.published_file_id == item_id)
+        else {
+            return Err(S7Error::steam_api(SteamError::FileNotFound));
+        };
+        if item.owner != self.local_user {
+            return Err(S7Error::steam_api(SteamError::AccessDenied));
+        }
+
+        change(item);
+        Ok(())
+    }
+
+    fn page(&self, matches: Vec<&FakeCatalogItem>, page: u32) -> Vec<Option<WorkshopItem>> {
+        let start = (page.saturating_sub(1) as usize) * PAGE_SIZE;
+        matches
+            .into_iter()
+            .skip(start)
+            .take(PAGE_SIZE)
+            .map(|item| Some(item.to_workshop_item(self.app_id)))
+            .collect()
+    }
+
+    // Fails like Steam does when a change to `item_id` is refused midway.
+    fn check_change<F>(&self, item_id: u64, fails: F) -> Result<(), S7Error>
+    where
+        F: FnOnce(&FakeCatalogItem) -> bool,
+    {
+        match self.find(item_id) {
+            Some(item) if fails(&item) => Err(S7Error::steam_api(SteamError::IOFailure)),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_exists(&self, item_id: u64) -> Result<(), S7Error> {
+        match self.find(item_id) {
+            Some(_) => Ok(()),
+            None => Err(S7Error::steam_api(SteamError::FileNotFound)),
+        }
+    }
+
+    fn find(&self, item_id: u64) -> Option<FakeCatalogItem> {
+        self.items
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|item| item.published_file_id == item_id)
+            .cloned()
+    }
+}
+
+impl SteamBackend for FakeBackend {
+    fn query_items(&self, item_ids: Vec<u64>) -> BackendFuture<'_, WorkshopItemsResult> {
+        // Steam won't create a details query for more than one page of IDs.
+        if item_ids.len() > PAGE_SIZE {
+            return Box::pin(async move {
+                Err(S7Error::Internal(format!(
+                    "Failed to create query handle: {} IDs in one query",
+                    item_ids.len()
+                )))
+            });
+        }
+        let items = item_ids
+            .into_iter()
+            .map(|id| self.find(id).map(|item| item.to_workshop_item(self.app_id)))
+            .collect::<Vec<_>>();
+
+        Box::pin(async move {
+            Ok(WorkshopItemsResult {
+                total_results: items.len() as u32,
+                items,
+                was_cached: false,
+            })
+        })
+    }
+
+    fn query_all(&self, query: UgcQuery) -> BackendFuture<'_, WorkshopItemsResult> {
+        let items = self.items.lock().unwrap();
+        let mut matches: Vec<&FakeCatalogItem> = items
+            .iter()
+            .filter(|item| item.matches_query(&query))
+            .collect();
+
+        match query.query_type {
+            UGCQueryType::RankedByPublicationDate => {
+                matches.sort_by_key(|item| std::cmp::Reverse(item.time_created))
+            }
+            UGCQueryType::RankedByLastUpdatedDate => {
+                matches.sort_by_key(|item| std::cmp::Reverse(item.time_updated))
+            }
+            UGCQueryType::RankedByVote | UGCQueryType::RankedByTrend => {
+                matches.sort_by_key(|item| std::cmp::Reverse(item.num_upvotes))
+            }
+            UGCQueryType::RankedByVotesUp => {
+                matches.sort_by_key(|item| std::cmp::Reverse(item.num_upvotes))
+            }
+            UGCQueryType::RankedByTotalVotesAsc => {
+                matches.sort_by_key(|item| item.num_upvotes + item.num_downvotes)
+            }
+            UGCQueryType::RankedByTotalUniqueSubscriptions => {
+                matches.sort_by_key(|item| std::cmp::Reverse(item.num_subscriptions))
+            }
+            _ => {}
+        }
+
+        let total_results = matches.len() as u32;
+        let items = self.page(matches, query.page);
+
+        Box::pin(async move {
+            Ok(WorkshopItemsResult {
+                items,
+                total_results,
+                was_cached: false,
+            })
+        })
+    }
+
+    fn query_user(&self, query: UserQuery) -> BackendFuture<'_, WorkshopItemsResult> {
+        let account_id = |steam_id: u64| SteamId::from_raw(steam_id).account_id().raw();
+        let is_local_user = query.account_id == account_id(self.local_user);
+        let items = self.items.lock().unwrap();
+        let subscribed = self.subscribed.lock().unwrap();
+        let favorited = self.favorited.lock().unwrap();
+        let votes = self.votes.lock().unwrap();
+
+        // Only published items are known for users other than the local one.
+        let in_list = |item: &FakeCatalogItem| {
+            let id = item.published_file_id;
+            match query.list {
+                UserListType::Published => account_id(item.owner) == query.account_id,
+                _ if !is_local_user => false,
+                UserListType::Subscribed => subscribed.contains(&id),
+                UserListType::Favorited => favorited.contains(&id),
+                UserListType::VotedOn => votes.contains_key(&id),
+                UserListType::VotedUp => votes.get(&id) == Some(&true),
+                UserListType::VotedDown => votes.get(&id) == Some(&false),
+                UserListType::UsedOrPlayed | UserListType::Followed => false,
+            }
+        };
+        let mut matches: Vec<&FakeCatalogItem> = items
+            .iter()
+            .filter(|item| query.ugc_type.matches_file_type(&item.file_type) && in_list(item))
+            .collect();
+
+        match query.order {
+            UserListOrder::CreationOrderAsc => matches.sort_by_key(|item| item.time_created),
+            UserListOrder::CreationOrderDesc => {
+                matches.sort_by_key(|item| std::cmp::Reverse(item.time_created))
+            }
+            UserListOrder::TitleAsc => matches.sort_by(|a, b| a.title.cmp(&b.title)),
+            UserListOrder::LastUpdatedDesc => {
+                matches.sort_by_key(|item| std::cmp::Reverse(item.time_updated))
+            }
+            UserListOrder::SubscriptionDateDesc => matches.sort_by_key(|item| {
+                std::cmp::Reverse(
+                    subscribed
+                        .iter()
+                        .position(|id| *id == item.published_file_id),
+                )
+            }),
+            UserListOrder::VoteScoreDesc => matches.sort_by_key(|item| {
+                std::cmp::Reverse(item.num_upvotes as i64 - item.num_downvotes as i64)
+            }),
+            UserListOrder::ForModeration => {}
+        }
+
+        let total_results = matches.len() as u32;
+        let items = self.page(matches, query.page);
+
+        Box::pin(async move {
+            Ok(WorkshopItemsResult {
+                items,
+                total_results,
+                was_cached: false,
+            })
+        })
+    }
+
+    fn subscribe_item(&self, item_id: u64) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            if self.find(item_id).is_none() {
+                return Err(S7Error::steam_api(SteamError::FileNotFound));
+            }
+
+            let mut subscribed = self.subscribed.lock().unwrap();
+            if !subscribed.contains(&item_id) {
+                subscribed.push(item_id);
+            }
+            Ok(())
+        })
+    }
+
+    fn unsubscribe_item(&self, item_id: u64) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            if self.find(item_id).is_none() {
+                return Err(S7Error::steam_api(SteamError::FileNotFound));
+            }
+
+            self.subscribed.lock().unwrap().retain(|id| *id != item_id);
+            self.installed.lock().unwrap().remove(&item_id);
+            Ok(())
+        })
+    }
+
+    fn subscribed_items(&self) -> Vec<u64> {
+        self.subscribed.lock().unwrap().clone()
+    }
+
+    fn item_state(&self, item_id: u64) -> ItemState {
+        let mut state = ItemState::NONE;
+        if self.subscribed.lock().unwrap().contains(&item_id) {
+            state |= ItemState::SUBSCRIBED;
+        }
+        if self.installed.lock().unwrap().contains(&item_id) {
+            state |= ItemState::INSTALLED;
+        }
+        state
+    }
+
+    fn item_install_info(&self, item_id: u64) -> Option<ItemInstallInfo> {
+        if !self.installed.lock().unwrap().contains(&item_id) {
+            return None;
+        }
+
+        self.find(item_id).map(|item| ItemInstallInfo {
+            folder: String::new(),
+            size_on_disk: item.file_size as u64,
+            timestamp: item.time_updated,
+        })
+    }
+
+    fn item_download_info(&self, item_id: u64) -> Option<(u64, u64)> {
+        self.item_install_info(item_id)
+            .map(|info| (info.size_on_disk, info.size_on_disk))
+    }
+
+    fn download_item(&self, item_id: u64, _high_priority: bool) -> bool {
+        if !self.subscribed.lock().unwrap().contains(&item_id) {
+            return false;
+        }
+
+        self.installed.lock().unwrap().insert(item_id);
+        true
+    }
+
+    fn persona_names(&self, steam_ids: Vec<u64>) -> BackendFuture<'_, FxHashMap<u64, String>> {
+        let names = steam_ids
+            .into_iter()
+            .map(|id| {
+                let name = self
+                    .persona_names
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or_else(|| "[unknown]".to_string());
+                (id, name)
+            })
+            .collect();
+
+        Box::pin(async move { Ok(names) })
+    }
+
+    fn local_user(&self) -> PlayerSteamId {
+        PlayerSteamId::from_steamid(SteamId::from_raw(self.local_user))
+    }
+
+    fn create_item(&self, file_type: FileType) -> BackendFuture<'_, SubmittedItem> {
+        Box::pin(async move {
+            let mut items = self.items.lock().unwrap();
+            let published_file_id = items
+                .iter()
+                .map(|item| item.published_file_id)
+                .max()
+                .unwrap_or_default()
+                + 1;
+            let now = unix_time();
+
+            items.push(FakeCatalogItem {
+                published_file_id,
+                owner: self.local_user,
+                file_type: file_type_name(file_type).to_string(),
+                time_created: now,
+                time_updated: now,
+                visibility: UgcItemVisibility::Private,
+                ..Default::default()
+            });
+
+            Ok(SubmittedItem {
+                published_file_id,
+                needs_legal_agreement: false,
+            })
+        })
+    }
+
+    fn update_item(
+        &self,
+        item_id: u64,
+        update: ItemUpdate,
+        on_progress: UpdateProgressSink,
+    ) -> BackendFuture<'_, SubmittedItem> {
+        Box::pin(async move {
+            let report = |status, bytes_processed, bytes_total| {
+                on_progress(UpdateProgress {
+                    item_id,
+                    status,
+                    bytes_processed,
+                    bytes_total,
+                })
+            };
+
+            let mut items = self.items.lock().unwrap();
+            let Some(item) = items
+                .iter_mut()
+                .find(|item| item.published_file_id == item_id)
+            else {
+                return Err(S7Error::steam_api(SteamError::FileNotFound));
+            };
+            if item.owner != self.local_user {
+                return Err(S7Error::steam_api(SteamError::AccessDenied));
+            }
+
+            if let Some(title) = update.title {
+                item.title = title;
+            }
+            if let Some(description) = update.description {
+                item.description = description;
+            }
+            if let Some(visibility) = update.visibility {
+                item.visibility = visibility;
+            }
+            if let Some(tags) = update.tags {
+                item.tags = tags;
+            }
+            if let Some(metadata) = update.metadata {
+                item.metadata = metadata;
+            }
+            report(UpdateStatus::PreparingConfig, 0, 0);
+            if let Some(content_folder) = update.content_folder {
+                let size = folder_size(&content_folder);
+                report(UpdateStatus::UploadingContent, 0, size);
+                report(UpdateStatus::UploadingContent, size, size);
+                item.file_size = size as u32;
+            }
+            if let Some(preview_image) = update.preview_image {
+                let size = fs::metadata(preview_image)
+                    .map(|m| m.len())
+                    .unwrap_or_default();
+                report(UpdateStatus::UploadingPreviewFile, size, size);
+            }
+            report(UpdateStatus::CommittingChanges, 0, 0);
+            item.time_updated = unix_time();
+
+            Ok(SubmittedItem {
+                published_file_id: item_id,
+                needs_legal_agreement: false,
+            })
+        })
+    }
+
+    fn delete_item(&self, item_id: u64) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            let mut items = self.items.lock().unwrap();
+            let Some(index) = items
+                .iter()
+                .position(|item| item.published_file_id == item_id)
+            else {
+                return Err(S7Error::steam_api(SteamError::FileNotFound));
+            };
+            if items[index].owner != self.local_user {
+                return Err(S7Error::steam_api(SteamError::AccessDenied));
+            }
+
+            items.remove(index);
+            self.subscribed.lock().unwrap().retain(|&id| id != item_id);
+            Ok(())
+        })
+    }
+
+    fn add_dependencies(
+        &self,
+        item_id: u64,
+        dependency_ids: Vec<u64>,
+    ) -> BackendFuture<'_, DependencyChanges> {
+        Box::pin(async move {
+            Ok(change_each(dependency_ids, |dependency_id| {
+                self.check_change(item_id, |item| {
+                    item.failing_dependencies.contains(&dependency_id)
+                })?;
+                self.update_owned_item(item_id, |item| {
+                    if !item.children.contains(&dependency_id) {
+                        item.children.push(dependency_id);
+                    }
+                })
+            }))
+        })
+    }
+
+    fn remove_dependencies(
+        &self,
+        item_id: u64,
+        dependency_ids: Vec<u64>,
+    ) -> BackendFuture<'_, DependencyChanges> {
+        Box::pin(async move {
+            Ok(change_each(dependency_ids, |dependency_id| {
+                self.check_change(item_id, |item| {
+                    item.failing_dependencies.contains(&dependency_id)
+                })?;
+                self.update_owned_item(item_id, |item| {
+                    item.children.retain(|&id| id != dependency_id)
+                })
+            }))
+        })
+    }
+
+    fn app_dependencies(&self, item_id: u64) -> BackendFuture<'_, Vec<u32>> {
+        Box::pin(async move {
+            self.find(item_id)
+                .map(|item| item.app_dependencies)
+                .ok_or(S7Error::steam_api(SteamError::FileNotFound))
+        })
+    }
+
+    fn add_app_dependency(&self, item_id: u64, app_id: u32) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            self.check_change(item_id, |item| {
+                item.failing_app_dependencies.contains(&app_id)
+            })?;
+            self.update_owned_item(item_id, |item| {
+                if !item.app_dependencies.contains(&app_id) {
+                    item.app_dependencies.push(app_id);
+                }
+            })
+        })
+    }
+
+    fn remove_app_dependency(&self, item_id: u64, app_id: u32) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            self.update_owned_item(item_id, |item| {
+                item.app_dependencies.retain(|&id| id != app_id)
+            })
+        })
+    }
+
+    fn set_item_vote(&self, item_id: u64, vote_up: bool) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            let mut items = self.items.lock().unwrap();
+            let Some(item) = items
+                .iter_mut()
+                .find(|item| item.published_file_id == item_id)
+            else {
+                return Err(S7Error::steam_api(SteamError::FileNotFound));
+            };
+
+            match self.votes.lock().unwrap().insert(item_id, vote_up) {
+                Some(true) => item.num_upvotes = item.num_upvotes.saturating_sub(1),
+                Some(false) => item.num_downvotes = item.num_downvotes.saturating_sub(1),
+                None => {}
+            }
+            if vote_up {
+                item.num_upvotes += 1;
+            } else {
+                item.num_downvotes += 1;
+            }
+            Ok(())
+        })
+    }
+
+    fn item_vote(&self, item_id: u64) -> BackendFuture<'_, Option<bool>> {
+        Box::pin(async move {
+            self.check_exists(item_id)?;
+            Ok(self.votes.lock().unwrap().get(&item_id).copied())
+        })
+    }
+
+    fn add_favorite(&self, item_id: u64) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            self.check_exists(item_id)?;
+
+            let mut favorited = self.favorited.lock().unwrap();
+            if !favorited.contains(&item_id) {
+                favorited.push(item_id);
+            }
+            Ok(())
+        })
+    }
+
+    fn remove_favorite(&self, item_id: u64) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            self.check_exists(item_id)?;
+
+            self.favorited.lock().unwrap().retain(|&id| id != item_id);
+            Ok(())
+        })
+    }
+}
+
+fn change_each<F>(dependency_ids: Vec<u64>, mut change: F) -> DependencyChanges
+where
+    F: FnMut(u64) -> Result<(), S7Error>,
+{
+    let mut changes = DependencyChanges::default();
+    for dependency_id in dependency_ids {
+        if let Err(error) = change(dependency_id) {
+            changes.failed = Some((dependency_id, error));
+            break;
+        }
+        changes.applied.push(dependency_id);
+    }
+    changes
+}
+
+fn unix_time() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as u32)
+        .unwrap_or_default()
+}
+
+fn folder_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => folder_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::core::workshop_item::is_version_tag;
+use crate::error::S7Error;
+
+/// A game version such as `1.2.3`, or a range of them such as `1.2.x` when `wildcard`
+/// is set. Missing components count as zero when comparing, so `1.2` sorts next to
+/// `1.2.0`, before `1.2.x` and `1.3`.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
+pub struct GameVersion {
+    components: Vec<u32>,
+    wildcard: bool,
+}
+
+impl GameVersion {
+    /// The version a tag like `v1.2.x` or `e3.4` declares, if it is a version tag.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        if !is_version_tag(tag) {
+            return None;
+        }
+        let cleaned: String = tag.trim()[1..]
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        Self::parse(&cleaned)
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        let (numbers, wildcard) = match value.strip_suffix(['x', 'X']) {
+            Some(numbers) => (numbers, true),
+            None => (value, false),
+        };
+        let components = numbers
+            .split('.')
+            .filter(|component| !component.is_empty())
+            .map(|component| component.parse().ok())
+            .collect::<Option<Vec<u32>>>()?;
+        if components.is_empty() {
+            return None;
+        }
+        Some(Self {
+            components,
+            wildcard,
+        })
+    }
+
+    /// Whether `version` is this version, or falls in its range for a wildcard. A plain
+    /// version is not a prefix: `1.2` covers `1.2.0` but not `1.2.3`.
+    pub fn covers(&self, version: &GameVersion) -> bool {
+        let length = if self.wildcard {
+            self.components.len()
+        } else {
+            self.components.len().max(version.components.len())
+        };
+        (0..length).all(|index| self.component(index) == version.component(index))
+    }
+
+    fn component(&self, index: usize) -> u32 {
+        self.components.get(index).copied().unwrap_or(0)
+    }
+}
+
+/// The versions an item's tags declare, oldest first.
+pub fn compatible_versions(tags: &[String]) -> Vec<GameVersion> {
+    let mut versions: Vec<GameVersion> = tags
+        .iter()
+        .filter_map(|tag| GameVersion::from_tag(tag))
+        .collect();
+    versions.sort();
+    versions.dedup();
+    versions
+}
+
+impl Ord for GameVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let length = self.components.len().max(other.components.len());
+        (0..length)
+            .map(|index| self.component(index).cmp(&other.component(index)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+            .then(self.wildcard.cmp(&other.wildcard))
+            .then(self.components.len().cmp(&other.components.len()))
+    }
+}
+
+impl PartialOrd for GameVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for GameVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let components: Vec<String> = self.components.iter().map(u32::to_string).collect();
+        write!(f, "{}", components.join("."))?;
+        if self.wildcard {
+            write!(f, ".x")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the version of a running game, like `1.2` or `v1.2.3`. Wildcards are only
+/// meaningful in tags and are rejected.
+impl FromStr for GameVersion {
+    type Err = S7Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let trimmed = value.trim();
+        let numbers = trimmed.strip_prefix(['v', 'V']).unwrap_or(trimmed);
+        let is_plain = numbers.starts_with(|c: char| c.is_ascii_digit())
+            && numbers.chars().all(|c| c.is_ascii_digit() || c == '.')
+            && !numbers.contains("..")
+            && !numbers.ends_with('.');
+        match Self::parse(numbers) {
+            Some(version) if is_plain => Ok(version),
+            _ => Err(S7Error::InvalidArgument(format!(
+                "Invalid game version '{}'. Expected a version like 1.2 or 1.2.3",
+                value
+            ))),
+        }
+    }
+}
+
+impl From<GameVersion> for String {
+    fn from(version: GameVersion) -> Self {
+        version.to_string()
+    }
+}
+
+impl TryFrom<String> for GameVersion {
+    type Error = S7Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+            .ok_or_else(|| S7Error::InvalidArgument(format!("Invalid game version '{}'", value)))
+    }
+}
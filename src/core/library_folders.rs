@@ -0,0 +1,117 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::core::steam_install_paths::steam_install_paths;
+use crate::error::S7Error;
+use crate::utils::vdf;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LibraryFolder {
+    pub path: String,
+    pub label: String,
+    pub contentid: Option<String>,
+    pub totalsize: Option<u64>,
+    pub apps: BTreeMap<u32, u64>,
+}
+
+impl LibraryFolder {
+    fn from_table(table: &vdf::VdfTable) -> Option<Self> {
+        let path = table.get_str("path")?.to_string();
+
+        let apps = table
+            .get_table("apps")
+            .map(|apps| {
+                apps.iter()
+                    .filter_map(|(app_id, size)| {
+                        Some((app_id.parse().ok()?, size.as_str()?.parse().ok()?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            path,
+            label: table.get_str("label").unwrap_or_default().to_string(),
+            contentid: table.get_str("contentid").map(str::to_string),
+            totalsize: table
+                .get_str("totalsize")
+                .and_then(|size| size.parse().ok()),
+            apps,
+        })
+    }
+}
+
+pub fn parse_library_folders(content: &str) -> Result<Vec<LibraryFolder>, vdf::VdfError> {
+    let root = vdf::parse(content)?;
+
+    // Older clients write "LibraryFolders" with plain path strings; newer ones
+    // write "libraryfolders" with one table per library. The lookup is case-insensitive.
+    let Some(folders) = root
+        .lookup("libraryfolders")
+        .and_then(vdf::VdfValue::as_table)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut libraries = Vec::new();
+    for (key, value) in folders.iter() {
+        if key.parse::<u32>().is_err() {
+            continue;
+        }
+
+        match value {
+            vdf::VdfValue::Table(table) => libraries.extend(LibraryFolder::from_table(table)),
+            vdf::VdfValue::String(path) => libraries.push(LibraryFolder {
+                path: path.clone(),
+                label: String::new(),
+                contentid: None,
+                totalsize: None,
+                apps: BTreeMap::new(),
+            }),
+        }
+    }
+
+    Ok(libraries)
+}
+
+pub fn library_folders() -> Result<Vec<LibraryFolder>, S7Error> {
+    let mut libraries: Vec<LibraryFolder> = Vec::new();
+
+    for steam_install_path in steam_install_paths()? {
+        for library in install_library_folders(Path::new(&steam_install_path.path))? {
+            if !libraries
+                .iter()
+                .any(|existing| existing.path == library.path)
+            {
+                libraries.push(library);
+            }
+        }
+    }
+
+    Ok(libraries)
+}
+
+/// The libraries listed by one Steam installation's `libraryfolders.vdf`, or none if it
+/// has no such file.
+pub fn install_library_folders(steam_install_path: &Path) -> Result<Vec<LibraryFolder>, S7Error> {
+    let library_meta_file = steam_install_path
+        .join("steamapps")
+        .join("libraryfolders.vdf");
+
+    if !library_meta_file.exists() {
+        return Ok(Vec::new());
+    }
+
+    let file_data = fs::read_to_string(&library_meta_file)
+        .map_err(|e| S7Error::Io(format!("Failed to read library metadata file: {:?}", e)))?;
+
+    parse_library_folders(&file_data).map_err(|e| {
+        S7Error::Io(format!(
+            "Failed to parse {}: {}",
+            library_meta_file.to_string_lossy(),
+            e
+        ))
+    })
+}
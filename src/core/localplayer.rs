@@ -22,11 +22,11 @@
 //
 // Modified by Burak Kartal on [24/06/2025]
 
-use bincode::{Decode, Encode};
 use serde::Serialize;
 use steamworks::SteamId;
 
-#[derive(Debug, Clone, Serialize, Encode, Decode)]
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
 pub struct PlayerSteamId {
     pub steam_id64: u64,
     pub steam_id32: String,
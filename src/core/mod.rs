@@ -1,6 +1,15 @@
+pub mod call_result;
 pub mod client;
+#[cfg(feature = "fake-steam")]
+pub mod fake_backend;
+pub mod game_version;
+pub mod library_folders;
 pub mod localplayer;
+pub mod steam_backend;
 pub mod steam_install_paths;
 pub mod steam_manager;
+pub mod steamworks_backend;
+pub mod tag_rules;
+pub mod ugc_query;
 pub mod workshop;
 pub mod workshop_item;
@@ -0,0 +1,163 @@
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use steamworks::{FileType, ItemState};
+
+use crate::core::localplayer::PlayerSteamId;
+use crate::core::workshop::{ItemInstallInfo, UgcItemVisibility, UpdateProgress};
+use crate::core::workshop_item::workshop::{
+    UGCQueryType, UGCType, UserListOrder, UserListType, WorkshopItemsResult,
+};
+use crate::error::S7Error;
+use crate::utils::parse_date::DateRange;
+
+pub type BackendFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, S7Error>> + Send + 'a>>;
+
+pub type UpdateProgressSink = Box<dyn Fn(UpdateProgress) + Send>;
+
+#[derive(Debug, Clone)]
+pub struct UgcQuery {
+    pub query_type: UGCQueryType,
+    pub ugc_type: UGCType,
+    pub page: u32,
+    pub search_text: Option<String>,
+    pub trend_days: Option<u32>,
+    pub required_tags: Vec<String>,
+    pub excluded_tags: Vec<String>,
+    /// Match items with any of `required_tags` instead of all of them.
+    pub match_any_tag: bool,
+    /// Items need at least one tag from every group.
+    pub required_tag_groups: Vec<Vec<String>>,
+    pub created: DateRange,
+    pub updated: DateRange,
+}
+
+impl UgcQuery {
+    pub fn new(query_type: UGCQueryType, page: u32) -> Self {
+        Self {
+            query_type,
+            ugc_type: UGCType::Items,
+            page,
+            search_text: None,
+            trend_days: None,
+            required_tags: Vec::new(),
+            excluded_tags: Vec::new(),
+            match_any_tag: false,
+            required_tag_groups: Vec::new(),
+            created: DateRange::default(),
+            updated: DateRange::default(),
+        }
+    }
+}
+
+/// A page of one of a user's workshop lists, such as their published or favorited items.
+#[derive(Debug, Clone)]
+pub struct UserQuery {
+    pub account_id: u32,
+    pub list: UserListType,
+    pub order: UserListOrder,
+    pub ugc_type: UGCType,
+    pub page: u32,
+}
+
+/// The fields to change in one item update. `None` leaves a field as it is.
+#[derive(Debug, Clone, Default)]
+pub struct ItemUpdate {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub visibility: Option<UgcItemVisibility>,
+    pub tags: Option<Vec<String>>,
+    pub content_folder: Option<PathBuf>,
+    pub preview_image: Option<PathBuf>,
+    pub metadata: Option<String>,
+    pub change_note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SubmittedItem {
+    pub published_file_id: u64,
+    /// The item stays hidden until the author accepts the Steam Workshop legal agreement.
+    pub needs_legal_agreement: bool,
+}
+
+/// How far a batch of dependency changes got. `applied` lists the dependencies changed,
+/// in order, before `failed`, if set, stopped the batch.
+#[derive(Debug, Default)]
+pub struct DependencyChanges {
+    pub applied: Vec<u64>,
+    pub failed: Option<(u64, S7Error)>,
+}
+
+/// The Steam calls the commands depend on. `SteamworksBackend` talks to the running
+/// Steam client, `FakeBackend` serves a fixed catalog so commands can run without Steam.
+pub trait SteamBackend: Send + Sync {
+    fn query_items(&self, item_ids: Vec<u64>) -> BackendFuture<'_, WorkshopItemsResult>;
+
+    fn query_all(&self, query: UgcQuery) -> BackendFuture<'_, WorkshopItemsResult>;
+
+    fn query_user(&self, query: UserQuery) -> BackendFuture<'_, WorkshopItemsResult>;
+
+    fn subscribe_item(&self, item_id: u64) -> BackendFuture<'_, ()>;
+
+    fn unsubscribe_item(&self, item_id: u64) -> BackendFuture<'_, ()>;
+
+    fn subscribed_items(&self) -> Vec<u64>;
+
+    fn item_state(&self, item_id: u64) -> ItemState;
+
+    fn item_install_info(&self, item_id: u64) -> Option<ItemInstallInfo>;
+
+    fn item_download_info(&self, item_id: u64) -> Option<(u64, u64)>;
+
+    fn download_item(&self, item_id: u64, high_priority: bool) -> bool;
+
+    fn persona_names(&self, steam_ids: Vec<u64>) -> BackendFuture<'_, FxHashMap<u64, String>>;
+
+    /// The user the Steam client is logged in as.
+    fn local_user(&self) -> PlayerSteamId;
+
+    fn create_item(&self, file_type: FileType) -> BackendFuture<'_, SubmittedItem>;
+
+    /// Applies `update` and uploads any content, reporting each progress change.
+    fn update_item(
+        &self,
+        item_id: u64,
+        update: ItemUpdate,
+        on_progress: UpdateProgressSink,
+    ) -> BackendFuture<'_, SubmittedItem>;
+
+    fn delete_item(&self, item_id: u64) -> BackendFuture<'_, ()>;
+
+    /// Makes `dependency_ids` required items of `item_id`, one after the other so Steam
+    /// keeps their order, and stops at the first that fails.
+    fn add_dependencies(
+        &self,
+        item_id: u64,
+        dependency_ids: Vec<u64>,
+    ) -> BackendFuture<'_, DependencyChanges>;
+
+    fn remove_dependencies(
+        &self,
+        item_id: u64,
+        dependency_ids: Vec<u64>,
+    ) -> BackendFuture<'_, DependencyChanges>;
+
+    /// The apps, such as DLC, that `item_id` requires.
+    fn app_dependencies(&self, item_id: u64) -> BackendFuture<'_, Vec<u32>>;
+
+    fn add_app_dependency(&self, item_id: u64, app_id: u32) -> BackendFuture<'_, ()>;
+
+    fn remove_app_dependency(&self, item_id: u64, app_id: u32) -> BackendFuture<'_, ()>;
+
+    /// Votes an item up or down for the logged-in user, replacing any earlier vote.
+    fn set_item_vote(&self, item_id: u64, vote_up: bool) -> BackendFuture<'_, ()>;
+
+    /// The logged-in user's vote on an item, `true` for up, or `None` without one.
+    fn item_vote(&self, item_id: u64) -> BackendFuture<'_, Option<bool>>;
+
+    fn add_favorite(&self, item_id: u64) -> BackendFuture<'_, ()>;
+
+    fn remove_favorite(&self, item_id: u64) -> BackendFuture<'_, ()>;
+}
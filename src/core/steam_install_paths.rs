@@ -1,21 +1,160 @@
-use winreg::RegKey;
-use winreg::enums::*;
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
 
-pub fn steam_install_paths() -> Result<Vec<String>, String> {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+use crate::error::S7Error;
+
+/// Where an installation was found. Besides `EnvOverride`, only the sources the current
+/// platform probes exist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SteamInstallSource {
+    EnvOverride,
+    #[cfg(windows)]
+    Registry,
+    #[cfg(all(unix, not(target_os = "macos")))]
+    Native,
+    #[cfg(all(unix, not(target_os = "macos")))]
+    XdgData,
+    #[cfg(all(unix, not(target_os = "macos")))]
+    Flatpak,
+    #[cfg(all(unix, not(target_os = "macos")))]
+    Snap,
+    #[cfg(target_os = "macos")]
+    MacOs,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SteamInstallPath {
+    pub path: String,
+    pub source: SteamInstallSource,
+}
+
+pub fn steam_install_paths() -> Result<Vec<SteamInstallPath>, S7Error> {
+    let mut candidates: Vec<(PathBuf, SteamInstallSource)> = Vec::new();
+
+    if let Some(steam_root) = env::var_os("STEAM_ROOT")
+        && !steam_root.is_empty()
+    {
+        candidates.push((PathBuf::from(steam_root), SteamInstallSource::EnvOverride));
+    }
+
+    candidates.extend(platform_candidates());
+
+    let mut seen = Vec::new();
     let mut paths = Vec::new();
 
-    if let Ok(steam_key) = hkcu.open_subkey("SOFTWARE\\Valve\\Steam") {
-        if let Ok(steam_path) = steam_key.get_value::<String, _>("SteamPath") {
-            paths.push(steam_path);
+    for (candidate, source) in candidates {
+        if !candidate.is_dir() {
+            continue;
         }
-    }
 
-    if let Ok(steam_key) = hkcu.open_subkey("SOFTWARE\\Wow6432Node\\Valve\\Steam") {
-        if let Ok(steam_path) = steam_key.get_value::<String, _>("SteamPath") {
-            paths.push(steam_path);
+        // ~/.steam/steam and ~/.steam/root are usually symlinks to the real
+        // install, so compare canonical paths to avoid reporting it twice.
+        let canonical = fs::canonicalize(&candidate).unwrap_or_else(|_| candidate.clone());
+        if seen.contains(&canonical) {
+            continue;
         }
+        seen.push(canonical);
+
+        paths.push(SteamInstallPath {
+            path: candidate.to_string_lossy().into_owned(),
+            source,
+        });
     }
 
     Ok(paths)
 }
+
+#[cfg(windows)]
+fn platform_candidates() -> Vec<(PathBuf, SteamInstallSource)> {
+    use winreg::RegKey;
+    use winreg::enums::*;
+
+    let mut candidates = Vec::new();
+
+    // The Steam client records itself per user, and its installer per machine. On
+    // 64-bit Windows the installer's 32-bit key is redirected under Wow6432Node.
+    for (hive, subkey, value) in [
+        (HKEY_CURRENT_USER, "SOFTWARE\\Valve\\Steam", "SteamPath"),
+        (
+            HKEY_LOCAL_MACHINE,
+            "SOFTWARE\\Wow6432Node\\Valve\\Steam",
+            "InstallPath",
+        ),
+        (HKEY_LOCAL_MACHINE, "SOFTWARE\\Valve\\Steam", "InstallPath"),
+    ] {
+        if let Ok(steam_key) = RegKey::predef(hive).open_subkey(subkey)
+            && let Ok(steam_path) = steam_key.get_value::<String, _>(value)
+        {
+            candidates.push((PathBuf::from(steam_path), SteamInstallSource::Registry));
+        }
+    }
+
+    candidates
+}
+
+#[cfg(target_os = "macos")]
+fn platform_candidates() -> Vec<(PathBuf, SteamInstallSource)> {
+    let Some(home) = home_dir() else {
+        return Vec::new();
+    };
+
+    vec![(
+        home.join("Library")
+            .join("Application Support")
+            .join("Steam"),
+        SteamInstallSource::MacOs,
+    )]
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn platform_candidates() -> Vec<(PathBuf, SteamInstallSource)> {
+    let Some(home) = home_dir() else {
+        return Vec::new();
+    };
+
+    let xdg_data_home = env::var_os("XDG_DATA_HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".local").join("share"));
+
+    let flatpak_root = home
+        .join(".var")
+        .join("app")
+        .join("com.valvesoftware.Steam");
+    let snap_root = home.join("snap").join("steam").join("common");
+
+    vec![
+        (
+            home.join(".steam").join("steam"),
+            SteamInstallSource::Native,
+        ),
+        (home.join(".steam").join("root"), SteamInstallSource::Native),
+        (xdg_data_home.join("Steam"), SteamInstallSource::XdgData),
+        (
+            flatpak_root.join(".local").join("share").join("Steam"),
+            SteamInstallSource::Flatpak,
+        ),
+        (
+            flatpak_root.join(".steam").join("steam"),
+            SteamInstallSource::Flatpak,
+        ),
+        (
+            snap_root.join(".local").join("share").join("Steam"),
+            SteamInstallSource::Snap,
+        ),
+        (
+            snap_root.join(".steam").join("steam"),
+            SteamInstallSource::Snap,
+        ),
+    ]
+}
+
+#[cfg(unix)]
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
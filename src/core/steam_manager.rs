@@ -1,44 +1,103 @@
 use once_cell::sync::Lazy;
+#[cfg(feature = "fake-steam")]
+use std::path::PathBuf;
+use std::sync::Arc;
+#[cfg(feature = "fake-steam")]
+use std::sync::Mutex;
 use steamworks::Client;
 
 use crate::core::client::SteamState;
+#[cfg(feature = "fake-steam")]
+use crate::core::fake_backend::{FAKE_CATALOG_ENV, FakeBackend};
+use crate::core::steam_backend::SteamBackend;
+use crate::core::steamworks_backend::SteamworksBackend;
+use crate::error::S7Error;
 
 pub static STEAM_MANAGER: Lazy<SteamManager> = Lazy::new(SteamManager::new);
 
 pub struct SteamManager {
     steam_state: SteamState,
+    #[cfg(feature = "fake-steam")]
+    fake_backend: Mutex<Option<(u32, Arc<FakeBackend>)>>,
 }
 
 impl SteamManager {
     pub fn new() -> Self {
         Self {
             steam_state: SteamState::new(),
+            #[cfg(feature = "fake-steam")]
+            fake_backend: Mutex::new(None),
         }
     }
 
-    pub async fn initialize_client(&self, app_id: u32) -> Result<steamworks::Client, String> {
+    pub fn initialize_client(&self, app_id: u32) -> Result<steamworks::Client, S7Error> {
         if !self.steam_state.has_client(app_id) {
+            // The SDK ties a process to one app, so switching apps means shutting the
+            // current client down. Daemon modes avoid this with `SteamWorkers`.
             self.steam_state.drop_all_clients();
-            let (steam_client, single_client) = Client::init_app(app_id)
-                .map_err(|err| format!("Failed to initialize Steam client: {:?}", err))?;
+            let (steam_client, single_client) =
+                Client::init_app(app_id).map_err(S7Error::steam_init)?;
             self.steam_state
                 .set_clients(app_id, steam_client, single_client);
         }
 
         self.steam_state
             .get_client(app_id)
-            .ok_or_else(|| "Failed to get Steam client".to_string())
+            .ok_or_else(|| S7Error::SteamNotRunning("Failed to get Steam client".to_string()))
     }
 
-    pub fn run_callbacks(&self, app_id: u32) -> Result<(), String> {
+    pub fn backend(&self, app_id: u32) -> Result<Arc<dyn SteamBackend>, S7Error> {
+        #[cfg(feature = "fake-steam")]
+        if let Some(catalog_path) = std::env::var_os(FAKE_CATALOG_ENV) {
+            let mut fake_backend = self.fake_backend.lock().unwrap();
+            if let Some((current_app_id, backend)) = fake_backend.as_ref()
+                && *current_app_id == app_id
+            {
+                return Ok(backend.clone());
+            }
+
+            let backend = Arc::new(FakeBackend::load(app_id, &PathBuf::from(catalog_path))?);
+            *fake_backend = Some((app_id, backend.clone()));
+            return Ok(backend);
+        }
+
+        let client = self.initialize_client(app_id)?;
+        Ok(Arc::new(SteamworksBackend::new(app_id, client)))
+    }
+
+    pub fn initialized_apps(&self) -> Vec<u32> {
+        #[cfg(feature = "fake-steam")]
+        let fake_app_id = self
+            .fake_backend
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|(app_id, _)| *app_id);
+        #[cfg(not(feature = "fake-steam"))]
+        let fake_app_id = None;
+
+        self.steam_state
+            .app_id()
+            .into_iter()
+            .chain(fake_app_id)
+            .collect()
+    }
+
+    /// Pumps the Steam client's callbacks. The fake backend answers at once and has
+    /// none to pump.
+    pub fn run_callbacks(&self, app_id: u32) -> Result<(), S7Error> {
+        #[cfg(feature = "fake-steam")]
+        if std::env::var_os(FAKE_CATALOG_ENV).is_some() {
+            return Ok(());
+        }
         self.steam_state.run_callbacks(app_id)
     }
 }
 
-pub async fn initialize_client(app_id: u32) -> Result<steamworks::Client, String> {
-    STEAM_MANAGER.initialize_client(app_id).await
+pub fn backend(app_id: u32) -> Result<Arc<dyn SteamBackend>, S7Error> {
+    STEAM_MANAGER.backend(app_id)
 }
 
-pub fn run_callbacks(app_id: u32) -> Result<(), String> {
+pub fn run_callbacks(app_id: u32) -> Result<(), S7Error> {
     STEAM_MANAGER.run_callbacks(app_id)
 }
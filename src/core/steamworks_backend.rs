@@ -0,0 +1,712 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::time::Duration;
+use steamworks::{
+    AppId, Client, ClientManager, FileType, ItemState, PublishedFileId, SteamError, SteamId,
+    UpdateWatchHandle, sys,
+};
+
+use crate::core::call_result::{
+    CallResultSender, RawCallResult, call_result, check_result, confirm_effect, default_timeout,
+    pump_until, raw_call_result, watch_call_result,
+};
+use crate::core::localplayer::PlayerSteamId;
+use crate::core::steam_backend::{
+    BackendFuture, DependencyChanges, ItemUpdate, SteamBackend, SubmittedItem, UgcQuery,
+    UpdateProgressSink, UserQuery,
+};
+use crate::core::ugc_query::RawUgcQuery;
+use crate::core::workshop::{ItemInstallInfo, UpdateProgress};
+use crate::core::workshop_item::workshop::{UserListType, WorkshopItem, WorkshopItemsResult};
+use crate::error::S7Error;
+use crate::utils::user_lists::user_list_ids;
+
+const PERSONA_NAME_TIMEOUT: Duration = Duration::from_secs(2);
+// Uploads are bounded by the content size rather than Steam's response time.
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+pub struct SteamworksBackend {
+    app_id: u32,
+    client: Client,
+    timeout: Duration,
+}
+
+impl SteamworksBackend {
+    pub fn new(app_id: u32, client: Client) -> Self {
+        Self {
+            app_id,
+            client,
+            timeout: default_timeout(),
+        }
+    }
+}
+
+impl SteamBackend for SteamworksBackend {
+    fn query_items(&self, item_ids: Vec<u64>) -> BackendFuture<'_, WorkshopItemsResult> {
+        Box::pin(async move {
+            RawUgcQuery::items(&item_ids)?
+                .fetch(self.app_id, self.timeout)
+                .await
+        })
+    }
+
+    fn query_all(&self, query: UgcQuery) -> BackendFuture<'_, WorkshopItemsResult> {
+        Box::pin(async move {
+            let raw_query =
+                RawUgcQuery::all(self.app_id, query.query_type, query.ugc_type, query.page)?;
+            if let Some(search_text) = &query.search_text {
+                raw_query.set_search_text(search_text);
+            }
+            if let Some(days) = query.trend_days {
+                raw_query.set_ranked_by_trend_days(days);
+            }
+            for tag in &query.required_tags {
+                raw_query.add_required_tag(tag);
+            }
+            for tag in &query.excluded_tags {
+                raw_query.add_excluded_tag(tag);
+            }
+            if query.match_any_tag {
+                raw_query.set_match_any_tag(true);
+            }
+            for group in &query.required_tag_groups {
+                raw_query.add_required_tag_group(group);
+            }
+            if !query.created.is_unbounded() {
+                raw_query.set_time_created_range(query.created);
+            }
+            if !query.updated.is_unbounded() {
+                raw_query.set_time_updated_range(query.updated);
+            }
+
+            raw_query.fetch(self.app_id, self.timeout).await
+        })
+    }
+
+    fn query_user(&self, query: UserQuery) -> BackendFuture<'_, WorkshopItemsResult> {
+        Box::pin(async move {
+            RawUgcQuery::user(
+                self.app_id,
+                query.account_id,
+                query.list,
+                query.ugc_type,
+                query.order,
+                query.page,
+            )?
+            .fetch(self.app_id, self.timeout)
+            .await
+        })
+    }
+
+    fn subscribe_item(&self, item_id: u64) -> BackendFuture<'_, ()> {
+        let client = self.client.clone();
+        Box::pin(call_result(self.app_id, self.timeout, move |sender| {
+            client
+                .ugc()
+                .subscribe_item(PublishedFileId(item_id), move |result| {
+                    sender.send(result.map_err(S7Error::steam_api));
+                });
+            Ok(())
+        }))
+    }
+
+    fn unsubscribe_item(&self, item_id: u64) -> BackendFuture<'_, ()> {
+        let client = self.client.clone();
+        Box::pin(call_result(self.app_id, self.timeout, move |sender| {
+            client
+                .ugc()
+                .unsubscribe_item(PublishedFileId(item_id), move |result| {
+                    sender.send(result.map_err(S7Error::steam_api));
+                });
+            Ok(())
+        }))
+    }
+
+    fn subscribed_items(&self) -> Vec<u64> {
+        self.client
+            .ugc()
+            .subscribed_items()
+            .into_iter()
+            .map(|id| id.0)
+            .collect()
+    }
+
+    fn item_state(&self, item_id: u64) -> ItemState {
+        self.client.ugc().item_state(PublishedFileId(item_id))
+    }
+
+    fn item_install_info(&self, item_id: u64) -> Option<ItemInstallInfo> {
+        self.client
+            .ugc()
+            .item_install_info(PublishedFileId(item_id))
+            .map(ItemInstallInfo::from)
+    }
+
+    fn item_download_info(&self, item_id: u64) -> Option<(u64, u64)> {
+        self.client
+            .ugc()
+            .item_download_info(PublishedFileId(item_id))
+    }
+
+    fn download_item(&self, item_id: u64, high_priority: bool) -> bool {
+        self.client
+            .ugc()
+            .download_item(PublishedFileId(item_id), high_priority)
+    }
+
+    fn persona_names(&self, steam_ids: Vec<u64>) -> BackendFuture<'_, FxHashMap<u64, String>> {
+        let client = self.client.clone();
+        let app_id = self.app_id;
+        Box::pin(async move {
+            // `Friends` is not `Send`, so it is fetched again rather than held across awaits.
+            let is_unknown = |id: SteamId| client.friends().get_friend(id).name() == "[unknown]";
+
+            let unique_creator_ids: FxHashSet<SteamId> =
+                steam_ids.into_iter().map(SteamId::from_raw).collect();
+
+            let mut unknown_creators: FxHashSet<SteamId> = unique_creator_ids
+                .iter()
+                .copied()
+                .filter(|&id| is_unknown(id))
+                .collect();
+            for &creator_id in &unknown_creators {
+                let _ = client.friends().request_user_information(creator_id, true);
+            }
+
+            // Names that don't arrive in time are reported as "[unknown]".
+            if !unknown_creators.is_empty() {
+                pump_until(app_id, PERSONA_NAME_TIMEOUT, || {
+                    unknown_creators.retain(|&id| is_unknown(id));
+                    unknown_creators.is_empty().then_some(())
+                })
+                .await?;
+            }
+
+            let friends = client.friends();
+            Ok(unique_creator_ids
+                .into_iter()
+                .map(|id| (id.raw(), friends.get_friend(id).name()))
+                .collect())
+        })
+    }
+
+    fn local_user(&self) -> PlayerSteamId {
+        PlayerSteamId::from_steamid(self.client.user().steam_id())
+    }
+
+    fn create_item(&self, file_type: FileType) -> BackendFuture<'_, SubmittedItem> {
+        let client = self.client.clone();
+        let app_id = self.app_id;
+        Box::pin(call_result(self.app_id, self.timeout, move |sender| {
+            client
+                .ugc()
+                .create_item(AppId(app_id), file_type, move |result| {
+                    sender.send(result.map(submitted_item).map_err(S7Error::steam_api));
+                });
+            Ok(())
+        }))
+    }
+
+    fn update_item(
+        &self,
+        item_id: u64,
+        update: ItemUpdate,
+        on_progress: UpdateProgressSink,
+    ) -> BackendFuture<'_, SubmittedItem> {
+        let client = self.client.clone();
+        let app_id = self.app_id;
+        let mut last_progress = None;
+
+        let start = move |sender: CallResultSender<SubmittedItem>| {
+            let mut handle = client
+                .ugc()
+                .start_item_update(AppId(app_id), PublishedFileId(item_id));
+
+            if let Some(title) = &update.title {
+                handle = handle.title(title);
+            }
+            if let Some(description) = &update.description {
+                handle = handle.description(description);
+            }
+            if let Some(visibility) = update.visibility {
+                handle = handle.visibility(visibility.into());
+            }
+            if let Some(tags) = update.tags {
+                handle = handle.tags(tags, false);
+            }
+            if let Some(content_folder) = &update.content_folder {
+                handle = handle.content_path(content_folder);
+            }
+            if let Some(preview_image) = &update.preview_image {
+                handle = handle.preview_path(preview_image);
+            }
+            if let Some(metadata) = &update.metadata {
+                handle = handle.metadata(metadata);
+            }
+
+            let watch_handle = handle.submit(update.change_note.as_deref(), move |result| {
+                sender.send(result.map(submitted_item).map_err(S7Error::steam_api));
+            });
+            Ok(watch_handle)
+        };
+
+        let watch = move |watch_handle: &UpdateWatchHandle<ClientManager>| {
+            let (status, bytes_processed, bytes_total) = watch_handle.progress();
+            let progress = UpdateProgress {
+                item_id,
+                status: status.into(),
+                bytes_processed,
+                bytes_total,
+            };
+            if last_progress != Some(progress) {
+                last_progress = Some(progress);
+                on_progress(progress);
+            }
+        };
+
+        Box::pin(watch_call_result(self.app_id, UPLOAD_TIMEOUT, start, watch))
+    }
+
+    fn delete_item(&self, item_id: u64) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            // steamworks 0.11 reads DeleteItem's call result with the layout of a different
+            // struct, so the call is made and its result read directly.
+            let result = raw_call_result(
+                self.app_id,
+                self.timeout,
+                sys::DeleteItemResult_t_k_iCallback as i32,
+                || unsafe {
+                    sys::SteamAPI_ISteamUGC_DeleteItem(sys::SteamAPI_SteamUGC_v018(), item_id)
+                },
+            )
+            .await?;
+            match result {
+                RawCallResult::Completed(sys::DeleteItemResult_t { m_eResult, .. }) => {
+                    check_result(m_eResult)
+                }
+                RawCallResult::Unread => {
+                    self.wait_for_item(item_id, "deleting it", |item| item.is_none())
+                        .await
+                }
+            }
+        })
+    }
+
+    fn add_dependencies(
+        &self,
+        item_id: u64,
+        dependency_ids: Vec<u64>,
+    ) -> BackendFuture<'_, DependencyChanges> {
+        Box::pin(self.change_dependencies(item_id, dependency_ids, true))
+    }
+
+    fn remove_dependencies(
+        &self,
+        item_id: u64,
+        dependency_ids: Vec<u64>,
+    ) -> BackendFuture<'_, DependencyChanges> {
+        Box::pin(self.change_dependencies(item_id, dependency_ids, false))
+    }
+
+    fn app_dependencies(&self, item_id: u64) -> BackendFuture<'_, Vec<u32>> {
+        Box::pin(async move {
+            let result = raw_call_result(
+                self.app_id,
+                self.timeout,
+                sys::GetAppDependenciesResult_t_k_iCallback as i32,
+                || unsafe {
+                    sys::SteamAPI_ISteamUGC_GetAppDependencies(
+                        sys::SteamAPI_SteamUGC_v018(),
+                        item_id,
+                    )
+                },
+            )
+            .await?;
+            // Nothing else reports an item's app dependencies to fall back on.
+            let RawCallResult::Completed(sys::GetAppDependenciesResult_t {
+                m_eResult,
+                m_rgAppIDs,
+                m_nNumAppDependencies,
+                ..
+            }) = result
+            else {
+                return Err(S7Error::steam_api(SteamError::IOFailure));
+            };
+            check_result(m_eResult)?;
+
+            // Steam sends at most 32 apps per result, far more than an item needs.
+            let count = (m_nNumAppDependencies as usize).min(m_rgAppIDs.len());
+            Ok(m_rgAppIDs[..count].to_vec())
+        })
+    }
+
+    fn add_app_dependency(&self, item_id: u64, app_id: u32) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            let result = raw_call_result(
+                self.app_id,
+                self.timeout,
+                sys::AddAppDependencyResult_t_k_iCallback as i32,
+                || unsafe {
+                    sys::SteamAPI_ISteamUGC_AddAppDependency(
+                        sys::SteamAPI_SteamUGC_v018(),
+                        item_id,
+                        app_id,
+                    )
+                },
+            )
+            .await?;
+            match result {
+                RawCallResult::Completed(sys::AddAppDependencyResult_t { m_eResult, .. }) => {
+                    check_result(m_eResult)
+                }
+                RawCallResult::Unread => {
+                    self.wait_for_app_dependency(item_id, app_id, true, "adding the app dependency")
+                        .await
+                }
+            }
+        })
+    }
+
+    fn remove_app_dependency(&self, item_id: u64, app_id: u32) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            let result = raw_call_result(
+                self.app_id,
+                self.timeout,
+                sys::RemoveAppDependencyResult_t_k_iCallback as i32,
+                || unsafe {
+                    sys::SteamAPI_ISteamUGC_RemoveAppDependency(
+                        sys::SteamAPI_SteamUGC_v018(),
+                        item_id,
+                        app_id,
+                    )
+                },
+            )
+            .await?;
+            match result {
+                RawCallResult::Completed(sys::RemoveAppDependencyResult_t {
+                    m_eResult, ..
+                }) => check_result(m_eResult),
+                RawCallResult::Unread => {
+                    self.wait_for_app_dependency(
+                        item_id,
+                        app_id,
+                        false,
+                        "removing the app dependency",
+                    )
+                    .await
+                }
+            }
+        })
+    }
+
+    fn set_item_vote(&self, item_id: u64, vote_up: bool) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            let result = raw_call_result(
+                self.app_id,
+                self.timeout,
+                sys::SetUserItemVoteResult_t_k_iCallback as i32,
+                || unsafe {
+                    sys::SteamAPI_ISteamUGC_SetUserItemVote(
+                        sys::SteamAPI_SteamUGC_v018(),
+                        item_id,
+                        vote_up,
+                    )
+                },
+            )
+            .await?;
+            match result {
+                RawCallResult::Completed(sys::SetUserItemVoteResult_t { m_eResult, .. }) => {
+                    check_result(m_eResult)
+                }
+                RawCallResult::Unread => {
+                    let list = if vote_up {
+                        UserListType::VotedUp
+                    } else {
+                        UserListType::VotedDown
+                    };
+                    self.wait_for_user_list(list, item_id, true, "the vote")
+                        .await
+                }
+            }
+        })
+    }
+
+    fn item_vote(&self, item_id: u64) -> BackendFuture<'_, Option<bool>> {
+        Box::pin(async move {
+            let result = raw_call_result(
+                self.app_id,
+                self.timeout,
+                sys::GetUserItemVoteResult_t_k_iCallback as i32,
+                || unsafe {
+                    sys::SteamAPI_ISteamUGC_GetUserItemVote(sys::SteamAPI_SteamUGC_v018(), item_id)
+                },
+            )
+            .await?;
+            match result {
+                RawCallResult::Completed(sys::GetUserItemVoteResult_t {
+                    m_eResult,
+                    m_bVotedUp,
+                    m_bVotedDown,
+                    ..
+                }) => {
+                    check_result(m_eResult)?;
+                    Ok(if m_bVotedUp {
+                        Some(true)
+                    } else if m_bVotedDown {
+                        Some(false)
+                    } else {
+                        None
+                    })
+                }
+                RawCallResult::Unread => {
+                    if user_list_ids(self, UserListType::VotedUp)
+                        .await?
+                        .contains(&item_id)
+                    {
+                        Ok(Some(true))
+                    } else if user_list_ids(self, UserListType::VotedDown)
+                        .await?
+                        .contains(&item_id)
+                    {
+                        Ok(Some(false))
+                    } else {
+                        Ok(None)
+                    }
+                }
+            }
+        })
+    }
+
+    fn add_favorite(&self, item_id: u64) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            let result = raw_call_result(
+                self.app_id,
+                self.timeout,
+                sys::UserFavoriteItemsListChanged_t_k_iCallback as i32,
+                || unsafe {
+                    sys::SteamAPI_ISteamUGC_AddItemToFavorites(
+                        sys::SteamAPI_SteamUGC_v018(),
+                        self.app_id,
+                        item_id,
+                    )
+                },
+            )
+            .await?;
+            match result {
+                RawCallResult::Completed(sys::UserFavoriteItemsListChanged_t {
+                    m_eResult, ..
+                }) => check_result(m_eResult),
+                RawCallResult::Unread => {
+                    self.wait_for_user_list(
+                        UserListType::Favorited,
+                        item_id,
+                        true,
+                        "adding the favorite",
+                    )
+                    .await
+                }
+            }
+        })
+    }
+
+    fn remove_favorite(&self, item_id: u64) -> BackendFuture<'_, ()> {
+        Box::pin(async move {
+            let result = raw_call_result(
+                self.app_id,
+                self.timeout,
+                sys::UserFavoriteItemsListChanged_t_k_iCallback as i32,
+                || unsafe {
+                    sys::SteamAPI_ISteamUGC_RemoveItemFromFavorites(
+                        sys::SteamAPI_SteamUGC_v018(),
+                        self.app_id,
+                        item_id,
+                    )
+                },
+            )
+            .await?;
+            match result {
+                RawCallResult::Completed(sys::UserFavoriteItemsListChanged_t {
+                    m_eResult, ..
+                }) => check_result(m_eResult),
+                RawCallResult::Unread => {
+                    self.wait_for_user_list(
+                        UserListType::Favorited,
+                        item_id,
+                        false,
+                        "removing the favorite",
+                    )
+                    .await
+                }
+            }
+        })
+    }
+}
+
+impl SteamworksBackend {
+    // Confirms a call whose result is not read by querying the item until `done`
+    // accepts what Steam returns. A deleted item is passed as `None`.
+    async fn wait_for_item<F>(&self, item_id: u64, action: &str, done: F) -> Result<(), S7Error>
+    where
+        F: Fn(Option<&WorkshopItem>) -> bool,
+    {
+        let confirmed = confirm_effect(self.timeout, || async {
+            match self.query_items(vec![item_id]).await {
+                Ok(result) => Ok(done(result.items.first().and_then(Option::as_ref))),
+                Err(S7Error::NotFound(_)) => Ok(done(None)),
+                Err(error) => Err(error),
+            }
+        })
+        .await?;
+
+        if confirmed {
+            return Ok(());
+        }
+        Err(self.not_reflected(action, item_id))
+    }
+
+    // Makes the calls one at a time, so Steam keeps the order of a collection's items.
+    // Calls whose results were not read are confirmed together by one item query.
+    async fn change_dependencies(
+        &self,
+        item_id: u64,
+        dependency_ids: Vec<u64>,
+        add: bool,
+    ) -> Result<DependencyChanges, S7Error> {
+        let mut changes = DependencyChanges::default();
+        let mut unconfirmed = Vec::new();
+        for dependency_id in dependency_ids {
+            let result = match self.dependency_call(item_id, dependency_id, add).await {
+                Ok(RawCallResult::Completed(result)) => check_result(result),
+                Ok(RawCallResult::Unread) => {
+                    unconfirmed.push(dependency_id);
+                    Ok(())
+                }
+                Err(error) => Err(error),
+            };
+            if let Err(error) = result {
+                changes.failed = Some((dependency_id, error));
+                break;
+            }
+            changes.applied.push(dependency_id);
+        }
+
+        if let Some(&first_unconfirmed) = unconfirmed.first() {
+            let action = if add {
+                "adding the dependencies"
+            } else {
+                "removing the dependencies"
+            };
+            let confirmed = self
+                .wait_for_item(item_id, action, |item| {
+                    item.is_some_and(|item| {
+                        unconfirmed
+                            .iter()
+                            .all(|id| item.required_items.contains(id) == add)
+                    })
+                })
+                .await;
+            if let Err(error) = confirmed {
+                changes.applied.retain(|id| !unconfirmed.contains(id));
+                changes.failed = Some((first_unconfirmed, error));
+            }
+        }
+        Ok(changes)
+    }
+
+    async fn dependency_call(
+        &self,
+        item_id: u64,
+        dependency_id: u64,
+        add: bool,
+    ) -> Result<RawCallResult<sys::EResult>, S7Error> {
+        // Not wrapped by steamworks 0.11.
+        if add {
+            let result = raw_call_result(
+                self.app_id,
+                self.timeout,
+                sys::AddUGCDependencyResult_t_k_iCallback as i32,
+                || unsafe {
+                    sys::SteamAPI_ISteamUGC_AddDependency(
+                        sys::SteamAPI_SteamUGC_v018(),
+                        item_id,
+                        dependency_id,
+                    )
+                },
+            )
+            .await?;
+            Ok(result.map(|sys::AddUGCDependencyResult_t { m_eResult, .. }| m_eResult))
+        } else {
+            let result = raw_call_result(
+                self.app_id,
+                self.timeout,
+                sys::RemoveUGCDependencyResult_t_k_iCallback as i32,
+                || unsafe {
+                    sys::SteamAPI_ISteamUGC_RemoveDependency(
+                        sys::SteamAPI_SteamUGC_v018(),
+                        item_id,
+                        dependency_id,
+                    )
+                },
+            )
+            .await?;
+            Ok(result.map(|sys::RemoveUGCDependencyResult_t { m_eResult, .. }| m_eResult))
+        }
+    }
+
+    // Like `wait_for_item`, for calls that add an item to or remove it from one of the
+    // logged-in user's lists.
+    async fn wait_for_user_list(
+        &self,
+        list: UserListType,
+        item_id: u64,
+        listed: bool,
+        action: &str,
+    ) -> Result<(), S7Error> {
+        let confirmed = confirm_effect(self.timeout, || async {
+            Ok(user_list_ids(self, list).await?.contains(&item_id) == listed)
+        })
+        .await?;
+
+        if confirmed {
+            return Ok(());
+        }
+        Err(self.not_reflected(action, item_id))
+    }
+
+    // Like `wait_for_item`, for an app the item requires.
+    async fn wait_for_app_dependency(
+        &self,
+        item_id: u64,
+        app_id: u32,
+        required: bool,
+        action: &str,
+    ) -> Result<(), S7Error> {
+        let confirmed = confirm_effect(self.timeout, || async {
+            Ok(self.app_dependencies(item_id).await?.contains(&app_id) == required)
+        })
+        .await?;
+
+        if confirmed {
+            return Ok(());
+        }
+        Err(self.not_reflected(action, item_id))
+    }
+
+    fn not_reflected(&self, action: &str, item_id: u64) -> S7Error {
+        S7Error::Timeout {
+            seconds: self.timeout.as_secs(),
+            message: format!(
+                "Steam did not reflect {} on item {} within {} seconds",
+                action,
+                item_id,
+                self.timeout.as_secs()
+            ),
+        }
+    }
+}
+
+fn submitted_item((file_id, needs_legal_agreement): (PublishedFileId, bool)) -> SubmittedItem {
+    SubmittedItem {
+        published_file_id: file_id.0,
+        needs_legal_agreement,
+    }
+}
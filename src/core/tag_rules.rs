@@ -0,0 +1,138 @@
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::core::workshop_item::is_version_tag;
+use crate::error::S7Error;
+
+pub const TAG_RULES_ENV: &str = "S7FORGE_TAG_RULES";
+
+const TAG_RULES_FILE: &str = "tag_rules.toml";
+
+/// One section of the tag rules file. Fields left out fall back to `[default]`, then to
+/// the built-in rules.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TagRulesSection {
+    exclude: Option<Vec<String>>,
+    exclude_versions: Option<bool>,
+    capitalize: Option<bool>,
+    rename: Option<FxHashMap<String, String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TagRulesFile {
+    #[serde(default)]
+    default: TagRulesSection,
+    #[serde(default)]
+    apps: FxHashMap<String, TagRulesSection>,
+}
+
+/// How an app's raw tags become the display list: excluded tags are dropped, renamed
+/// tags are shown as given, and the rest are capitalized.
+#[derive(Debug, Clone)]
+pub struct TagRules {
+    exclude: Vec<String>,
+    exclude_versions: bool,
+    capitalize: bool,
+    rename: FxHashMap<String, String>,
+}
+
+impl Default for TagRules {
+    fn default() -> Self {
+        Self {
+            exclude: ["mod", "Singleplayer", "Native"].map(String::from).to_vec(),
+            exclude_versions: true,
+            capitalize: true,
+            rename: FxHashMap::from_iter([("ui".to_string(), "UI".to_string())]),
+        }
+    }
+}
+
+impl TagRules {
+    /// The rules for `app_id` from `tag_rules.toml` next to the executable, or the file
+    /// named by `S7FORGE_TAG_RULES`. Without a file the built-in rules apply.
+    pub fn for_app(app_id: u32) -> Result<Self, S7Error> {
+        let Some(path) = rules_path() else {
+            return Ok(Self::default());
+        };
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| S7Error::Io(format!("Failed to read {}: {}", path.display(), e)))?;
+        let file = parse_rules_file(&content).map_err(|e| {
+            S7Error::InvalidArgument(format!("Invalid tag rules in {}: {}", path.display(), e))
+        })?;
+
+        let mut rules = Self::default();
+        rules.apply(file.default);
+        if let Some(section) = file.apps.get(&app_id.to_string()) {
+            rules.apply(section.clone());
+        }
+        Ok(rules)
+    }
+
+    fn apply(&mut self, section: TagRulesSection) {
+        if let Some(exclude) = section.exclude {
+            self.exclude = exclude;
+        }
+        if let Some(exclude_versions) = section.exclude_versions {
+            self.exclude_versions = exclude_versions;
+        }
+        if let Some(capitalize) = section.capitalize {
+            self.capitalize = capitalize;
+        }
+        if let Some(rename) = section.rename {
+            self.rename = rename
+                .into_iter()
+                .map(|(tag, name)| (tag.to_lowercase(), name))
+                .collect();
+        }
+    }
+
+    pub fn display_tags(&self, tags: &[String]) -> Vec<String> {
+        tags.iter()
+            .filter(|tag| !self.is_excluded(tag))
+            .map(|tag| match self.rename.get(&tag.to_lowercase()) {
+                Some(name) => name.clone(),
+                None if self.capitalize => capitalize(tag),
+                None => tag.clone(),
+            })
+            .collect()
+    }
+
+    fn is_excluded(&self, tag: &str) -> bool {
+        self.exclude.iter().any(|excluded| excluded == tag)
+            || (self.exclude_versions && is_version_tag(tag))
+    }
+}
+
+#[cfg(feature = "tag-rules")]
+fn parse_rules_file(content: &str) -> Result<TagRulesFile, String> {
+    toml::from_str(content).map_err(|e| e.to_string())
+}
+
+// A rules file that cannot be read must not be ignored silently.
+#[cfg(not(feature = "tag-rules"))]
+fn parse_rules_file(_content: &str) -> Result<TagRulesFile, String> {
+    Err("s7forge was built without the tag-rules feature".to_string())
+}
+
+fn rules_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(TAG_RULES_ENV) {
+        return Some(PathBuf::from(path));
+    }
+    let exe_path = std::env::current_exe().ok()?;
+    Some(exe_path.parent()?.join(TAG_RULES_FILE))
+}
+
+fn capitalize(tag: &str) -> String {
+    let mut chars = tag.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
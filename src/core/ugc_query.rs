@@ -0,0 +1,424 @@
+use std::ffi::{CString, c_char};
+use std::time::Duration;
+use steamworks::{
+    AppId, FileType, PublishedFileId, PublishedFileVisibility, QueryResult, SteamError, SteamId,
+    UGCStatisticType, sys,
+};
+
+use crate::core::call_result::{RawCallResult, check_result, raw_call_result};
+use crate::core::workshop::PreviewType;
+use crate::core::workshop_item::workshop::{
+    AdditionalPreview, UGCQueryType, UGCType, UserListOrder, UserListType, WorkshopItemsResult,
+};
+use crate::error::S7Error;
+use crate::utils::parse_date::DateRange;
+
+/// A UGC query made through the raw bindings, since steamworks keeps its query handle
+/// private and doesn't read additional previews back. Every query asks for the same
+/// item details, so items look alike whichever query found them. The handle is released
+/// when the query is dropped.
+pub struct RawUgcQuery {
+    handle: sys::UGCQueryHandle_t,
+}
+
+impl RawUgcQuery {
+    /// A page of every item of `app_id` of `ugc_type`, ranked by `query_type`.
+    pub fn all(
+        app_id: u32,
+        query_type: UGCQueryType,
+        ugc_type: UGCType,
+        page: u32,
+    ) -> Result<Self, S7Error> {
+        let query_type: steamworks::UGCQueryType = query_type.into();
+        let ugc_type: steamworks::UGCType = ugc_type.into();
+        Self::new(unsafe {
+            sys::SteamAPI_ISteamUGC_CreateQueryAllUGCRequestPage(
+                ugc(),
+                query_type.into(),
+                ugc_type.into(),
+                app_id,
+                app_id,
+                page,
+            )
+        })
+    }
+
+    /// A page of one of a user's lists, such as their published or favorited items.
+    pub fn user(
+        app_id: u32,
+        account_id: u32,
+        list: UserListType,
+        ugc_type: UGCType,
+        order: UserListOrder,
+        page: u32,
+    ) -> Result<Self, S7Error> {
+        let list: steamworks::UserList = list.into();
+        let ugc_type: steamworks::UGCType = ugc_type.into();
+        let order: steamworks::UserListOrder = order.into();
+        Self::new(unsafe {
+            sys::SteamAPI_ISteamUGC_CreateQueryUserUGCRequest(
+                ugc(),
+                account_id,
+                list.into(),
+                ugc_type.into(),
+                order.into(),
+                app_id,
+                app_id,
+                page,
+            )
+        })
+    }
+
+    /// The items with the given IDs, at most 50.
+    pub fn items(item_ids: &[u64]) -> Result<Self, S7Error> {
+        let mut item_ids = item_ids.to_vec();
+        Self::new(unsafe {
+            sys::SteamAPI_ISteamUGC_CreateQueryUGCDetailsRequest(
+                ugc(),
+                item_ids.as_mut_ptr(),
+                item_ids.len() as u32,
+            )
+        })
+    }
+
+    fn new(handle: sys::UGCQueryHandle_t) -> Result<Self, S7Error> {
+        if handle == sys::k_UGCQueryHandleInvalid {
+            return Err(S7Error::Internal("Failed to create query".to_string()));
+        }
+        let query = Self { handle };
+        unsafe {
+            sys::SteamAPI_ISteamUGC_SetReturnLongDescription(ugc(), handle, true);
+            sys::SteamAPI_ISteamUGC_SetReturnMetadata(ugc(), handle, true);
+            sys::SteamAPI_ISteamUGC_SetReturnChildren(ugc(), handle, true);
+            sys::SteamAPI_ISteamUGC_SetReturnKeyValueTags(ugc(), handle, true);
+            sys::SteamAPI_ISteamUGC_SetReturnAdditionalPreviews(ugc(), handle, true);
+        }
+        Ok(query)
+    }
+
+    pub fn set_search_text(&self, search_text: &str) {
+        let search_text = c_string(search_text);
+        unsafe {
+            sys::SteamAPI_ISteamUGC_SetSearchText(ugc(), self.handle, search_text.as_ptr());
+        }
+    }
+
+    pub fn set_ranked_by_trend_days(&self, days: u32) {
+        unsafe {
+            sys::SteamAPI_ISteamUGC_SetRankedByTrendDays(ugc(), self.handle, days);
+        }
+    }
+
+    pub fn add_required_tag(&self, tag: &str) {
+        let tag = c_string(tag);
+        unsafe {
+            sys::SteamAPI_ISteamUGC_AddRequiredTag(ugc(), self.handle, tag.as_ptr());
+        }
+    }
+
+    pub fn add_excluded_tag(&self, tag: &str) {
+        let tag = c_string(tag);
+        unsafe {
+            sys::SteamAPI_ISteamUGC_AddExcludedTag(ugc(), self.handle, tag.as_ptr());
+        }
+    }
+
+    /// Requires at least one of `tags`. Each call adds another group.
+    pub fn add_required_tag_group(&self, tags: &[String]) {
+        let tags: Vec<CString> = tags.iter().map(|tag| c_string(tag)).collect();
+        let mut tag_pointers: Vec<*const c_char> = tags.iter().map(|tag| tag.as_ptr()).collect();
+        let tag_array = sys::SteamParamStringArray_t {
+            m_ppStrings: tag_pointers.as_mut_ptr(),
+            m_nNumStrings: tag_pointers.len() as i32,
+        };
+        unsafe {
+            sys::SteamAPI_ISteamUGC_AddRequiredTagGroup(ugc(), self.handle, &tag_array);
+        }
+    }
+
+    /// Keeps items created within `range`.
+    pub fn set_time_created_range(&self, range: DateRange) {
+        let (start, end) = steam_time_range(range);
+        unsafe {
+            sys::SteamAPI_ISteamUGC_SetTimeCreatedDateRange(ugc(), self.handle, start, end);
+        }
+    }
+
+    /// Keeps items last updated within `range`.
+    pub fn set_time_updated_range(&self, range: DateRange) {
+        let (start, end) = steam_time_range(range);
+        unsafe {
+            sys::SteamAPI_ISteamUGC_SetTimeUpdatedDateRange(ugc(), self.handle, start, end);
+        }
+    }
+
+    pub fn set_match_any_tag(&self, match_any_tag: bool) {
+        unsafe {
+            sys::SteamAPI_ISteamUGC_SetMatchAnyTag(ugc(), self.handle, match_any_tag);
+        }
+    }
+
+    /// Sends the query and reads every returned item.
+    pub async fn fetch(
+        self,
+        app_id: u32,
+        timeout: Duration,
+    ) -> Result<WorkshopItemsResult, S7Error> {
+        let handle = self.handle;
+        let result = raw_call_result(
+            app_id,
+            timeout,
+            sys::SteamUGCQueryCompleted_t_k_iCallback as i32,
+            || unsafe { sys::SteamAPI_ISteamUGC_SendQueryUGCRequest(ugc(), handle) },
+        )
+        .await?;
+        // Without the result there is no telling how many items came back.
+        let RawCallResult::Completed(sys::SteamUGCQueryCompleted_t {
+            m_eResult,
+            m_unNumResultsReturned,
+            m_unTotalMatchingResults,
+            m_bCachedData,
+            ..
+        }) = result
+        else {
+            return Err(S7Error::steam_api(SteamError::IOFailure));
+        };
+        check_result(m_eResult)?;
+
+        Ok(WorkshopItemsResult::from_query_results(RawQueryResults {
+            query: self,
+            returned_results: m_unNumResultsReturned,
+            total_results: m_unTotalMatchingResults,
+            was_cached: m_bCachedData,
+        }))
+    }
+}
+
+impl Drop for RawUgcQuery {
+    fn drop(&mut self) {
+        unsafe {
+            sys::SteamAPI_ISteamUGC_ReleaseQueryUGCRequest(ugc(), self.handle);
+        }
+    }
+}
+
+/// The items a [`RawUgcQuery`] returned, read by index.
+pub struct RawQueryResults {
+    query: RawUgcQuery,
+    returned_results: u32,
+    total_results: u32,
+    was_cached: bool,
+}
+
+impl RawQueryResults {
+    pub fn returned_results(&self) -> u32 {
+        self.returned_results
+    }
+
+    pub fn total_results(&self) -> u32 {
+        self.total_results
+    }
+
+    pub fn was_cached(&self) -> bool {
+        self.was_cached
+    }
+
+    /// The item at `index`, or `None` if Steam had no item for it.
+    pub fn get(&self, index: u32) -> Option<QueryResult> {
+        let mut details: sys::SteamUGCDetails_t = unsafe { std::mem::zeroed() };
+        let found = unsafe {
+            sys::SteamAPI_ISteamUGC_GetQueryUGCResult(ugc(), self.query.handle, index, &mut details)
+        };
+        if !found || details.m_eResult != sys::EResult::k_EResultOK {
+            return None;
+        }
+
+        let app_id = |id: u32| Some(AppId(id)).filter(|_| id != 0);
+        Some(QueryResult {
+            published_file_id: PublishedFileId(details.m_nPublishedFileId),
+            creator_app_id: app_id(details.m_nCreatorAppID),
+            consumer_app_id: app_id(details.m_nConsumerAppID),
+            title: text(&details.m_rgchTitle),
+            description: text(&details.m_rgchDescription),
+            owner: SteamId::from_raw(details.m_ulSteamIDOwner),
+            time_created: details.m_rtimeCreated,
+            time_updated: details.m_rtimeUpdated,
+            time_added_to_user_list: details.m_rtimeAddedToUserList,
+            visibility: PublishedFileVisibility::from(details.m_eVisibility),
+            banned: details.m_bBanned,
+            accepted_for_use: details.m_bAcceptedForUse,
+            tags: text(&details.m_rgchTags)
+                .split(',')
+                .map(String::from)
+                .collect(),
+            tags_truncated: details.m_bTagsTruncated,
+            file_type: FileType::from(details.m_eFileType),
+            file_size: details.m_nFileSize.max(0) as u32,
+            url: text(&details.m_rgchURL),
+            num_upvotes: details.m_unVotesUp,
+            num_downvotes: details.m_unVotesDown,
+            score: details.m_flScore,
+            num_children: details.m_unNumChildren,
+        })
+    }
+
+    pub fn get_children(&self, index: u32) -> Option<Vec<u64>> {
+        let mut children = vec![0; self.get(index)?.num_children as usize];
+        let found = unsafe {
+            sys::SteamAPI_ISteamUGC_GetQueryUGCChildren(
+                ugc(),
+                self.query.handle,
+                index,
+                children.as_mut_ptr(),
+                children.len() as u32,
+            )
+        };
+        Some(children).filter(|_| found)
+    }
+
+    pub fn preview_url(&self, index: u32) -> Option<String> {
+        let mut url = [0 as c_char; 4096];
+        let found = unsafe {
+            sys::SteamAPI_ISteamUGC_GetQueryUGCPreviewURL(
+                ugc(),
+                self.query.handle,
+                index,
+                url.as_mut_ptr(),
+                url.len() as u32,
+            )
+        };
+        Some(text(&url)).filter(|_| found)
+    }
+
+    pub fn statistic(&self, index: u32, statistic: UGCStatisticType) -> Option<u64> {
+        let mut value = 0;
+        let found = unsafe {
+            sys::SteamAPI_ISteamUGC_GetQueryUGCStatistic(
+                ugc(),
+                self.query.handle,
+                index,
+                statistic.into(),
+                &mut value,
+            )
+        };
+        Some(value).filter(|_| found)
+    }
+
+    pub fn get_metadata(&self, index: u32) -> Option<String> {
+        let mut metadata = [0 as c_char; sys::k_cchDeveloperMetadataMax as usize];
+        let found = unsafe {
+            sys::SteamAPI_ISteamUGC_GetQueryUGCMetadata(
+                ugc(),
+                self.query.handle,
+                index,
+                metadata.as_mut_ptr(),
+                metadata.len() as u32,
+            )
+        };
+        Some(text(&metadata)).filter(|metadata| found && !metadata.is_empty())
+    }
+
+    pub fn key_value_tags(&self, index: u32) -> Vec<(String, String)> {
+        let count = unsafe {
+            sys::SteamAPI_ISteamUGC_GetQueryUGCNumKeyValueTags(ugc(), self.query.handle, index)
+        };
+        (0..count)
+            .filter_map(|tag_index| {
+                let mut key = [0 as c_char; 256];
+                let mut value = [0 as c_char; 256];
+                let found = unsafe {
+                    sys::SteamAPI_ISteamUGC_GetQueryUGCKeyValueTag(
+                        ugc(),
+                        self.query.handle,
+                        index,
+                        tag_index,
+                        key.as_mut_ptr(),
+                        key.len() as u32,
+                        value.as_mut_ptr(),
+                        value.len() as u32,
+                    )
+                };
+                Some((text(&key), text(&value))).filter(|_| found)
+            })
+            .collect()
+    }
+
+    pub fn additional_previews(&self, index: u32) -> Vec<AdditionalPreview> {
+        let count = unsafe {
+            sys::SteamAPI_ISteamUGC_GetQueryUGCNumAdditionalPreviews(
+                ugc(),
+                self.query.handle,
+                index,
+            )
+        };
+        (0..count)
+            .filter_map(|preview_index| {
+                let mut url_or_video_id = [0 as c_char; 4096];
+                let mut original_file_name = [0 as c_char; sys::k_cchFilenameMax as usize];
+                // Read as a number, since Steam may send types newer than the bindings.
+                let mut preview_type = 0u32;
+                let found = unsafe {
+                    sys::SteamAPI_ISteamUGC_GetQueryUGCAdditionalPreview(
+                        ugc(),
+                        self.query.handle,
+                        index,
+                        preview_index,
+                        url_or_video_id.as_mut_ptr(),
+                        url_or_video_id.len() as u32,
+                        original_file_name.as_mut_ptr(),
+                        original_file_name.len() as u32,
+                        (&mut preview_type as *mut u32).cast::<sys::EItemPreviewType>(),
+                    )
+                };
+                if !found {
+                    return None;
+                }
+                Some(AdditionalPreview {
+                    url_or_video_id: text(&url_or_video_id),
+                    original_file_name: Some(text(&original_file_name))
+                        .filter(|name| !name.is_empty()),
+                    preview_type: preview_type_from_raw(preview_type)?,
+                })
+            })
+            .collect()
+    }
+}
+
+fn ugc() -> *mut sys::ISteamUGC {
+    unsafe { sys::SteamAPI_SteamUGC_v018() }
+}
+
+// Steam can't take strings with NUL bytes, so anything after one is dropped.
+fn c_string(value: &str) -> CString {
+    let value = value.split('\0').next().unwrap_or_default();
+    CString::new(value).unwrap_or_default()
+}
+
+// Steam takes both ends of a range, so open sides reach the first or last
+// representable time.
+fn steam_time_range(range: DateRange) -> (u32, u32) {
+    (range.since.unwrap_or(0), range.until.unwrap_or(u32::MAX))
+}
+
+// Reads a NUL-terminated string Steam wrote into `buffer`.
+fn text(buffer: &[c_char]) -> String {
+    let bytes: Vec<u8> = buffer
+        .iter()
+        .take_while(|&&byte| byte != 0)
+        .map(|&byte| byte as u8)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+// The values of `EItemPreviewType`. Clip is newer than the bindings.
+fn preview_type_from_raw(preview_type: u32) -> Option<PreviewType> {
+    match preview_type {
+        0 => Some(PreviewType::Image),
+        1 => Some(PreviewType::YoutubeVideo),
+        2 => Some(PreviewType::Sketchfab),
+        3 => Some(PreviewType::EnvironmentMapHorizontalCross),
+        4 => Some(PreviewType::EnvironmentMapLatLong),
+        5 => Some(PreviewType::Clip),
+        _ => None,
+    }
+}
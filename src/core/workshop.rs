@@ -22,11 +22,15 @@
 //
 // Modified by Burak Kartal on [24/06/2025]
 
-use bincode::{Decode, Encode};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Encode, Decode)]
+use crate::error::S7Error;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
 pub enum UgcItemVisibility {
+    #[default]
     Public,
     FriendsOnly,
     Private,
@@ -55,7 +59,61 @@ impl From<UgcItemVisibility> for steamworks::PublishedFileVisibility {
     }
 }
 
-#[derive(Debug)]
+impl FromStr for UgcItemVisibility {
+    type Err = S7Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "public" => Ok(UgcItemVisibility::Public),
+            "friends-only" => Ok(UgcItemVisibility::FriendsOnly),
+            "private" => Ok(UgcItemVisibility::Private),
+            "unlisted" => Ok(UgcItemVisibility::Unlisted),
+            _ => Err(S7Error::InvalidArgument(format!(
+                "Invalid visibility '{}'. Valid options: public, friends-only, private, unlisted",
+                value
+            ))),
+        }
+    }
+}
+
+/// The logged-in user's vote on an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Vote {
+    Up,
+    Down,
+}
+
+impl FromStr for Vote {
+    type Err = S7Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "up" => Ok(Vote::Up),
+            "down" => Ok(Vote::Down),
+            _ => Err(S7Error::InvalidArgument(format!(
+                "Invalid vote '{}'. Valid options: up, down",
+                value
+            ))),
+        }
+    }
+}
+
+/// What an additional preview of an item shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
+pub enum PreviewType {
+    Image,
+    YoutubeVideo,
+    Sketchfab,
+    EnvironmentMapHorizontalCross,
+    EnvironmentMapLatLong,
+    Clip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum UpdateStatus {
     Invalid,
     PreparingConfig,
@@ -77,3 +135,31 @@ impl From<steamworks::UpdateStatus> for UpdateStatus {
         }
     }
 }
+
+/// Where an installed item lives on disk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ItemInstallInfo {
+    pub folder: String,
+    pub size_on_disk: u64,
+    /// When the installed content was last updated, as a Unix timestamp.
+    pub timestamp: u32,
+}
+
+impl From<steamworks::InstallInfo> for ItemInstallInfo {
+    fn from(info: steamworks::InstallInfo) -> Self {
+        ItemInstallInfo {
+            folder: info.folder,
+            size_on_disk: info.size_on_disk,
+            timestamp: info.timestamp,
+        }
+    }
+}
+
+/// One progress report of an item upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct UpdateProgress {
+    pub item_id: u64,
+    pub status: UpdateStatus,
+    pub bytes_processed: u64,
+    pub bytes_total: u64,
+}
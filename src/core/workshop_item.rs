@@ -22,17 +22,7 @@
 //
 // Modified by Burak Kartal on [24/06/2025]
 
-use std::collections::HashSet;
-
-fn capitalize(s: String) -> String {
-    let mut chars = s.chars();
-    match chars.next() {
-        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
-        None => String::new(),
-    }
-}
-
-fn is_version_tag(tag: &str) -> bool {
+pub fn is_version_tag(tag: &str) -> bool {
     let tag = tag.trim();
     if tag.is_empty()
         || !tag
@@ -104,22 +94,43 @@ fn is_valid_version_string(s: &str) -> bool {
     has_any_digits
 }
 
-fn is_filtered_tag(tag: &str) -> bool {
-    let excluded_tags: HashSet<&str> = ["mod", "Singleplayer", "Native"].iter().cloned().collect();
+pub fn file_type_name(file_type: steamworks::FileType) -> &'static str {
+    use steamworks::FileType;
 
-    excluded_tags.contains(tag) || is_version_tag(tag)
+    match file_type {
+        FileType::Community => "Community",
+        FileType::Microtransaction => "Microtransaction",
+        FileType::Collection => "Collection",
+        FileType::Art => "Art",
+        FileType::Video => "Video",
+        FileType::Screenshot => "Screenshot",
+        FileType::Game => "Game",
+        FileType::Software => "Software",
+        FileType::Concept => "Concept",
+        FileType::WebGuide => "WebGuide",
+        FileType::IntegratedGuide => "IntegratedGuide",
+        FileType::Merch => "Merch",
+        FileType::ControllerBinding => "ControllerBinding",
+        FileType::SteamworksAccessInvite => "SteamworksAccessInvite",
+        FileType::SteamVideo => "SteamVideo",
+        FileType::GameManagedItem => "GameManagedItem",
+    }
 }
 
 pub mod workshop {
-    use bincode::{Decode, Encode};
-    use serde::Serialize;
-    use steamworks::FileType;
+    use serde::{Deserialize, Serialize};
+    use std::str::FromStr;
 
     use crate::core::localplayer::PlayerSteamId;
-    use crate::core::workshop::UgcItemVisibility;
+    use crate::core::ugc_query::RawQueryResults;
+    use crate::core::workshop::{PreviewType, UgcItemVisibility};
+    use crate::error::S7Error;
 
-    use super::{capitalize, is_filtered_tag};
+    use super::file_type_name;
+    use crate::core::game_version::{GameVersion, compatible_versions};
 
+    #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+    #[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
     pub enum UGCQueryType {
         RankedByVote,
         RankedByPublicationDate,
@@ -200,7 +211,10 @@ pub mod workshop {
         }
     }
 
+    #[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, Serialize, Deserialize)]
+    #[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
     pub enum UGCType {
+        #[default]
         Items,
         ItemsMtx,
         ItemsReadyToUse,
@@ -238,6 +252,52 @@ pub mod workshop {
         }
     }
 
+    impl UGCType {
+        /// Whether an item with this file type, as named by `file_type_name`, belongs to
+        /// the type.
+        pub fn matches_file_type(self, file_type: &str) -> bool {
+            match self {
+                UGCType::All => true,
+                UGCType::Collections => file_type == "Collection",
+                UGCType::Artwork => file_type == "Art",
+                UGCType::Videos => file_type == "Video",
+                UGCType::Screenshots => file_type == "Screenshot",
+                UGCType::AllGuides => file_type == "WebGuide" || file_type == "IntegratedGuide",
+                UGCType::WebGuides => file_type == "WebGuide",
+                UGCType::IntegratedGuides => file_type == "IntegratedGuide",
+                UGCType::ControllerBindings => file_type == "ControllerBinding",
+                UGCType::GameManagedItems => file_type == "GameManagedItem",
+                UGCType::Items
+                | UGCType::ItemsMtx
+                | UGCType::ItemsReadyToUse
+                | UGCType::UsableInGame => {
+                    file_type == "Community" || file_type == "Microtransaction"
+                }
+            }
+        }
+    }
+
+    impl FromStr for UGCType {
+        type Err = S7Error;
+
+        fn from_str(value: &str) -> Result<Self, Self::Err> {
+            match value {
+                "items" => Ok(UGCType::Items),
+                "collections" => Ok(UGCType::Collections),
+                "artwork" => Ok(UGCType::Artwork),
+                "videos" => Ok(UGCType::Videos),
+                "screenshots" => Ok(UGCType::Screenshots),
+                "guides" => Ok(UGCType::AllGuides),
+                "all" => Ok(UGCType::All),
+                _ => Err(S7Error::InvalidArgument(format!(
+                    "Invalid type '{}'. Valid options: items, collections, artwork, videos, screenshots, guides, all",
+                    value
+                ))),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub enum UserListType {
         Published,
         VotedOn,
@@ -264,6 +324,7 @@ pub mod workshop {
         }
     }
 
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub enum UserListOrder {
         CreationOrderAsc,
         CreationOrderDesc,
@@ -289,7 +350,8 @@ pub mod workshop {
             }
         }
     }
-    #[derive(Debug, Clone, Serialize, Encode, Decode)]
+    #[derive(Debug, Clone, Default, Serialize)]
+    #[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
     pub struct WorkshopItemStatistic {
         pub num_subscriptions: Option<u64>, //   0	gets the number of subscriptions.
         pub num_favorites: Option<u64>,     //   1	gets the number of favorites.
@@ -307,7 +369,7 @@ pub mod workshop {
     }
 
     impl WorkshopItemStatistic {
-        fn from_query_results(results: &steamworks::QueryResults, index: u32) -> Self {
+        fn from_query_results(results: &RawQueryResults, index: u32) -> Self {
             Self {
                 num_subscriptions: results
                     .statistic(index, steamworks::UGCStatisticType::Subscriptions),
@@ -338,7 +400,8 @@ pub mod workshop {
             }
         }
     }
-    #[derive(Debug, Clone, Serialize, Encode, Decode)]
+    #[derive(Debug, Clone, Serialize)]
+    #[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
     pub struct WorkshopItem {
         pub published_file_id: u64,
         pub creator_steam_game_id: Option<u32>,
@@ -352,7 +415,10 @@ pub mod workshop {
         pub visibility: UgcItemVisibility,
         pub banned: bool,
         pub accepted_for_use: bool,
-        pub tags: String,
+        /// The tags as Steam returns them.
+        pub tags: Vec<String>,
+        /// The game versions the version tags declare, oldest first.
+        pub compatible_versions: Vec<GameVersion>,
         pub tags_truncated: bool,
         pub url: String,
         pub num_upvotes: u32,
@@ -363,44 +429,40 @@ pub mod workshop {
         pub required_items: Vec<u64>,
         pub file_type: String,
         pub file_size: u32,
+        /// Developer metadata set with `update-item --metadata`.
+        pub metadata: Option<String>,
+        pub key_value_tags: Vec<(String, String)>,
+        pub additional_previews: Vec<AdditionalPreview>,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
+    pub struct AdditionalPreview {
+        /// An image URL, or the video ID for YouTube previews.
+        pub url_or_video_id: String,
+        pub original_file_name: Option<String>,
+        pub preview_type: PreviewType,
     }
 
     impl WorkshopItem {
-        fn from_query_results(results: &steamworks::QueryResults, index: u32) -> Option<Self> {
+        /// Whether one of the item's version tags covers `game_version`.
+        pub fn supports_game_version(&self, game_version: &GameVersion) -> bool {
+            self.compatible_versions
+                .iter()
+                .any(|version| version.covers(game_version))
+        }
+
+        fn from_query_results(results: &RawQueryResults, index: u32) -> Option<Self> {
             results.get(index).map(|item| {
                 let time_created_u128 = item.time_created as u128;
                 let time_updated_u128 = item.time_updated as u128;
                 let time_created = time_created_u128.checked_mul(1000);
                 let time_updated = time_updated_u128.checked_mul(1000);
 
-                let required_items = results
-                    .get_children(index)
-                    .unwrap_or_default()
-                    .into_iter()
-                    .map(|file_id| file_id.0)
-                    .collect();
+                let required_items = results.get_children(index).unwrap_or_default();
 
                 let published_file_id = item.published_file_id.0;
 
-                let file_type = match item.file_type {
-                    FileType::Community => "Community",
-                    FileType::Microtransaction => "Microtransaction",
-                    FileType::Collection => "Collection",
-                    FileType::Art => "Art",
-                    FileType::Video => "Video",
-                    FileType::Screenshot => "Screenshot",
-                    FileType::Game => "Game",
-                    FileType::Software => "Software",
-                    FileType::Concept => "Concept",
-                    FileType::WebGuide => "WebGuide",
-                    FileType::IntegratedGuide => "IntegratedGuide",
-                    FileType::Merch => "Merch",
-                    FileType::ControllerBinding => "ControllerBinding",
-                    FileType::SteamworksAccessInvite => "SteamworksAccessInvite",
-                    FileType::SteamVideo => "SteamVideo",
-                    FileType::GameManagedItem => "GameManagedItem",
-                };
-
                 Self {
                     published_file_id,
                     creator_steam_game_id: item.creator_app_id.map(|id| id.0),
@@ -414,13 +476,8 @@ pub mod workshop {
                     visibility: item.visibility.into(),
                     banned: item.banned,
                     accepted_for_use: item.accepted_for_use,
-                    tags: item
-                        .tags
-                        .iter()
-                        .filter(|tag| !is_filtered_tag(tag))
-                        .map(|tag| capitalize(tag.clone()).replace("Ui", "UI"))
-                        .collect::<Vec<String>>()
-                        .join(", "),
+                    compatible_versions: compatible_versions(&item.tags),
+                    tags: item.tags,
                     tags_truncated: item.tags_truncated,
                     url: item.url,
                     num_upvotes: item.num_upvotes,
@@ -429,8 +486,11 @@ pub mod workshop {
                     preview_url: results.preview_url(index),
                     statistics: WorkshopItemStatistic::from_query_results(results, index),
                     required_items,
-                    file_type: file_type.to_string(),
+                    file_type: file_type_name(item.file_type).to_string(),
                     file_size: item.file_size,
+                    metadata: results.get_metadata(index),
+                    key_value_tags: results.key_value_tags(index),
+                    additional_previews: results.additional_previews(index),
                 }
             })
         }
@@ -439,16 +499,19 @@ pub mod workshop {
     #[derive(Debug)]
     pub struct WorkshopItemsResult {
         pub items: Vec<Option<WorkshopItem>>,
+        /// How many items match the query across all pages.
+        pub total_results: u32,
         #[allow(dead_code)]
         pub was_cached: bool,
     }
 
     impl WorkshopItemsResult {
-        pub fn from_query_results(query_results: steamworks::QueryResults) -> Self {
+        pub fn from_query_results(query_results: RawQueryResults) -> Self {
             Self {
                 items: (0..query_results.returned_results())
                     .map(|i| WorkshopItem::from_query_results(&query_results, i))
                     .collect(),
+                total_results: query_results.total_results(),
                 was_cached: query_results.was_cached(),
             }
         }
@@ -0,0 +1,452 @@
+use serde_json::{Value, json};
+
+use crate::cli::{Command, ItemChanges};
+use crate::steam_workers::SteamWorkers;
+use s7forge::{
+    CollectionOptions, DateRange, ItemUpdate, ManifestRelease, PublishOptions, S7Error,
+    SearchQuery, TagFilter, UpdateProgress, UserItemsQuery, Workshop, WorkshopManifest,
+};
+
+/// Runs app-scoped commands on that app's worker when `workers` is set and everything
+/// else in this process.
+pub async fn dispatch_with(
+    command: Command,
+    workers: Option<&SteamWorkers>,
+) -> Result<Value, S7Error> {
+    let app_id = match &command {
+        Command::PublishManifest { manifest, .. } => Some(WorkshopManifest::load(manifest)?.app_id),
+        command => command.app_id(),
+    };
+
+    match (workers, app_id) {
+        (Some(workers), Some(app_id)) => {
+            let call = serde_json::to_value(&command)
+                .map_err(|e| S7Error::Internal(format!("Failed to encode command: {}", e)))?;
+            workers.call(app_id, call).await
+        }
+        _ => dispatch(command).await,
+    }
+}
+
+pub fn status(workers: Option<&SteamWorkers>) -> Value {
+    match workers {
+        Some(workers) => {
+            let statuses = workers.statuses();
+            json!({
+                "mode": "workers",
+                "initialized_apps": statuses.iter().map(|status| status.app_id).collect::<Vec<_>>(),
+                "workers": statuses,
+            })
+        }
+        None => json!({
+            "mode": "in-process",
+            "initialized_apps": s7forge::initialized_apps(),
+        }),
+    }
+}
+
+pub async fn dispatch(command: Command) -> Result<Value, S7Error> {
+    match command {
+        Command::CheckItemDownload { app_id, item_id } => Workshop::new(app_id)?
+            .download_status(item_id)
+            .await
+            .map(|info| serde_json::to_value(&info).unwrap()),
+        Command::CollectionItems { app_id, item_id } => Workshop::new(app_id)?
+            .collection(item_id)
+            .await
+            .map(|items| serde_json::to_value(&items).unwrap()),
+        Command::WorkshopItems {
+            app_id,
+            item_ids,
+            with_user_state: false,
+            item_type: None,
+        } => Workshop::new(app_id)?
+            .items(item_ids)
+            .await
+            .map(|items| serde_json::to_value(&items).unwrap()),
+        Command::WorkshopItems {
+            app_id,
+            item_ids,
+            with_user_state: true,
+            item_type: None,
+        } => Workshop::new(app_id)?
+            .items_with_user_state(item_ids)
+            .await
+            .map(|items| serde_json::to_value(&items).unwrap()),
+        Command::WorkshopItems {
+            app_id,
+            item_ids,
+            with_user_state: false,
+            item_type: Some(item_type),
+        } => Workshop::new(app_id)?
+            .items_of_type(item_ids, item_type.parse()?)
+            .await
+            .map(|result| serde_json::to_value(&result).unwrap()),
+        Command::WorkshopItems {
+            app_id,
+            item_ids,
+            with_user_state: true,
+            item_type: Some(item_type),
+        } => Workshop::new(app_id)?
+            .items_of_type_with_user_state(item_ids, item_type.parse()?)
+            .await
+            .map(|result| serde_json::to_value(&result).unwrap()),
+        Command::Subscribe { app_id, item_ids } => Workshop::new(app_id)?
+            .subscribe(item_ids)
+            .await
+            .map(|results| serde_json::to_value(&results).unwrap()),
+        Command::Unsubscribe { app_id, item_ids } => Workshop::new(app_id)?
+            .unsubscribe(item_ids)
+            .await
+            .map(|results| serde_json::to_value(&results).unwrap()),
+        Command::DownloadWorkshopItem { app_id, item_id } => Workshop::new(app_id)?
+            .download(item_id)
+            .await
+            .map(|_| Value::from("Workshop item download completed successfully")),
+        Command::SubscribedItems {
+            app_id,
+            game_version: None,
+        } => Workshop::new(app_id)?
+            .subscribed_items()
+            .await
+            .map(|items| serde_json::to_value(&items).unwrap()),
+        Command::SubscribedItems {
+            app_id,
+            game_version: Some(game_version),
+        } => Workshop::new(app_id)?
+            .subscribed_items_for_version(&game_version.parse()?)
+            .await
+            .map(|items| serde_json::to_value(&items).unwrap()),
+        Command::CompatReport {
+            app_id,
+            game_version,
+        } => Workshop::new(app_id)?
+            .compat_report(&game_version.parse()?)
+            .await
+            .map(|report| serde_json::to_value(&report).unwrap()),
+        Command::SearchWorkshop {
+            app_id,
+            query,
+            sort_by,
+            period,
+            page,
+            tags,
+            exclude_tags,
+            match_any_tags,
+            tag_groups,
+            created_since,
+            created_until,
+            updated_since,
+            updated_until,
+            item_type,
+            game_version,
+            page_info,
+            all_pages,
+            max_pages,
+        } => {
+            let max_pages = match (all_pages, max_pages) {
+                (true, Some(_)) => {
+                    return Err(S7Error::InvalidArgument(
+                        "Pass only one of --all-pages and --max-pages".to_string(),
+                    ));
+                }
+                (true, None) => None,
+                (false, max_pages) => Some(max_pages.unwrap_or(1)),
+            };
+            // These filters are applied after Steam returns each page and can leave
+            // results out, which only the page output reports.
+            if !page_info && (item_type.is_some() || game_version.is_some()) {
+                return Err(S7Error::InvalidArgument(
+                    "--type and --game-version need --page-info, which lists the items they leave out"
+                        .to_string(),
+                ));
+            }
+            let search = SearchQuery {
+                text: query,
+                sort_by: sort_by.parse()?,
+                period,
+                page,
+                tags: TagFilter::parse(
+                    tags.as_deref(),
+                    exclude_tags.as_deref(),
+                    match_any_tags,
+                    &tag_groups,
+                ),
+                created: DateRange::parse(created_since.as_deref(), created_until.as_deref())?,
+                updated: DateRange::parse(updated_since.as_deref(), updated_until.as_deref())?,
+                item_type: item_type
+                    .as_deref()
+                    .map(str::parse)
+                    .transpose()?
+                    .unwrap_or_default(),
+                game_version: game_version.as_deref().map(str::parse).transpose()?,
+                max_pages,
+            };
+            let workshop = Workshop::new(app_id)?;
+            if page_info {
+                workshop
+                    .search_page(search)
+                    .await
+                    .map(|page| serde_json::to_value(&page).unwrap())
+            } else {
+                workshop
+                    .search(search)
+                    .await
+                    .map(|items| serde_json::to_value(&items).unwrap())
+            }
+        }
+        Command::UserItems {
+            app_id,
+            list,
+            order,
+            page,
+            user,
+        } => Workshop::new(app_id)?
+            .user_items(UserItemsQuery {
+                list,
+                order,
+                page,
+                user,
+            })
+            .await
+            .map(|items| serde_json::to_value(&items).unwrap()),
+        Command::WorkshopPath { app_id } => match s7forge::workshop_path(app_id) {
+            Some(path) => Ok(serde_json::to_value(&path).unwrap()),
+            None => Err(S7Error::NotFound(format!(
+                "Workshop path not found for app ID {}",
+                app_id
+            ))),
+        },
+        Command::SteamLibraryPaths { detailed: false } => {
+            s7forge::steam_library_paths().map(|paths| serde_json::to_value(&paths).unwrap())
+        }
+        Command::SteamLibraryPaths { detailed: true } => s7forge::steam_library_folders()
+            .map(|libraries| serde_json::to_value(&libraries).unwrap()),
+        Command::SteamInstallPaths => {
+            s7forge::steam_install_paths().map(|paths| serde_json::to_value(&paths).unwrap())
+        }
+        Command::ClearCache => {
+            s7forge::clear_cache().map(|message| serde_json::to_value(&message).unwrap())
+        }
+        Command::DiscoverTags { app_id } => Workshop::new(app_id)?
+            .tags()
+            .await
+            .map(|tags| serde_json::to_value(&tags).unwrap()),
+        Command::Publish {
+            app_id,
+            title,
+            description,
+            content_folder,
+            preview_image,
+            visibility,
+            tags,
+            change_note,
+        } => {
+            let options = PublishOptions {
+                title,
+                description,
+                content_folder: content_folder.into(),
+                preview_image: preview_image.into(),
+                visibility: visibility.parse()?,
+                tags,
+                change_note,
+            };
+            Workshop::new(app_id)?
+                .publish(options)
+                .await
+                .map(|result| serde_json::to_value(result).unwrap())
+        }
+        Command::PublishManifest {
+            manifest,
+            version,
+            change_note,
+        } => {
+            let mut manifest = WorkshopManifest::load(manifest)?;
+            Workshop::new(manifest.app_id)?
+                .publish_manifest(
+                    &mut manifest,
+                    ManifestRelease {
+                        version,
+                        change_note,
+                    },
+                )
+                .await
+                .map(|result| serde_json::to_value(result).unwrap())
+        }
+        Command::UpdateItem {
+            app_id,
+            item_id,
+            changes,
+        } => update_item(app_id, item_id, changes, |_| {}).await,
+        Command::DeleteItem {
+            app_id,
+            item_id,
+            yes,
+        } => {
+            if !yes {
+                return Err(S7Error::InvalidArgument(format!(
+                    "Deleting item {} cannot be undone, pass --yes to confirm",
+                    item_id
+                )));
+            }
+            Workshop::new(app_id)?
+                .delete_item(item_id)
+                .await
+                .map(|result| serde_json::to_value(&result).unwrap())
+        }
+        Command::SetVisibility {
+            app_id,
+            item_id,
+            visibility,
+        } => {
+            let visibility = visibility.parse()?;
+            Workshop::new(app_id)?
+                .set_visibility(item_id, visibility)
+                .await
+                .map(|result| serde_json::to_value(&result).unwrap())
+        }
+        Command::AddDependency {
+            app_id,
+            item_id,
+            dependency_ids,
+            app_dependency_ids,
+        } => Workshop::new(app_id)?
+            .add_dependency(item_id, dependency_ids, app_dependency_ids)
+            .await
+            .map(|result| serde_json::to_value(&result).unwrap()),
+        Command::RemoveDependency {
+            app_id,
+            item_id,
+            dependency_ids,
+            app_dependency_ids,
+        } => Workshop::new(app_id)?
+            .remove_dependency(item_id, dependency_ids, app_dependency_ids)
+            .await
+            .map(|result| serde_json::to_value(&result).unwrap()),
+        Command::ResolveDependencies {
+            app_id,
+            item_ids,
+            dot,
+        } => Workshop::new(app_id)?
+            .resolve_dependencies(item_ids, dot)
+            .await
+            .map(|graph| serde_json::to_value(&graph).unwrap()),
+        Command::Vote {
+            app_id,
+            item_id,
+            vote,
+        } => {
+            let vote = vote.parse()?;
+            Workshop::new(app_id)?
+                .vote(item_id, vote)
+                .await
+                .map(|result| serde_json::to_value(&result).unwrap())
+        }
+        Command::GetVote { app_id, item_id } => Workshop::new(app_id)?
+            .get_vote(item_id)
+            .await
+            .map(|result| serde_json::to_value(&result).unwrap()),
+        Command::Favorite {
+            app_id,
+            item_id,
+            action,
+        } => {
+            let workshop = Workshop::new(app_id)?;
+            let result = match action.as_str() {
+                "add" => workshop.add_favorite(item_id).await,
+                "remove" => workshop.remove_favorite(item_id).await,
+                _ => Err(S7Error::InvalidArgument(format!(
+                    "Invalid favorite action '{}'. Valid options: add, remove",
+                    action
+                ))),
+            };
+            result.map(|result| serde_json::to_value(&result).unwrap())
+        }
+        Command::CollectionCreate {
+            app_id,
+            title,
+            description,
+            visibility,
+            preview_image,
+            item_ids,
+        } => {
+            let options = CollectionOptions {
+                title,
+                description,
+                visibility: visibility.parse()?,
+                preview_image: preview_image.map(Into::into),
+                item_ids,
+            };
+            Workshop::new(app_id)?
+                .collection_create(options)
+                .await
+                .map(|result| serde_json::to_value(&result).unwrap())
+        }
+        Command::CollectionFromSubscriptions {
+            app_id,
+            title,
+            description,
+            visibility,
+            preview_image,
+        } => {
+            let options = CollectionOptions {
+                title,
+                description,
+                visibility: visibility.parse()?,
+                preview_image: preview_image.map(Into::into),
+                item_ids: Vec::new(),
+            };
+            Workshop::new(app_id)?
+                .collection_from_subscriptions(options)
+                .await
+                .map(|result| serde_json::to_value(&result).unwrap())
+        }
+        Command::CollectionAdd {
+            app_id,
+            collection_id,
+            item_ids,
+            position,
+        } => Workshop::new(app_id)?
+            .collection_add(collection_id, item_ids, position)
+            .await
+            .map(|result| serde_json::to_value(&result).unwrap()),
+        Command::CollectionRemove {
+            app_id,
+            collection_id,
+            item_ids,
+        } => Workshop::new(app_id)?
+            .collection_remove(collection_id, item_ids)
+            .await
+            .map(|result| serde_json::to_value(&result).unwrap()),
+        Command::Serve(_) | Command::HttpServe { .. } => Err(S7Error::InvalidArgument(
+            "Server commands cannot be dispatched".to_string(),
+        )),
+    }
+}
+
+/// Runs `update-item`, passing every upload progress change to `on_progress`.
+pub async fn update_item<F>(
+    app_id: u32,
+    item_id: u64,
+    changes: ItemChanges,
+    on_progress: F,
+) -> Result<Value, S7Error>
+where
+    F: Fn(UpdateProgress) + Send + 'static,
+{
+    let update = ItemUpdate {
+        title: changes.title,
+        description: changes.description,
+        visibility: changes.visibility.map(|v| v.parse()).transpose()?,
+        tags: changes.tags,
+        content_folder: changes.content_folder.map(Into::into),
+        preview_image: changes.preview_image.map(Into::into),
+        metadata: changes.metadata,
+        change_note: changes.change_note,
+    };
+
+    Workshop::new(app_id)?
+        .update_item_with_progress(item_id, update, on_progress)
+        .await
+        .map(|result| serde_json::to_value(result).unwrap())
+}
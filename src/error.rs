@@ -0,0 +1,189 @@
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::fmt;
+use steamworks::{SteamAPIInitError, SteamError};
+
+/// Every failure a command can report. The variant decides the machine-readable
+/// `code` and the process exit code, the message is what a user reads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum S7Error {
+    InvalidArgument(String),
+    SteamNotRunning(String),
+    NotFound(String),
+    NotSubscribed { item_id: u64, message: String },
+    NotOwner { item_id: u64, message: String },
+    Timeout { seconds: u64, message: String },
+    SteamApi { result: String, message: String },
+    Io(String),
+    Internal(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorBody<'a> {
+    pub code: &'static str,
+    pub message: &'a str,
+    pub details: Value,
+}
+
+impl S7Error {
+    pub fn steam_api(error: SteamError) -> Self {
+        match error {
+            SteamError::FileNotFound => {
+                S7Error::NotFound("Steam API error: FileNotFound".to_string())
+            }
+            SteamError::Timeout => S7Error::Timeout {
+                seconds: 0,
+                message: "Steam API error: Timeout".to_string(),
+            },
+            error => S7Error::SteamApi {
+                result: format!("{:?}", error),
+                message: format!("Steam API error: {:?}", error),
+            },
+        }
+    }
+
+    pub fn steam_init(error: SteamAPIInitError) -> Self {
+        S7Error::SteamNotRunning(format!("Failed to initialize Steam client: {:?}", error))
+    }
+
+    pub fn not_subscribed(item_id: u64) -> Self {
+        S7Error::NotSubscribed {
+            item_id,
+            message: "Workshop item is not subscribed".to_string(),
+        }
+    }
+
+    pub fn not_owner(item_id: u64) -> Self {
+        S7Error::NotOwner {
+            item_id,
+            message: format!(
+                "Workshop item {} is not owned by the logged-in user",
+                item_id
+            ),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            S7Error::InvalidArgument(_) => "invalid_argument",
+            S7Error::SteamNotRunning(_) => "steam_not_running",
+            S7Error::NotFound(_) => "not_found",
+            S7Error::NotSubscribed { .. } => "not_subscribed",
+            S7Error::NotOwner { .. } => "not_owner",
+            S7Error::Timeout { .. } => "timeout",
+            S7Error::SteamApi { .. } => "steam_api_error",
+            S7Error::Io(_) => "io_error",
+            S7Error::Internal(_) => "internal_error",
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            S7Error::Internal(_) => 1,
+            S7Error::InvalidArgument(_) => 2,
+            S7Error::SteamNotRunning(_) => 3,
+            S7Error::NotFound(_) => 4,
+            S7Error::NotSubscribed { .. } => 5,
+            S7Error::Timeout { .. } => 6,
+            S7Error::SteamApi { .. } => 7,
+            S7Error::Io(_) => 8,
+            S7Error::NotOwner { .. } => 9,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            S7Error::InvalidArgument(message)
+            | S7Error::SteamNotRunning(message)
+            | S7Error::NotFound(message)
+            | S7Error::NotSubscribed { message, .. }
+            | S7Error::NotOwner { message, .. }
+            | S7Error::Timeout { message, .. }
+            | S7Error::SteamApi { message, .. }
+            | S7Error::Io(message)
+            | S7Error::Internal(message) => message,
+        }
+    }
+
+    pub fn details(&self) -> Value {
+        match self {
+            S7Error::NotSubscribed { item_id, .. } | S7Error::NotOwner { item_id, .. } => {
+                json!({ "item_id": item_id })
+            }
+            S7Error::Timeout { seconds, .. } if *seconds > 0 => {
+                json!({ "timeout_secs": seconds })
+            }
+            S7Error::SteamApi { result, .. } => json!({ "steam_result": result }),
+            _ => Value::Null,
+        }
+    }
+
+    pub fn body(&self) -> ErrorBody<'_> {
+        ErrorBody {
+            code: self.code(),
+            message: self.message(),
+            details: self.details(),
+        }
+    }
+
+    /// Rebuilds an error from its `body()`, e.g. when a worker process reported it.
+    pub fn from_body(code: &str, message: String, details: &Value) -> Self {
+        match code {
+            "invalid_argument" => S7Error::InvalidArgument(message),
+            "steam_not_running" => S7Error::SteamNotRunning(message),
+            "not_found" => S7Error::NotFound(message),
+            "not_subscribed" => S7Error::NotSubscribed {
+                item_id: details["item_id"].as_u64().unwrap_or_default(),
+                message,
+            },
+            "not_owner" => S7Error::NotOwner {
+                item_id: details["item_id"].as_u64().unwrap_or_default(),
+                message,
+            },
+            "timeout" => S7Error::Timeout {
+                seconds: details["timeout_secs"].as_u64().unwrap_or_default(),
+                message,
+            },
+            "steam_api_error" => S7Error::SteamApi {
+                result: details["steam_result"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                message,
+            },
+            "io_error" => S7Error::Io(message),
+            _ => S7Error::Internal(message),
+        }
+    }
+
+    /// Prefixes the message with what was being attempted, keeping the error kind.
+    pub fn context(mut self, context: impl fmt::Display) -> Self {
+        let message = match &mut self {
+            S7Error::InvalidArgument(message)
+            | S7Error::SteamNotRunning(message)
+            | S7Error::NotFound(message)
+            | S7Error::NotSubscribed { message, .. }
+            | S7Error::NotOwner { message, .. }
+            | S7Error::Timeout { message, .. }
+            | S7Error::SteamApi { message, .. }
+            | S7Error::Io(message)
+            | S7Error::Internal(message) => message,
+        };
+        *message = format!("{}: {}", context, message);
+        self
+    }
+}
+
+impl fmt::Display for S7Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for S7Error {}
+
+impl From<std::io::Error> for S7Error {
+    fn from(error: std::io::Error) -> Self {
+        S7Error::Io(error.to_string())
+    }
+}
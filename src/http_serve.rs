@@ -0,0 +1,684 @@
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::runtime::Handle;
+
+use crate::cli::{Command, ServeOptions};
+use crate::dispatch::{dispatch_with, status};
+use crate::steam_workers::SteamWorkers;
+use s7forge::S7Error;
+
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+const MAX_LINE_LENGTH: usize = 8 * 1024;
+const MAX_HEADERS: usize = 64;
+const MAX_CONNECTIONS: usize = 64;
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+// The body is empty only for an answered CORS preflight.
+struct HttpResponse {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Option<Value>,
+}
+
+struct HttpError {
+    status: u16,
+    error: S7Error,
+}
+
+impl HttpError {
+    fn new(status: u16, error: S7Error) -> Self {
+        Self { status, error }
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(400, S7Error::InvalidArgument(message.into()))
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self::new(404, S7Error::NotFound(message.into()))
+    }
+
+    fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(403, S7Error::InvalidArgument(message.into()))
+    }
+}
+
+impl From<S7Error> for HttpError {
+    fn from(error: S7Error) -> Self {
+        let status = match error {
+            S7Error::InvalidArgument(_) => 400,
+            S7Error::NotOwner { .. } => 403,
+            S7Error::NotFound(_) => 404,
+            S7Error::NotSubscribed { .. } => 409,
+            S7Error::SteamApi { .. } => 502,
+            S7Error::SteamNotRunning(_) => 503,
+            S7Error::Timeout { .. } => 504,
+            S7Error::Io(_) | S7Error::Internal(_) => 500,
+        };
+        Self::new(status, error)
+    }
+}
+
+#[derive(Deserialize)]
+struct ItemIdsBody {
+    item_ids: Vec<u64>,
+}
+
+#[derive(Deserialize)]
+struct VoteBody {
+    vote: String,
+}
+
+// A route either forwards the command result as-is, or unwraps the single item a
+// `/items/{id}` lookup asked for.
+enum Route {
+    Command(Command),
+    SingleItem(Command),
+    Status,
+}
+
+struct Server {
+    runtime: Handle,
+    workers: Option<Arc<SteamWorkers>>,
+    port: u16,
+    allowed_origins: Vec<String>,
+    connections: AtomicUsize,
+}
+
+// Frees a connection slot when its thread ends, however it ends.
+struct ConnectionSlot(Arc<Server>);
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.connections.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Serves the workshop commands as a small REST API on `127.0.0.1`. Every connection
+/// runs on its own thread, up to `MAX_CONNECTIONS` at once, and shares the same Steam
+/// clients. Only requests addressed to this server are answered, and browser pages may
+/// only call it from its own origin or one of `allowed_origins`.
+pub async fn http_serve(
+    port: u16,
+    allowed_origins: Vec<String>,
+    options: ServeOptions,
+) -> Result<(), S7Error> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .map_err(|e| S7Error::Io(format!("Failed to bind to port {}: {}", port, e)))?;
+    let address = listener
+        .local_addr()
+        .map_err(|e| S7Error::Io(format!("Failed to read listening address: {}", e)))?;
+
+    println!("Listening on http://{}", address);
+    let _ = std::io::stdout().flush();
+
+    let server = Arc::new(Server {
+        runtime: Handle::current(),
+        workers: (!options.in_process)
+            .then(|| SteamWorkers::new(Duration::from_secs(options.idle_timeout_secs), |_| {})),
+        port: address.port(),
+        allowed_origins: allowed_origins
+            .iter()
+            .map(|origin| origin.trim_end_matches('/').to_ascii_lowercase())
+            .collect(),
+        connections: AtomicUsize::new(0),
+    });
+
+    tokio::task::spawn_blocking(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+            if server.connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                server.connections.fetch_sub(1, Ordering::SeqCst);
+                let _ = stream.set_write_timeout(Some(Duration::from_secs(1)));
+                let error = HttpError::new(
+                    503,
+                    S7Error::Internal("Too many open connections".to_string()),
+                );
+                write_response(&mut stream, error.into());
+                continue;
+            }
+            let slot = ConnectionSlot(server.clone());
+            std::thread::spawn(move || {
+                handle_connection(stream, &slot.0);
+                drop(slot);
+            });
+        }
+    })
+    .await
+    .map_err(|e| S7Error::Internal(format!("Server task error: {:?}", e)))
+}
+
+fn handle_connection(mut stream: TcpStream, server: &Server) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(30)));
+
+    let response = match read_request(&stream) {
+        Ok(request) => match server.check_request(&request) {
+            Ok(allow_origin) => {
+                let mut response = match (request.method.as_str(), &allow_origin) {
+                    ("OPTIONS", Some(_)) => HttpResponse {
+                        status: 204,
+                        headers: vec![
+                            (
+                                "Access-Control-Allow-Methods",
+                                "GET, POST, PUT, DELETE".to_string(),
+                            ),
+                            ("Access-Control-Allow-Headers", "Content-Type".to_string()),
+                        ],
+                        body: None,
+                    },
+                    ("OPTIONS", None) => HttpError::bad_request(
+                        "OPTIONS is only answered for CORS preflight requests",
+                    )
+                    .into(),
+                    _ => respond(request, server).into(),
+                };
+                if let Some(origin) = allow_origin {
+                    response
+                        .headers
+                        .push(("Access-Control-Allow-Origin", origin));
+                    response.headers.push(("Vary", "Origin".to_string()));
+                }
+                response
+            }
+            Err(error) => error.into(),
+        },
+        Err(error) => error.into(),
+    };
+
+    write_response(&mut stream, response);
+
+    // A request refused before it was read in full leaves input behind. Closing over it
+    // would reset the connection and could discard the response, so read it off first.
+    let _ = stream.shutdown(Shutdown::Write);
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(1)));
+    let _ = std::io::copy(
+        &mut (&stream).take(MAX_BODY_SIZE as u64),
+        &mut std::io::sink(),
+    );
+}
+
+fn write_response(stream: &mut TcpStream, response: HttpResponse) {
+    let body = response
+        .body
+        .as_ref()
+        .map(|body| serde_json::to_string(body).unwrap())
+        .unwrap_or_default();
+
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\n",
+        response.status,
+        reason_phrase(response.status)
+    );
+    if response.body.is_some() {
+        head.push_str("Content-Type: application/json\r\n");
+    }
+    for (name, value) in response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+
+    let _ = write!(
+        stream,
+        "{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        head,
+        body.len(),
+        body
+    );
+    let _ = stream.flush();
+}
+
+impl From<HttpError> for HttpResponse {
+    fn from(HttpError { status, error }: HttpError) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Some(json!({ "error": error.body() })),
+        }
+    }
+}
+
+impl From<Result<Value, HttpError>> for HttpResponse {
+    fn from(result: Result<Value, HttpError>) -> Self {
+        match result {
+            Ok(value) => Self {
+                status: 200,
+                headers: Vec::new(),
+                body: Some(value),
+            },
+            Err(error) => error.into(),
+        }
+    }
+}
+
+fn respond(request: HttpRequest, server: &Server) -> Result<Value, HttpError> {
+    match route(&request)? {
+        Route::Command(command) => server.run(command),
+        Route::SingleItem(command) => match server.run(command)? {
+            Value::Array(mut items) if !items.is_empty() => Ok(items.swap_remove(0)),
+            // With a type, the result is `{items, filtered, not_found}`.
+            Value::Object(mut result) => match result.remove("items") {
+                Some(Value::Array(mut items)) if !items.is_empty() => Ok(items.swap_remove(0)),
+                _ => match result.get("filtered").and_then(|filtered| filtered.get(0)) {
+                    Some(filtered) => Err(HttpError::not_found(format!(
+                        "Workshop item is a {} item, not of the requested type",
+                        filtered["file_type"].as_str().unwrap_or("different")
+                    ))),
+                    None => Err(HttpError::not_found("Workshop item not found")),
+                },
+            },
+            _ => Err(HttpError::not_found("Workshop item not found")),
+        },
+        Route::Status => Ok(status(server.workers.as_deref())),
+    }
+}
+
+impl Server {
+    /// Refuses requests a web page could forge: a Host other than this server guards
+    /// against DNS rebinding, an unknown Origin against cross-site requests, and the
+    /// JSON content type keeps mutating routes out of reach of plain HTML forms.
+    /// Returns the origin to echo back in `Access-Control-Allow-Origin`.
+    fn check_request(&self, request: &HttpRequest) -> Result<Option<String>, HttpError> {
+        let local_hosts = [
+            format!("127.0.0.1:{}", self.port),
+            format!("localhost:{}", self.port),
+        ];
+
+        let host = request
+            .header("host")
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if !local_hosts.contains(&host) {
+            return Err(HttpError::forbidden(format!(
+                "Host {:?} is not allowed",
+                host
+            )));
+        }
+
+        let allow_origin = match request.header("origin") {
+            Some(origin) => {
+                let normalized = origin.trim_end_matches('/').to_ascii_lowercase();
+                let own_origin = local_hosts
+                    .iter()
+                    .any(|host| normalized == format!("http://{}", host));
+                if !own_origin && !self.allowed_origins.contains(&normalized) {
+                    return Err(HttpError::forbidden(format!(
+                        "Origin {} is not allowed, pass it with --allow-origin",
+                        origin
+                    )));
+                }
+                Some(origin.to_string())
+            }
+            None => None,
+        };
+
+        if matches!(request.method.as_str(), "POST" | "PUT" | "DELETE") {
+            let content_type = request.header("content-type").unwrap_or_default();
+            let media_type = content_type.split(';').next().unwrap_or_default().trim();
+            if !media_type.eq_ignore_ascii_case("application/json") {
+                return Err(HttpError::new(
+                    415,
+                    S7Error::InvalidArgument(format!(
+                        "{} requests need Content-Type: application/json",
+                        request.method
+                    )),
+                ));
+            }
+        }
+
+        Ok(allow_origin)
+    }
+
+    fn run(&self, command: Command) -> Result<Value, HttpError> {
+        self.runtime
+            .block_on(dispatch_with(command, self.workers.as_deref()))
+            .map_err(HttpError::from)
+    }
+}
+
+fn route(request: &HttpRequest) -> Result<Route, HttpError> {
+    let segments: Vec<&str> = request
+        .path
+        .trim_matches('/')
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    let command = match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["apps", app_id, "search"]) => Command::SearchWorkshop {
+            app_id: parse_segment("app ID", app_id)?,
+            query: request.query_param("q").unwrap_or_default().to_string(),
+            sort_by: request
+                .query_param("sort")
+                .unwrap_or("relevance")
+                .to_string(),
+            period: request.query_param("period").map(str::to_string),
+            page: match request.query_param("page") {
+                Some(page) => parse_segment("page", page)?,
+                None => 1,
+            },
+            tags: request.query_param("tags").map(str::to_string),
+            exclude_tags: request.query_param("exclude_tags").map(str::to_string),
+            match_any_tags: request.query_param("match_any_tags") == Some("true"),
+            tag_groups: request
+                .query_params("tag_group")
+                .map(str::to_string)
+                .collect(),
+            created_since: request.query_param("created_since").map(str::to_string),
+            created_until: request.query_param("created_until").map(str::to_string),
+            updated_since: request.query_param("updated_since").map(str::to_string),
+            updated_until: request.query_param("updated_until").map(str::to_string),
+            item_type: request.query_param("type").map(str::to_string),
+            game_version: request.query_param("game_version").map(str::to_string),
+            page_info: request.query_param("page_info") == Some("true"),
+            all_pages: request.query_param("all_pages") == Some("true"),
+            max_pages: match request.query_param("max_pages") {
+                Some(max_pages) => Some(parse_segment("max pages", max_pages)?),
+                None => None,
+            },
+        },
+        ("GET", ["apps", app_id, "user-items"]) => Command::UserItems {
+            app_id: parse_segment("app ID", app_id)?,
+            list: request
+                .query_param("list")
+                .unwrap_or("published")
+                .to_string(),
+            order: request.query_param("order").map(str::to_string),
+            page: match request.query_param("page") {
+                Some(page) => parse_segment("page", page)?,
+                None => 1,
+            },
+            user: request.query_param("user").map(str::to_string),
+        },
+        ("GET", ["apps", app_id, "items"]) => Command::WorkshopItems {
+            app_id: parse_segment("app ID", app_id)?,
+            item_ids: parse_id_list(request.query_param("ids").unwrap_or_default())?,
+            with_user_state: request.query_param("user_state") == Some("true"),
+            item_type: request.query_param("type").map(str::to_string),
+        },
+        ("GET", ["apps", app_id, "items", item_id]) => {
+            return Ok(Route::SingleItem(Command::WorkshopItems {
+                app_id: parse_segment("app ID", app_id)?,
+                item_ids: vec![parse_segment("item ID", item_id)?],
+                with_user_state: request.query_param("user_state") == Some("true"),
+                item_type: request.query_param("type").map(str::to_string),
+            }));
+        }
+        ("GET", ["apps", app_id, "items", item_id, "vote"]) => Command::GetVote {
+            app_id: parse_segment("app ID", app_id)?,
+            item_id: parse_segment("item ID", item_id)?,
+        },
+        ("PUT", ["apps", app_id, "items", item_id, "vote"]) => Command::Vote {
+            app_id: parse_segment("app ID", app_id)?,
+            item_id: parse_segment("item ID", item_id)?,
+            vote: request.vote_body()?,
+        },
+        ("POST", ["apps", app_id, "favorites", item_id]) => Command::Favorite {
+            app_id: parse_segment("app ID", app_id)?,
+            item_id: parse_segment("item ID", item_id)?,
+            action: "add".to_string(),
+        },
+        ("DELETE", ["apps", app_id, "favorites", item_id]) => Command::Favorite {
+            app_id: parse_segment("app ID", app_id)?,
+            item_id: parse_segment("item ID", item_id)?,
+            action: "remove".to_string(),
+        },
+        ("GET", ["apps", app_id, "items", item_id, "download"]) => Command::CheckItemDownload {
+            app_id: parse_segment("app ID", app_id)?,
+            item_id: parse_segment("item ID", item_id)?,
+        },
+        ("POST", ["apps", app_id, "items", item_id, "download"]) => Command::DownloadWorkshopItem {
+            app_id: parse_segment("app ID", app_id)?,
+            item_id: parse_segment("item ID", item_id)?,
+        },
+        ("GET", ["apps", app_id, "collections", item_id]) => Command::CollectionItems {
+            app_id: parse_segment("app ID", app_id)?,
+            item_id: parse_segment("collection ID", item_id)?,
+        },
+        ("GET", ["apps", app_id, "subscriptions"]) => Command::SubscribedItems {
+            app_id: parse_segment("app ID", app_id)?,
+            game_version: request.query_param("game_version").map(str::to_string),
+        },
+        ("GET", ["apps", app_id, "compat-report"]) => Command::CompatReport {
+            app_id: parse_segment("app ID", app_id)?,
+            game_version: request
+                .query_param("game_version")
+                .ok_or_else(|| HttpError::bad_request("Missing game_version"))?
+                .to_string(),
+        },
+        ("POST", ["apps", app_id, "subscriptions"]) => Command::Subscribe {
+            app_id: parse_segment("app ID", app_id)?,
+            item_ids: request.item_ids_body()?,
+        },
+        ("DELETE", ["apps", app_id, "subscriptions"]) => Command::Unsubscribe {
+            app_id: parse_segment("app ID", app_id)?,
+            item_ids: request.item_ids_body()?,
+        },
+        ("DELETE", ["apps", app_id, "subscriptions", item_id]) => Command::Unsubscribe {
+            app_id: parse_segment("app ID", app_id)?,
+            item_ids: vec![parse_segment("item ID", item_id)?],
+        },
+        ("GET", ["apps", app_id, "dependencies"]) => Command::ResolveDependencies {
+            app_id: parse_segment("app ID", app_id)?,
+            item_ids: parse_id_list(request.query_param("ids").unwrap_or_default())?,
+            dot: request.query_param("dot") == Some("true"),
+        },
+        ("GET", ["apps", app_id, "workshop-path"]) => Command::WorkshopPath {
+            app_id: parse_segment("app ID", app_id)?,
+        },
+        ("GET", ["apps", app_id, "tags"]) => Command::DiscoverTags {
+            app_id: parse_segment("app ID", app_id)?,
+        },
+        ("GET", ["steam", "library-paths"]) => Command::SteamLibraryPaths {
+            detailed: request.query_param("detailed") == Some("true"),
+        },
+        ("GET", ["steam", "install-paths"]) => Command::SteamInstallPaths,
+        ("DELETE", ["cache"]) => Command::ClearCache,
+        ("GET", ["status"]) => return Ok(Route::Status),
+        _ => {
+            return Err(HttpError::not_found(format!(
+                "No route for {} {}",
+                request.method, request.path
+            )));
+        }
+    };
+
+    Ok(Route::Command(command))
+}
+
+impl HttpRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn query_param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn query_params<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.query
+            .iter()
+            .filter(move |(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn item_ids_body(&self) -> Result<Vec<u64>, HttpError> {
+        serde_json::from_slice::<ItemIdsBody>(&self.body)
+            .map(|body| body.item_ids)
+            .map_err(|e| HttpError::bad_request(format!("Invalid request body: {}", e)))
+    }
+
+    fn vote_body(&self) -> Result<String, HttpError> {
+        serde_json::from_slice::<VoteBody>(&self.body)
+            .map(|body| body.vote)
+            .map_err(|e| HttpError::bad_request(format!("Invalid request body: {}", e)))
+    }
+}
+
+fn parse_segment<T: FromStr>(name: &str, value: &str) -> Result<T, HttpError> {
+    value
+        .parse()
+        .map_err(|_| HttpError::bad_request(format!("Invalid {}: {}", name, value)))
+}
+
+fn parse_id_list(ids: &str) -> Result<Vec<u64>, HttpError> {
+    ids.split(',')
+        .filter(|id| !id.trim().is_empty())
+        .map(|id| parse_segment("item ID", id.trim()))
+        .collect()
+}
+
+fn read_request(stream: &TcpStream) -> Result<HttpRequest, HttpError> {
+    let mut reader = BufReader::new(stream);
+    let bad_request = |message: &str| HttpError::bad_request(message);
+
+    let request_line = read_line(&mut reader, 414, "Request line")?;
+
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(bad_request("Malformed request line"));
+    };
+
+    let mut content_length = 0;
+    let mut headers = Vec::new();
+    loop {
+        let header = read_line(&mut reader, 431, "Header")?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if headers.len() == MAX_HEADERS {
+            return Err(HttpError::new(
+                431,
+                S7Error::InvalidArgument(format!("More than {} headers", MAX_HEADERS)),
+            ));
+        }
+
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(bad_request("Malformed header"));
+        };
+        let (name, value) = (name.trim(), value.trim());
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value
+                .parse()
+                .map_err(|_| bad_request("Invalid Content-Length"))?;
+        }
+        headers.push((name.to_string(), value.to_string()));
+    }
+
+    if content_length > MAX_BODY_SIZE {
+        return Err(HttpError::new(
+            413,
+            S7Error::InvalidArgument("Request body too large".to_string()),
+        ));
+    }
+
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|_| bad_request("Failed to read request body"))?;
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect();
+
+    Ok(HttpRequest {
+        method: method.to_string(),
+        path: percent_decode(path),
+        query,
+        headers,
+        body,
+    })
+}
+
+/// Reads one line of at most `MAX_LINE_LENGTH` bytes, answering a longer one with
+/// `too_long_status` instead of buffering it.
+fn read_line(
+    reader: &mut BufReader<&TcpStream>,
+    too_long_status: u16,
+    what: &str,
+) -> Result<String, HttpError> {
+    let mut line = String::new();
+    reader
+        .take(MAX_LINE_LENGTH as u64 + 1)
+        .read_line(&mut line)
+        .map_err(|_| HttpError::bad_request(format!("Failed to read {}", what.to_lowercase())))?;
+
+    if line.len() > MAX_LINE_LENGTH {
+        return Err(HttpError::new(
+            too_long_status,
+            S7Error::InvalidArgument(format!("{} is longer than {} bytes", what, MAX_LINE_LENGTH)),
+        ));
+    }
+    Ok(line)
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        414 => "URI Too Long",
+        415 => "Unsupported Media Type",
+        431 => "Request Header Fields Too Large",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Internal Server Error",
+    }
+}
@@ -0,0 +1,112 @@
+//! Steam Workshop operations through the Steamworks SDK.
+//!
+//! [`Workshop`] wraps every call that needs a Steam client for one app. Functions that
+//! only inspect the local Steam installation or the cache are available at the crate
+//! root.
+//!
+//! ```no_run
+//! use s7forge::{SearchQuery, UGCQueryType, Workshop};
+//!
+//! # async fn run() -> Result<(), s7forge::S7Error> {
+//! let workshop = Workshop::new(548430)?;
+//! let items = workshop
+//!     .search(SearchQuery {
+//!         text: "tanks".to_string(),
+//!         sort_by: UGCQueryType::RankedByTrend,
+//!         ..SearchQuery::default()
+//!     })
+//!     .await?;
+//!
+//! for item in items {
+//!     println!("{} by {}", item.workshop_item.title, item.creator_name);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Features
+//!
+//! - `cli` (default): the `s7forge` binary.
+//! - `cache` (default): caches workshop items, search results and creator names on
+//!   disk. Without it every call goes to Steam.
+//! - `manifest` (default): publishing items described by a TOML manifest with
+//!   `Workshop::publish_manifest`.
+//! - `tag-rules` (default): per-app display tag rules read from `tag_rules.toml`.
+//!   Without it the built-in rules apply.
+//! - `fake-steam`: a fixed workshop catalog served instead of Steam when
+//!   `S7FORGE_FAKE_STEAM_CATALOG` is set. Only for tests.
+
+mod commands;
+mod core;
+mod error;
+mod utils;
+mod workshop;
+
+pub use commands::add_dependency::AddDependencyResult;
+pub use commands::check_item_download::DownloadInfo;
+pub use commands::clear_cache::ClearCacheResult;
+pub use commands::collection_create::{CollectionCreateResult, CollectionOptions};
+pub use commands::collection_edit::CollectionEditResult;
+pub use commands::collection_items::{CollectionDetails, CollectionInfo};
+pub use commands::compat_report::{CompatReport, IncompatibleItem};
+pub use commands::delete_item::DeleteItemResult;
+pub use commands::favorite::FavoriteResult;
+pub use commands::publish::PublishOptions;
+#[cfg(feature = "manifest")]
+pub use commands::publish_manifest::{
+    MANIFEST_FILE_NAMES, ManifestPublishResult, ManifestRelease, WorkshopManifest,
+};
+pub use commands::remove_dependency::RemoveDependencyResult;
+pub use commands::resolve_dependencies::{DependencyGraph, DependencyNode, DependencyStatus};
+pub use commands::search_workshop::{ALL_PAGES_LIMIT, SEARCH_PAGE_SIZE, SearchPage, TagFilter};
+pub use commands::set_visibility::SetVisibilityResult;
+pub use commands::subscribe::SubscribeResult;
+pub use commands::unsubscribe::UnsubscribeResult;
+pub use commands::vote::ItemVote;
+pub use commands::workshop_items::{
+    EnhancedWorkshopItem, FilterReason, FilteredItem, ItemWithUserState, ItemsOfType, UserItemState,
+};
+pub use core::game_version::GameVersion;
+pub use core::library_folders::LibraryFolder;
+pub use core::localplayer::PlayerSteamId;
+pub use core::steam_backend::{ItemUpdate, SubmittedItem};
+pub use core::steam_install_paths::{SteamInstallPath, SteamInstallSource};
+pub use core::workshop::{
+    ItemInstallInfo, PreviewType, UgcItemVisibility, UpdateProgress, UpdateStatus, Vote,
+};
+pub use core::workshop_item::workshop::{
+    AdditionalPreview, UGCQueryType, UGCType, WorkshopItem, WorkshopItemStatistic,
+};
+pub use error::{ErrorBody, S7Error};
+pub use utils::parse_date::DateRange;
+pub use workshop::{SearchQuery, UserItemsQuery, Workshop};
+
+/// The local workshop content folder for `app_id`, if any Steam library has one.
+pub fn workshop_path(app_id: u32) -> Option<String> {
+    commands::workshop_path::workshop_path(app_id)
+}
+
+/// The paths of all Steam library folders.
+pub fn steam_library_paths() -> Result<Vec<String>, S7Error> {
+    commands::steam_library_paths::steam_library_paths()
+}
+
+/// All Steam library folders with their label, size and installed apps.
+pub fn steam_library_folders() -> Result<Vec<LibraryFolder>, S7Error> {
+    commands::steam_library_paths::steam_library_folders()
+}
+
+/// The detected Steam installations and where each one was found.
+pub fn steam_install_paths() -> Result<Vec<SteamInstallPath>, S7Error> {
+    commands::steam_install_paths::steam_install_paths()
+}
+
+/// Removes every file from the cache directory.
+pub fn clear_cache() -> Result<ClearCacheResult, S7Error> {
+    commands::clear_cache::clear_cache()
+}
+
+/// The apps this process currently holds a Steam client for.
+pub fn initialized_apps() -> Vec<u32> {
+    core::steam_manager::STEAM_MANAGER.initialized_apps()
+}
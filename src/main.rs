@@ -1,87 +1,77 @@
-mod commands;
-mod core;
-mod utils;
 mod cli;
+mod dispatch;
+mod http_serve;
+mod serve;
+mod steam_workers;
 
-use cli::{parse_args, Command};
+use cli::{Command, json_errors_requested, parse_args};
+use s7forge::S7Error;
 
 #[tokio::main]
 async fn main() {
     let command = match parse_args() {
         Ok(cmd) => cmd,
-        Err(err) => {
-            eprintln!("Error: {}", err);
-            std::process::exit(1);
-        }
+        Err(err) => exit_with_error(S7Error::InvalidArgument(err.to_string())),
     };
 
-    let result = match command {
-        Command::CheckItemDownload { app_id, item_id } => {
-            commands::check_item_download::check_item_download(app_id, item_id)
-                .await
-                .map(|info| serde_json::to_string_pretty(&info).unwrap())
-        }
-        Command::CollectionItems { app_id, item_id } => {
-            commands::collection_items::collection_items(app_id, item_id)
-                .await
-                .map(|items| serde_json::to_string_pretty(&items).unwrap())
-        }
-        Command::WorkshopItems { app_id, item_ids } => {
-            commands::workshop_items::workshop_items(app_id, item_ids)
-                .await
-                .map(|items| serde_json::to_string_pretty(&items).unwrap())
-        }
-        Command::Subscribe { app_id, item_ids } => {
-            commands::subscribe::subscribe(app_id, item_ids)
-                .await
-                .map(|results| serde_json::to_string_pretty(&results).unwrap())
-        }
-        Command::Unsubscribe { app_id, item_ids } => {
-            commands::unsubscribe::unsubscribe(app_id, item_ids)
-                .await
-                .map(|results| serde_json::to_string_pretty(&results).unwrap())
-        }
-        Command::DownloadWorkshopItem { app_id, item_id } => {
-            commands::download_workshop_item::download_workshop_item(app_id, item_id)
-                .await
-                .map(|_| "\"Workshop item download completed successfully\"".to_string())
+    match command {
+        Command::Serve(options) => {
+            serve::serve(options).await;
+            std::process::exit(0);
         }
-        Command::SubscribedItems { app_id } => {
-            commands::subscribed_items::subscribed_items(app_id)
-                .await
-                .map(|items| serde_json::to_string_pretty(&items).unwrap())
+        Command::HttpServe {
+            port,
+            allowed_origins,
+            options,
+        } => {
+            if let Err(error) = http_serve::http_serve(port, allowed_origins, options).await {
+                exit_with_error(error);
+            }
+            std::process::exit(0);
         }
-        Command::SearchWorkshop {
+        Command::UpdateItem {
             app_id,
-            query,
-            sort_by,
-            period,
-            page,
-            tags,
-        } => commands::search_workshop::search_workshop(app_id, query, sort_by, period, page, tags)
-            .await
-            .map(|items| serde_json::to_string_pretty(&items).unwrap()),
-        Command::WorkshopPath { app_id } => match commands::workshop_path::workshop_path(app_id) {
-            Some(path) => Ok(serde_json::to_string_pretty(&path).unwrap()),
-            None => Err(format!("Workshop path not found for app ID {}", app_id)),
-        },
-        Command::SteamLibraryPaths => commands::steam_library_paths::steam_library_paths()
-            .map(|paths| serde_json::to_string_pretty(&paths).unwrap()),
-        Command::ClearCache => commands::clear_cache::clear_cache()
-            .map(|message| serde_json::to_string_pretty(&message).unwrap()),
-        Command::DiscoverTags { app_id } => commands::discover_tags::discover_tags(app_id)
-            .await
-            .map(|tags| serde_json::to_string_pretty(&tags).unwrap()),
-    };
+            item_id,
+            changes,
+        } => {
+            // Progress goes to stdout as NDJSON, so the result is printed compact
+            // to keep one JSON value per line.
+            let result = dispatch::update_item(app_id, item_id, changes, |progress| {
+                let mut event = serde_json::to_value(progress).unwrap();
+                event["event"] = "progress".into();
+                println!("{}", event);
+            })
+            .await;
+
+            match result {
+                Ok(output) => {
+                    println!("{}", output);
+                    std::process::exit(0);
+                }
+                Err(error) => exit_with_error(error),
+            }
+        }
+        _ => {}
+    }
+
+    let result = dispatch::dispatch(command)
+        .await
+        .map(|output| serde_json::to_string_pretty(&output).unwrap());
 
     match result {
         Ok(output) => {
             println!("{}", output);
             std::process::exit(0);
         }
-        Err(error) => {
-            eprintln!("Error: {:?}", error);
-            std::process::exit(1);
-        }
+        Err(error) => exit_with_error(error),
+    }
+}
+
+fn exit_with_error(error: S7Error) -> ! {
+    if json_errors_requested() {
+        eprintln!("{}", serde_json::json!({ "error": error.body() }));
+    } else {
+        eprintln!("Error: {}", error);
     }
+    std::process::exit(error.exit_code());
 }
@@ -0,0 +1,264 @@
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::io::{BufRead, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+
+use crate::cli::{Command, ServeOptions};
+use crate::dispatch::{dispatch_with, status, update_item};
+use crate::steam_workers::SteamWorkers;
+use s7forge::{S7Error, Workshop};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const COMMAND_ERROR: i64 = -32000;
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Option<Value>,
+}
+
+/// Reads newline-delimited JSON-RPC 2.0 requests from stdin until EOF. Each request runs
+/// on its own task, so a slow download does not block other calls. Responses may
+/// therefore arrive out of order and must be matched by id.
+pub async fn serve(options: ServeOptions) {
+    // Worker notifications are forwarded unchanged, so clients see the same
+    // download-progress messages in both modes.
+    let workers = (!options.in_process).then(|| {
+        SteamWorkers::new(Duration::from_secs(options.idle_timeout_secs), |message| {
+            write_message(&message)
+        })
+    });
+
+    let (line_tx, mut line_rx) = mpsc::channel::<String>(32);
+
+    // tokio is built without io-std, so stdin is read on a plain thread.
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if line_tx.blocking_send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut tasks = JoinSet::new();
+
+    while let Some(line) = line_rx.recv().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let value: Value = match serde_json::from_str(&line) {
+            Ok(value) => value,
+            Err(e) => {
+                write_error(Value::Null, PARSE_ERROR, format!("Parse error: {}", e));
+                continue;
+            }
+        };
+
+        let request: Request = match serde_json::from_value(value) {
+            Ok(request) => request,
+            Err(e) => {
+                write_error(
+                    Value::Null,
+                    INVALID_REQUEST,
+                    format!("Invalid request: {}", e),
+                );
+                continue;
+            }
+        };
+
+        if request.jsonrpc != "2.0" {
+            write_error(
+                request.id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "Invalid request: jsonrpc must be \"2.0\"".to_string(),
+            );
+            continue;
+        }
+
+        tasks.spawn(handle_request(request, workers.clone()));
+        while tasks.try_join_next().is_some() {}
+    }
+
+    while tasks.join_next().await.is_some() {}
+}
+
+async fn handle_request(request: Request, workers: Option<Arc<SteamWorkers>>) {
+    if request.method == "status" {
+        if let Some(id) = request.id {
+            write_message(&json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": status(workers.as_deref()),
+            }));
+        }
+        return;
+    }
+
+    let command = match parse_command(&request.method, request.params) {
+        Ok(command) => command,
+        Err((code, message)) => {
+            if let Some(id) = request.id {
+                write_error(id, code, message);
+            }
+            return;
+        }
+    };
+
+    let result = match (command, workers.as_deref()) {
+        (Command::DownloadWorkshopItem { app_id, item_id }, None) => {
+            download_with_notifications(app_id, item_id).await
+        }
+        (
+            Command::UpdateItem {
+                app_id,
+                item_id,
+                changes,
+            },
+            None,
+        ) => {
+            update_item(app_id, item_id, changes, move |progress| {
+                let mut params = serde_json::to_value(progress).unwrap();
+                params["app_id"] = app_id.into();
+                write_notification("update-progress", params);
+            })
+            .await
+        }
+        (command, workers) => dispatch_with(command, workers).await,
+    };
+
+    let Some(id) = request.id else {
+        return;
+    };
+
+    match result {
+        Ok(result) => write_message(&json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": result,
+        })),
+        Err(error) => write_command_error(id, &error),
+    }
+}
+
+fn parse_command(method: &str, params: Option<Value>) -> Result<Command, (i64, String)> {
+    let params = match params {
+        None | Some(Value::Null) => Value::Object(Map::new()),
+        Some(params) => params,
+    };
+    let has_params = params.as_object().is_none_or(|fields| !fields.is_empty());
+
+    let result =
+        serde_json::from_value(json!({ "method": method, "params": params })).or_else(|e| {
+            // Commands without options are unit variants, which only deserialize
+            // when params are left out entirely.
+            if has_params {
+                Err(e)
+            } else {
+                serde_json::from_value(json!({ "method": method })).map_err(|_| e)
+            }
+        });
+
+    result.map_err(|e| {
+        let message = e.to_string();
+        if message.starts_with("unknown variant") {
+            (METHOD_NOT_FOUND, format!("Method not found: {}", method))
+        } else {
+            (INVALID_PARAMS, format!("Invalid params: {}", message))
+        }
+    })
+}
+
+async fn download_with_notifications(app_id: u32, item_id: u64) -> Result<Value, S7Error> {
+    let workshop = Workshop::new(app_id)?;
+    let last_progress = Mutex::new(None);
+
+    workshop
+        .download_with_progress(item_id, move |downloaded, total| {
+            let mut last_progress = last_progress.lock().unwrap();
+            if *last_progress == Some((downloaded, total)) {
+                return;
+            }
+            *last_progress = Some((downloaded, total));
+
+            write_notification(
+                "download-progress",
+                json!({
+                    "app_id": app_id,
+                    "item_id": item_id,
+                    "downloaded_bytes": downloaded,
+                    "total_bytes": total,
+                }),
+            );
+        })
+        .await?;
+
+    let install_info = workshop.install_info(item_id)?;
+
+    write_notification(
+        "item-installed",
+        json!({
+            "app_id": app_id,
+            "item_id": item_id,
+            "folder": install_info.as_ref().map(|info| info.folder.clone()),
+            "size_on_disk": install_info.as_ref().map(|info| info.size_on_disk),
+        }),
+    );
+
+    Ok(Value::from("Workshop item download completed successfully"))
+}
+
+fn write_notification(method: &str, params: Value) {
+    write_message(&json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "params": params,
+    }));
+}
+
+fn write_error(id: Value, code: i64, message: String) {
+    write_message(&json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {
+            "code": code,
+            "message": message,
+        },
+    }));
+}
+
+// Command failures carry the `S7Error` code and details in `data`, which is also how
+// the worker parent rebuilds the original error.
+fn write_command_error(id: Value, error: &S7Error) {
+    write_message(&json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {
+            "code": COMMAND_ERROR,
+            "message": error.message(),
+            "data": {
+                "code": error.code(),
+                "details": error.details(),
+            },
+        },
+    }));
+}
+
+fn write_message(message: &Value) {
+    let mut stdout = std::io::stdout().lock();
+    let _ = writeln!(stdout, "{}", message);
+    let _ = stdout.flush();
+}
@@ -0,0 +1,311 @@
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use s7forge::S7Error;
+
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+type ResponseHandler = Box<dyn FnOnce(Result<Value, S7Error>) + Send>;
+type NotificationSink = Arc<dyn Fn(Value) + Send + Sync>;
+
+#[derive(Debug, Serialize)]
+pub struct WorkerStatus {
+    pub app_id: u32,
+    pub pid: u32,
+    pub uptime_secs: u64,
+    pub idle_secs: u64,
+    pub pending_requests: usize,
+    pub healthy: bool,
+}
+
+// One `s7forge serve --in-process` child that owns the Steam client for a single app.
+struct Worker {
+    app_id: u32,
+    child: Mutex<Child>,
+    stdin: Mutex<ChildStdin>,
+    pending: Arc<Mutex<FxHashMap<u64, ResponseHandler>>>,
+    next_id: AtomicU64,
+    started_at: Instant,
+    last_used: Mutex<Instant>,
+    healthy: Arc<AtomicBool>,
+}
+
+impl Worker {
+    fn spawn(app_id: u32, on_notification: NotificationSink) -> Result<Self, S7Error> {
+        let executable = std::env::current_exe()
+            .map_err(|e| S7Error::Io(format!("Failed to locate s7forge executable: {}", e)))?;
+
+        let mut child = Command::new(executable)
+            .args(["serve", "--in-process"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| {
+                S7Error::Io(format!("Failed to start worker for app {}: {}", app_id, e))
+            })?;
+
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(S7Error::Io("Failed to open worker pipes".to_string()));
+        };
+
+        let pending: Arc<Mutex<FxHashMap<u64, ResponseHandler>>> = Arc::default();
+        let healthy = Arc::new(AtomicBool::new(true));
+
+        let reader_pending = pending.clone();
+        let reader_healthy = healthy.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                    continue;
+                };
+
+                match message.get("id").and_then(Value::as_u64) {
+                    Some(id) => {
+                        let handler = reader_pending.lock().unwrap().remove(&id);
+                        if let Some(handler) = handler {
+                            handler(response_result(message));
+                        }
+                    }
+                    None if message.get("method").is_some() => on_notification(message),
+                    None => {}
+                }
+            }
+
+            reader_healthy.store(false, Ordering::SeqCst);
+            let handlers: Vec<ResponseHandler> = reader_pending
+                .lock()
+                .unwrap()
+                .drain()
+                .map(|(_, handler)| handler)
+                .collect();
+            for handler in handlers {
+                handler(Err(S7Error::Internal(format!(
+                    "Steam worker for app {} exited",
+                    app_id
+                ))));
+            }
+        });
+
+        Ok(Self {
+            app_id,
+            child: Mutex::new(child),
+            stdin: Mutex::new(stdin),
+            pending,
+            next_id: AtomicU64::new(1),
+            started_at: Instant::now(),
+            last_used: Mutex::new(Instant::now()),
+            healthy,
+        })
+    }
+
+    fn send(&self, mut call: Value, handler: ResponseHandler) {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        call["jsonrpc"] = Value::from("2.0");
+        call["id"] = Value::from(id);
+
+        self.pending.lock().unwrap().insert(id, handler);
+
+        let written = {
+            let mut stdin = self.stdin.lock().unwrap();
+            writeln!(stdin, "{}", call).and_then(|_| stdin.flush())
+        };
+
+        if let Err(e) = written {
+            self.healthy.store(false, Ordering::SeqCst);
+            let handler = self.pending.lock().unwrap().remove(&id);
+            if let Some(handler) = handler {
+                handler(Err(S7Error::Io(format!(
+                    "Failed to send request to Steam worker for app {}: {}",
+                    self.app_id, e
+                ))));
+            }
+        }
+    }
+
+    async fn call(&self, call: Value) -> Result<Value, S7Error> {
+        *self.last_used.lock().unwrap() = Instant::now();
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.send(
+            call,
+            Box::new(move |result| {
+                let _ = tx.send(result);
+            }),
+        );
+
+        rx.await.map_err(|_| {
+            S7Error::Internal(format!(
+                "Steam worker for app {} dropped the request",
+                self.app_id
+            ))
+        })?
+    }
+
+    fn is_alive(&self) -> bool {
+        self.healthy.load(Ordering::SeqCst)
+            && matches!(self.child.lock().unwrap().try_wait(), Ok(None))
+    }
+
+    // A worker is healthy when its process is running and it still answers a
+    // `status` request in time.
+    fn check_health(&self) -> bool {
+        if !self.is_alive() {
+            return false;
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.send(
+            json!({ "method": "status" }),
+            Box::new(move |result| {
+                let _ = tx.send(result);
+            }),
+        );
+
+        let healthy = matches!(rx.recv_timeout(HEALTH_CHECK_TIMEOUT), Ok(Ok(_)));
+        self.healthy.store(healthy, Ordering::SeqCst);
+        healthy
+    }
+
+    fn idle_for(&self) -> Duration {
+        if self.pending.lock().unwrap().is_empty() {
+            self.last_used.lock().unwrap().elapsed()
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    fn status(&self) -> WorkerStatus {
+        let pid = self.child.lock().unwrap().id();
+        WorkerStatus {
+            app_id: self.app_id,
+            pid,
+            uptime_secs: self.started_at.elapsed().as_secs(),
+            idle_secs: self.idle_for().as_secs(),
+            pending_requests: self.pending.lock().unwrap().len(),
+            healthy: self.is_alive(),
+        }
+    }
+
+    fn kill(&self) {
+        let mut child = self.child.lock().unwrap();
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+fn response_result(message: Value) -> Result<Value, S7Error> {
+    if let Some(error) = message.get("error") {
+        let text = error["message"]
+            .as_str()
+            .unwrap_or("Unknown worker error")
+            .to_string();
+        let data = &error["data"];
+        return Err(S7Error::from_body(
+            data["code"].as_str().unwrap_or_default(),
+            text,
+            &data["details"],
+        ));
+    }
+
+    Ok(message.get("result").cloned().unwrap_or(Value::Null))
+}
+
+/// Keeps one worker process per app ID. The Steam SDK binds a process to a single
+/// app, so daemon modes route app-scoped commands here instead of re-initializing
+/// the in-process client whenever the app changes. Workers are started on first
+/// use, replaced when they stop answering and shut down after `idle_timeout`.
+pub struct SteamWorkers {
+    workers: Mutex<FxHashMap<u32, Arc<Worker>>>,
+    idle_timeout: Duration,
+    on_notification: NotificationSink,
+}
+
+impl SteamWorkers {
+    pub fn new<F>(idle_timeout: Duration, on_notification: F) -> Arc<Self>
+    where
+        F: Fn(Value) + Send + Sync + 'static,
+    {
+        let workers = Arc::new(Self {
+            workers: Mutex::new(FxHashMap::default()),
+            idle_timeout,
+            on_notification: Arc::new(on_notification),
+        });
+
+        let maintained = Arc::downgrade(&workers);
+        let interval = (idle_timeout / 2).clamp(Duration::from_secs(1), Duration::from_secs(30));
+        std::thread::spawn(move || maintain(maintained, interval));
+
+        workers
+    }
+
+    /// Sends a `{ "method", "params" }` call to the worker for `app_id`.
+    pub async fn call(&self, app_id: u32, call: Value) -> Result<Value, S7Error> {
+        self.worker(app_id)?.call(call).await
+    }
+
+    pub fn statuses(&self) -> Vec<WorkerStatus> {
+        let mut statuses: Vec<WorkerStatus> = self
+            .workers
+            .lock()
+            .unwrap()
+            .values()
+            .map(|worker| worker.status())
+            .collect();
+        statuses.sort_by_key(|status| status.app_id);
+        statuses
+    }
+
+    fn worker(&self, app_id: u32) -> Result<Arc<Worker>, S7Error> {
+        let mut workers = self.workers.lock().unwrap();
+        if let Some(worker) = workers.get(&app_id) {
+            if worker.is_alive() {
+                return Ok(worker.clone());
+            }
+            worker.kill();
+        }
+
+        let worker = Arc::new(Worker::spawn(app_id, self.on_notification.clone())?);
+        workers.insert(app_id, worker.clone());
+        Ok(worker)
+    }
+
+    fn evict_idle_and_unhealthy(&self) {
+        let workers: Vec<Arc<Worker>> = self.workers.lock().unwrap().values().cloned().collect();
+
+        for worker in workers {
+            let healthy = worker.check_health();
+
+            let mut current = self.workers.lock().unwrap();
+            let still_registered = current
+                .get(&worker.app_id)
+                .is_some_and(|current| Arc::ptr_eq(current, &worker));
+            if !still_registered || (healthy && worker.idle_for() < self.idle_timeout) {
+                continue;
+            }
+
+            current.remove(&worker.app_id);
+            drop(current);
+            worker.kill();
+        }
+    }
+}
+
+fn maintain(workers: Weak<SteamWorkers>, interval: Duration) {
+    loop {
+        std::thread::sleep(interval);
+        let Some(workers) = workers.upgrade() else {
+            break;
+        };
+        workers.evict_idle_and_unhealthy();
+    }
+}
@@ -0,0 +1,29 @@
+use crate::core::steam_backend::SteamBackend;
+use crate::core::workshop_item::workshop::WorkshopItem;
+use crate::error::S7Error;
+
+/// Looks up `item_id` and fails unless the logged-in user owns it.
+pub async fn check_item_owner(
+    backend: &dyn SteamBackend,
+    item_id: u64,
+) -> Result<WorkshopItem, S7Error> {
+    let item = find_item(backend, item_id).await?;
+
+    if item.owner.steam_id64 != backend.local_user().steam_id64 {
+        return Err(S7Error::not_owner(item_id));
+    }
+
+    Ok(item)
+}
+
+/// Looks up `item_id` directly, including items that are not regular workshop content.
+pub async fn find_item(backend: &dyn SteamBackend, item_id: u64) -> Result<WorkshopItem, S7Error> {
+    backend
+        .query_items(vec![item_id])
+        .await?
+        .items
+        .into_iter()
+        .next()
+        .flatten()
+        .ok_or_else(|| S7Error::NotFound(format!("Workshop item {} not found", item_id)))
+}
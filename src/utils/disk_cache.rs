@@ -0,0 +1,71 @@
+#[cfg(feature = "cache")]
+use std::fs;
+
+use crate::error::S7Error;
+#[cfg(feature = "cache")]
+use crate::utils::get_cache_dir::get_cache_dir;
+
+#[cfg(feature = "cache")]
+const LOCK_FILE: &str = "cache.lock";
+
+/// Reads `file_name` from the cache directory. Returns `Ok(None)` when nothing was
+/// cached yet, or always when built without the `cache` feature.
+#[cfg(feature = "cache")]
+pub fn load<T: bincode::Decode<()>>(file_name: &str) -> Result<Option<T>, S7Error> {
+    let cache_path = get_cache_dir()?.join(file_name);
+    if !cache_path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read(&cache_path)
+        .map_err(|e| S7Error::Io(format!("Failed to read {}: {}", file_name, e)))?;
+    let (value, _) = bincode::decode_from_slice(&content, bincode::config::standard())
+        .map_err(|e| S7Error::Internal(format!("Failed to decode {}: {}", file_name, e)))?;
+
+    Ok(Some(value))
+}
+
+/// Changes the cached `file_name` under a lock shared by every s7forge process, so
+/// concurrent changes are not lost. A file that cannot be read starts from the default,
+/// and nothing is written when `change` returns false.
+#[cfg(feature = "cache")]
+pub fn update<T, F>(file_name: &str, change: F) -> Result<(), S7Error>
+where
+    T: Default + bincode::Encode + bincode::Decode<()>,
+    F: FnOnce(&mut T) -> bool,
+{
+    let cache_dir = get_cache_dir()?;
+    let lock = fs::File::create(cache_dir.join(LOCK_FILE))
+        .map_err(|e| S7Error::Io(format!("Failed to open cache lock: {}", e)))?;
+    lock.lock()
+        .map_err(|e| S7Error::Io(format!("Failed to lock cache: {}", e)))?;
+
+    let mut value = load(file_name).ok().flatten().unwrap_or_default();
+    if !change(&mut value) {
+        return Ok(());
+    }
+
+    let encoded = bincode::encode_to_vec(&value, bincode::config::standard())
+        .map_err(|e| S7Error::Internal(format!("Failed to encode {}: {}", file_name, e)))?;
+    // Readers don't take the lock, so the file is replaced rather than rewritten.
+    let temp_path = cache_dir.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+    fs::write(&temp_path, encoded)
+        .and_then(|_| fs::rename(&temp_path, cache_dir.join(file_name)))
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            S7Error::Io(format!("Failed to write {}: {}", file_name, e))
+        })
+}
+
+#[cfg(not(feature = "cache"))]
+pub fn load<T>(_file_name: &str) -> Result<Option<T>, S7Error> {
+    Ok(None)
+}
+
+#[cfg(not(feature = "cache"))]
+pub fn update<T, F>(_file_name: &str, _change: F) -> Result<(), S7Error>
+where
+    F: FnOnce(&mut T) -> bool,
+{
+    Ok(())
+}
@@ -1,16 +1,26 @@
 use std::path::PathBuf;
 
-pub fn get_cache_dir() -> Result<PathBuf, String> {
-    let exe_path =
-        std::env::current_exe().map_err(|e| format!("Failed to get executable path: {}", e))?;
-    let exe_dir = exe_path
-        .parent()
-        .ok_or("Failed to get executable directory")?;
-    let cache_dir = exe_dir.join("cache");
+use crate::error::S7Error;
+
+/// Names a cache directory to use instead of the one next to the executable.
+pub const CACHE_DIR_ENV: &str = "S7FORGE_CACHE_DIR";
+
+pub fn get_cache_dir() -> Result<PathBuf, S7Error> {
+    let cache_dir = match std::env::var_os(CACHE_DIR_ENV) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let exe_path = std::env::current_exe()
+                .map_err(|e| S7Error::Io(format!("Failed to get executable path: {}", e)))?;
+            let exe_dir = exe_path
+                .parent()
+                .ok_or_else(|| S7Error::Io("Failed to get executable directory".to_string()))?;
+            exe_dir.join("cache")
+        }
+    };
 
     if !cache_dir.exists() {
         std::fs::create_dir_all(&cache_dir)
-            .map_err(|e| format!("Failed to create cache directory: {:?}", e))?;
+            .map_err(|e| S7Error::Io(format!("Failed to create cache directory: {:?}", e)))?;
     }
 
     Ok(cache_dir)
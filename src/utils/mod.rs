@@ -1,3 +1,7 @@
-pub mod extract_quoted_strings;
+pub mod check_item_owner;
+pub mod disk_cache;
 pub mod fetch_creator_names;
 pub mod get_cache_dir;
+pub mod parse_date;
+pub mod user_lists;
+pub mod vdf;
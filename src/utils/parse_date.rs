@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::S7Error;
+
+pub const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
+/// An inclusive range of Unix timestamps, open on a side left at `None`.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
+pub struct DateRange {
+    pub since: Option<u32>,
+    pub until: Option<u32>,
+}
+
+impl DateRange {
+    /// Takes Unix timestamps or `YYYY-MM-DD` dates in UTC. A date as `until` covers the
+    /// whole day.
+    pub fn parse(since: Option<&str>, until: Option<&str>) -> Result<Self, S7Error> {
+        let range = Self {
+            since: since.map(|date| parse_date(date, 0)).transpose()?,
+            until: until
+                .map(|date| parse_date(date, SECONDS_PER_DAY - 1))
+                .transpose()?,
+        };
+        if let (Some(since), Some(until)) = (range.since, range.until)
+            && since > until
+        {
+            return Err(S7Error::InvalidArgument(
+                "Date range ends before it starts".to_string(),
+            ));
+        }
+        Ok(range)
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.since.is_none() && self.until.is_none()
+    }
+}
+
+/// Parses a Unix timestamp or a `YYYY-MM-DD` date in UTC. A date resolves to
+/// `time_of_day` seconds after its midnight.
+pub fn parse_date(value: &str, time_of_day: u32) -> Result<u32, S7Error> {
+    let invalid = || {
+        S7Error::InvalidArgument(format!(
+            "Invalid date '{}'. Expected YYYY-MM-DD or a Unix timestamp",
+            value
+        ))
+    };
+    let value = value.trim();
+
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) {
+        return value.parse().map_err(|_| invalid());
+    }
+
+    let parts = value
+        .split('-')
+        .map(|part| part.parse::<u32>().ok())
+        .collect::<Option<Vec<u32>>>()
+        .ok_or_else(invalid)?;
+    let [year, month, day] = parts[..] else {
+        return Err(invalid());
+    };
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return Err(invalid());
+    }
+
+    let seconds = days_from_civil(year.into(), month.into(), day.into())
+        * i64::from(SECONDS_PER_DAY)
+        + i64::from(time_of_day);
+    u32::try_from(seconds).map_err(|_| invalid())
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Formats a Unix timestamp as its `YYYY-MM-DD` date in UTC, the inverse of
+/// `parse_date`.
+pub fn format_date(timestamp: u64) -> String {
+    let days = (timestamp / u64::from(SECONDS_PER_DAY)) as i64;
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Days since 1970-01-01 in the proleptic Gregorian calendar, by Howard Hinnant's
+// algorithm. `civil_from_days` is its inverse.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,44 @@
+use rustc_hash::FxHashSet;
+
+use crate::core::steam_backend::{SteamBackend, UserQuery};
+use crate::core::workshop_item::workshop::{UGCType, UserListOrder, UserListType};
+use crate::error::S7Error;
+
+// Steam returns at most this many items per page of a UGC query.
+const RESULTS_PER_PAGE: usize = 50;
+
+/// The IDs of every item on one of the logged-in user's lists, read page by page.
+/// Steam has no query for whether a single item is a favorite, so this is also how
+/// favorites are looked up.
+pub async fn user_list_ids(
+    backend: &dyn SteamBackend,
+    list: UserListType,
+) -> Result<FxHashSet<u64>, S7Error> {
+    let account_id = backend.local_user().account_id;
+    let mut ids = FxHashSet::default();
+
+    for page in 1u32.. {
+        let result = backend
+            .query_user(UserQuery {
+                account_id,
+                list,
+                order: UserListOrder::CreationOrderDesc,
+                ugc_type: UGCType::All,
+                page,
+            })
+            .await?;
+        let page_len = result.items.len();
+        ids.extend(
+            result
+                .items
+                .into_iter()
+                .flatten()
+                .map(|item| item.published_file_id),
+        );
+        if page_len < RESULTS_PER_PAGE {
+            break;
+        }
+    }
+
+    Ok(ids)
+}
@@ -0,0 +1,345 @@
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A node in a parsed KeyValues document: either a plain string or a nested table.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VdfValue {
+    String(String),
+    Table(VdfTable),
+}
+
+impl VdfValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            VdfValue::String(value) => Some(value),
+            VdfValue::Table(_) => None,
+        }
+    }
+
+    pub fn as_table(&self) -> Option<&VdfTable> {
+        match self {
+            VdfValue::Table(table) => Some(table),
+            VdfValue::String(_) => None,
+        }
+    }
+}
+
+/// An ordered list of key/value pairs. Keys are matched case-insensitively, like Steam does,
+/// and duplicate keys are kept in document order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VdfTable {
+    entries: Vec<(String, VdfValue)>,
+}
+
+impl VdfTable {
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &VdfValue)> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    pub fn get(&self, key: &str) -> Option<&VdfValue> {
+        self.entries
+            .iter()
+            .find(|(entry_key, _)| entry_key.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(VdfValue::as_str)
+    }
+
+    pub fn get_table(&self, key: &str) -> Option<&VdfTable> {
+        self.get(key).and_then(VdfValue::as_table)
+    }
+
+    /// Resolves a `/`-separated path such as `libraryfolders/0/path`.
+    pub fn lookup(&self, path: &str) -> Option<&VdfValue> {
+        let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+        let mut current = self.get(segments.next()?)?;
+        for segment in segments {
+            current = current.as_table()?.get(segment)?;
+        }
+        Some(current)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VdfError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for VdfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for VdfError {}
+
+/// Parses a text KeyValues (VDF) document into its root table.
+pub fn parse(input: &str) -> Result<VdfTable, VdfError> {
+    let mut parser = Parser::new(input);
+    parser.parse_table(false)
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Str(String),
+    Open,
+    Close,
+    Condition(String),
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+    peeked: Option<(Token, usize, usize)>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input
+                .strip_prefix('\u{feff}')
+                .unwrap_or(input)
+                .chars()
+                .peekable(),
+            line: 1,
+            column: 1,
+            peeked: None,
+        }
+    }
+
+    fn parse_table(&mut self, nested: bool) -> Result<VdfTable, VdfError> {
+        let mut table = VdfTable::default();
+
+        loop {
+            let (token, line, column) = match self.next_token()? {
+                Some(token) => token,
+                None if nested => return Err(self.error("Unexpected end of input, expected '}'")),
+                None => return Ok(table),
+            };
+
+            let key = match token {
+                Token::Str(key) => key,
+                Token::Close if nested => return Ok(table),
+                Token::Close => return Err(error_at(line, column, "Unmatched '}'")),
+                Token::Open => return Err(error_at(line, column, "Expected a key, found '{'")),
+                Token::Condition(_) => {
+                    return Err(error_at(
+                        line,
+                        column,
+                        "Expected a key, found a conditional",
+                    ));
+                }
+            };
+
+            let (token, line, column) = self
+                .next_token()?
+                .ok_or_else(|| self.error(&format!("Missing value for key \"{}\"", key)))?;
+
+            let value = match token {
+                Token::Str(value) => VdfValue::String(value),
+                Token::Open => VdfValue::Table(self.parse_table(true)?),
+                Token::Close | Token::Condition(_) => {
+                    return Err(error_at(
+                        line,
+                        column,
+                        &format!("Missing value for key \"{}\"", key),
+                    ));
+                }
+            };
+
+            let include = match self.peek_token()? {
+                Some(Token::Condition(_)) => match self.next_token()? {
+                    Some((Token::Condition(condition), line, column)) => {
+                        evaluate_condition(&condition)
+                            .ok_or_else(|| error_at(line, column, "Invalid conditional"))?
+                    }
+                    _ => unreachable!(),
+                },
+                _ => true,
+            };
+
+            if include {
+                table.entries.push((key, value));
+            }
+        }
+    }
+
+    fn peek_token(&mut self) -> Result<Option<&Token>, VdfError> {
+        if self.peeked.is_none() {
+            self.peeked = self.read_token()?;
+        }
+        Ok(self.peeked.as_ref().map(|(token, _, _)| token))
+    }
+
+    fn next_token(&mut self) -> Result<Option<(Token, usize, usize)>, VdfError> {
+        match self.peeked.take() {
+            Some(token) => Ok(Some(token)),
+            None => self.read_token(),
+        }
+    }
+
+    fn read_token(&mut self) -> Result<Option<(Token, usize, usize)>, VdfError> {
+        self.skip_whitespace_and_comments();
+
+        let (line, column) = (self.line, self.column);
+        let token = match self.chars.peek().copied() {
+            None => return Ok(None),
+            Some('{') => {
+                self.bump();
+                Token::Open
+            }
+            Some('}') => {
+                self.bump();
+                Token::Close
+            }
+            Some('"') => {
+                self.bump();
+                Token::Str(self.read_quoted(line, column)?)
+            }
+            Some('[') => {
+                self.bump();
+                Token::Condition(self.read_condition(line, column)?)
+            }
+            Some(_) => Token::Str(self.read_unquoted()),
+        };
+
+        Ok(Some((token, line, column)))
+    }
+
+    fn read_quoted(&mut self, line: usize, column: usize) -> Result<String, VdfError> {
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                None => return Err(error_at(line, column, "Unterminated string")),
+                Some('"') => return Ok(value),
+                Some('\\') => match self.bump() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some('\\') => value.push('\\'),
+                    Some('"') => value.push('"'),
+                    Some(other) => {
+                        value.push('\\');
+                        value.push(other);
+                    }
+                    None => return Err(error_at(line, column, "Unterminated string")),
+                },
+                Some(ch) => value.push(ch),
+            }
+        }
+    }
+
+    fn read_unquoted(&mut self) -> String {
+        let mut value = String::new();
+        while let Some(&ch) = self.chars.peek() {
+            if ch.is_whitespace() || matches!(ch, '"' | '{' | '}' | '[') {
+                break;
+            }
+            value.push(ch);
+            self.bump();
+        }
+        value
+    }
+
+    fn read_condition(&mut self, line: usize, column: usize) -> Result<String, VdfError> {
+        let mut condition = String::new();
+        loop {
+            match self.bump() {
+                None | Some('\n') => {
+                    return Err(error_at(line, column, "Unterminated conditional"));
+                }
+                Some(']') => return Ok(condition),
+                Some(ch) => condition.push(ch),
+            }
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        loop {
+            match self.chars.peek().copied() {
+                Some(ch) if ch.is_whitespace() => {
+                    self.bump();
+                }
+                Some('/') => {
+                    let mut lookahead = self.chars.clone();
+                    lookahead.next();
+                    if lookahead.peek() != Some(&'/') {
+                        return;
+                    }
+                    while let Some(ch) = self.bump() {
+                        if ch == '\n' {
+                            break;
+                        }
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.chars.next()?;
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(ch)
+    }
+
+    fn error(&self, message: &str) -> VdfError {
+        error_at(self.line, self.column, message)
+    }
+}
+
+fn error_at(line: usize, column: usize, message: &str) -> VdfError {
+    VdfError {
+        line,
+        column,
+        message: message.to_string(),
+    }
+}
+
+/// Evaluates a platform conditional such as `$WIN32`, `!$OSX` or `$LINUX || $OSX`.
+fn evaluate_condition(condition: &str) -> Option<bool> {
+    let mut any = false;
+    for alternative in condition.split("||") {
+        let mut all = true;
+        for term in alternative.split("&&") {
+            let term = term.trim();
+            let (negated, name) = match term.strip_prefix('!') {
+                Some(name) => (true, name.trim()),
+                None => (false, term),
+            };
+            let name = name.strip_prefix('$')?;
+            all &= platform_flag(name) != negated;
+        }
+        any |= all;
+    }
+    Some(any)
+}
+
+fn platform_flag(name: &str) -> bool {
+    match name.to_ascii_uppercase().as_str() {
+        "WINDOWS" | "WIN32" => cfg!(windows),
+        "WIN64" => cfg!(all(windows, target_pointer_width = "64")),
+        "X360" | "PS3" => false,
+        "OSX" => cfg!(target_os = "macos"),
+        "LINUX" => cfg!(target_os = "linux"),
+        "POSIX" => cfg!(unix),
+        _ => false,
+    }
+}
@@ -0,0 +1,416 @@
+use crate::commands;
+use crate::commands::add_dependency::AddDependencyResult;
+use crate::commands::check_item_download::DownloadInfo;
+use crate::commands::collection_create::{CollectionCreateResult, CollectionOptions};
+use crate::commands::collection_edit::CollectionEditResult;
+use crate::commands::collection_items::CollectionDetails;
+use crate::commands::compat_report::CompatReport;
+use crate::commands::delete_item::DeleteItemResult;
+use crate::commands::favorite::FavoriteResult;
+use crate::commands::publish::PublishOptions;
+#[cfg(feature = "manifest")]
+use crate::commands::publish_manifest::{ManifestPublishResult, ManifestRelease, WorkshopManifest};
+use crate::commands::remove_dependency::RemoveDependencyResult;
+use crate::commands::resolve_dependencies::DependencyGraph;
+use crate::commands::search_workshop::{SearchFilter, SearchPage, TagFilter};
+use crate::commands::set_visibility::SetVisibilityResult;
+use crate::commands::subscribe::SubscribeResult;
+use crate::commands::unsubscribe::UnsubscribeResult;
+use crate::commands::vote::ItemVote;
+use crate::commands::workshop_items::{EnhancedWorkshopItem, ItemWithUserState, ItemsOfType};
+use crate::core::game_version::GameVersion;
+use crate::core::steam_backend::{ItemUpdate, SubmittedItem};
+use crate::core::steam_manager;
+use crate::core::workshop::{ItemInstallInfo, UgcItemVisibility, UpdateProgress, Vote};
+use crate::core::workshop_item::workshop::{UGCQueryType, UGCType};
+use crate::error::S7Error;
+use crate::utils::parse_date::DateRange;
+
+/// Options for [`Workshop::search`]. `period` only applies to the trend sorts and is one
+/// of `today`, `one-week` (the default), `three-months`, `six-months` or `one-year`.
+/// Items of other types than `item_type` are listed in [`SearchPage::filtered`], as are
+/// items without a version tag covering `game_version`. Pages from `page` on are fetched
+/// until `max_pages` is reached, or all of them up to
+/// [`ALL_PAGES_LIMIT`](crate::ALL_PAGES_LIMIT) when it is `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchQuery {
+    pub text: String,
+    pub sort_by: UGCQueryType,
+    pub period: Option<String>,
+    pub page: u32,
+    pub tags: TagFilter,
+    pub created: DateRange,
+    pub updated: DateRange,
+    pub item_type: UGCType,
+    pub game_version: Option<GameVersion>,
+    pub max_pages: Option<u32>,
+}
+
+impl Default for SearchQuery {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            sort_by: UGCQueryType::RankedByTextSearch,
+            period: None,
+            page: 1,
+            tags: TagFilter::default(),
+            created: DateRange::default(),
+            updated: DateRange::default(),
+            item_type: UGCType::Items,
+            game_version: None,
+            max_pages: Some(1),
+        }
+    }
+}
+
+/// Options for [`Workshop::user_items`]. `list` is one of `published`, `favorited`,
+/// `voted-on`, `voted-up`, `voted-down`, `followed` or `subscribed`. `order` defaults
+/// to `recently-subscribed` for `subscribed` and `newest` otherwise. `user` is a
+/// SteamID64, SteamID or account ID, the logged-in user when `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserItemsQuery {
+    pub list: String,
+    pub order: Option<String>,
+    pub page: u32,
+    pub user: Option<String>,
+}
+
+impl Default for UserItemsQuery {
+    fn default() -> Self {
+        Self {
+            list: "published".to_string(),
+            order: None,
+            page: 1,
+            user: None,
+        }
+    }
+}
+
+/// The workshop of one Steam app.
+///
+/// The Steam SDK binds a process to a single app, so creating a `Workshop` for another
+/// app shuts down the client of the previous one.
+#[derive(Debug, Clone, Copy)]
+pub struct Workshop {
+    app_id: u32,
+}
+
+impl Workshop {
+    /// Connects to the running Steam client for `app_id`.
+    pub fn new(app_id: u32) -> Result<Self, S7Error> {
+        steam_manager::backend(app_id)?;
+        Ok(Self { app_id })
+    }
+
+    pub fn app_id(&self) -> u32 {
+        self.app_id
+    }
+
+    pub async fn search(&self, query: SearchQuery) -> Result<Vec<EnhancedWorkshopItem>, S7Error> {
+        self.search_page(query).await.map(|page| page.items)
+    }
+
+    /// Like [`Workshop::search`], with the total result count and whether more pages
+    /// follow.
+    pub async fn search_page(&self, query: SearchQuery) -> Result<SearchPage, S7Error> {
+        commands::search_workshop::search_workshop(
+            self.app_id,
+            query.text,
+            query.sort_by,
+            query.period,
+            query.page,
+            SearchFilter {
+                ugc_type: query.item_type,
+                tags: query.tags,
+                created: query.created,
+                updated: query.updated,
+                game_version: query.game_version,
+            },
+            query.max_pages,
+        )
+        .await
+    }
+
+    pub async fn user_items(
+        &self,
+        query: UserItemsQuery,
+    ) -> Result<Vec<EnhancedWorkshopItem>, S7Error> {
+        commands::user_items::user_items(
+            self.app_id,
+            query.list,
+            query.order,
+            query.page,
+            query.user,
+        )
+        .await
+    }
+
+    /// Looks up items of any type by ID. Unknown IDs are left out; use
+    /// [`Workshop::items_of_type`] to have them listed.
+    pub async fn items(&self, item_ids: Vec<u64>) -> Result<Vec<EnhancedWorkshopItem>, S7Error> {
+        commands::workshop_items::workshop_items_of_type(self.app_id, item_ids, UGCType::All)
+            .await
+            .map(|result| result.items)
+    }
+
+    /// Like [`Workshop::items`], with the logged-in user's vote and favorite on each item.
+    pub async fn items_with_user_state(
+        &self,
+        item_ids: Vec<u64>,
+    ) -> Result<Vec<ItemWithUserState>, S7Error> {
+        commands::workshop_items::workshop_items_with_user_state(
+            self.app_id,
+            item_ids,
+            UGCType::All,
+        )
+        .await
+        .map(|result| result.items)
+    }
+
+    /// Looks up items of `item_type`. Items of other types are listed in `filtered` and
+    /// unknown IDs in `not_found`.
+    pub async fn items_of_type(
+        &self,
+        item_ids: Vec<u64>,
+        item_type: UGCType,
+    ) -> Result<ItemsOfType<EnhancedWorkshopItem>, S7Error> {
+        commands::workshop_items::workshop_items_of_type(self.app_id, item_ids, item_type).await
+    }
+
+    /// Like [`Workshop::items_of_type`], with the logged-in user's vote and favorite on
+    /// each item.
+    pub async fn items_of_type_with_user_state(
+        &self,
+        item_ids: Vec<u64>,
+        item_type: UGCType,
+    ) -> Result<ItemsOfType<ItemWithUserState>, S7Error> {
+        commands::workshop_items::workshop_items_with_user_state(self.app_id, item_ids, item_type)
+            .await
+    }
+
+    pub async fn collection(&self, collection_id: u64) -> Result<CollectionDetails, S7Error> {
+        commands::collection_items::collection_items(self.app_id, collection_id).await
+    }
+
+    pub async fn subscribed_items(&self) -> Result<Vec<EnhancedWorkshopItem>, S7Error> {
+        commands::subscribed_items::subscribed_items(self.app_id, None).await
+    }
+
+    /// The subscribed items with a version tag covering `game_version`, such as `1.2`.
+    pub async fn subscribed_items_for_version(
+        &self,
+        game_version: &GameVersion,
+    ) -> Result<Vec<EnhancedWorkshopItem>, S7Error> {
+        commands::subscribed_items::subscribed_items(self.app_id, Some(game_version)).await
+    }
+
+    /// The subscribed items that do not declare compatibility with `game_version`.
+    pub async fn compat_report(&self, game_version: &GameVersion) -> Result<CompatReport, S7Error> {
+        commands::compat_report::compat_report(self.app_id, game_version.clone()).await
+    }
+
+    pub async fn subscribe(&self, item_ids: Vec<u64>) -> Result<Vec<SubscribeResult>, S7Error> {
+        commands::subscribe::subscribe(self.app_id, item_ids).await
+    }
+
+    pub async fn unsubscribe(&self, item_ids: Vec<u64>) -> Result<Vec<UnsubscribeResult>, S7Error> {
+        commands::unsubscribe::unsubscribe(self.app_id, item_ids).await
+    }
+
+    /// Votes an item up or down, replacing the logged-in user's earlier vote.
+    pub async fn vote(&self, item_id: u64, vote: Vote) -> Result<ItemVote, S7Error> {
+        commands::vote::vote(self.app_id, item_id, vote).await
+    }
+
+    pub async fn get_vote(&self, item_id: u64) -> Result<ItemVote, S7Error> {
+        commands::vote::get_vote(self.app_id, item_id).await
+    }
+
+    pub async fn add_favorite(&self, item_id: u64) -> Result<FavoriteResult, S7Error> {
+        commands::favorite::add_favorite(self.app_id, item_id).await
+    }
+
+    pub async fn remove_favorite(&self, item_id: u64) -> Result<FavoriteResult, S7Error> {
+        commands::favorite::remove_favorite(self.app_id, item_id).await
+    }
+
+    /// Downloads a subscribed item and waits until Steam has installed it.
+    pub async fn download(&self, item_id: u64) -> Result<(), S7Error> {
+        commands::download_workshop_item::download_workshop_item(self.app_id, item_id).await
+    }
+
+    /// Like [`Workshop::download`], calling `on_progress(downloaded_bytes, total_bytes)`
+    /// while the download runs.
+    pub async fn download_with_progress<F>(
+        &self,
+        item_id: u64,
+        on_progress: F,
+    ) -> Result<(), S7Error>
+    where
+        F: Fn(u64, u64) + Send + 'static,
+    {
+        commands::download_workshop_item::download_workshop_item_with_progress(
+            self.app_id,
+            item_id,
+            on_progress,
+        )
+        .await
+    }
+
+    pub async fn download_status(&self, item_id: u64) -> Result<DownloadInfo, S7Error> {
+        commands::check_item_download::check_item_download(self.app_id, item_id).await
+    }
+
+    /// Where an installed item lives on disk, or `None` if it is not installed.
+    pub fn install_info(&self, item_id: u64) -> Result<Option<ItemInstallInfo>, S7Error> {
+        Ok(steam_manager::backend(self.app_id)?.item_install_info(item_id))
+    }
+
+    /// Creates a new item owned by the current user and uploads its content and preview.
+    pub async fn publish(&self, options: PublishOptions) -> Result<SubmittedItem, S7Error> {
+        commands::publish::publish(self.app_id, options).await
+    }
+
+    /// Publishes the item `manifest` describes: creates it and writes the new ID back to
+    /// the manifest on the first run, updates it on later ones.
+    #[cfg(feature = "manifest")]
+    pub async fn publish_manifest(
+        &self,
+        manifest: &mut WorkshopManifest,
+        release: ManifestRelease,
+    ) -> Result<ManifestPublishResult, S7Error> {
+        if manifest.app_id != self.app_id {
+            return Err(S7Error::InvalidArgument(format!(
+                "Manifest {} is for app {}, not {}",
+                manifest.path.display(),
+                manifest.app_id,
+                self.app_id
+            )));
+        }
+        commands::publish_manifest::publish_manifest(manifest, release).await
+    }
+
+    /// Changes the fields set in `update` on an existing item.
+    pub async fn update_item(
+        &self,
+        item_id: u64,
+        update: ItemUpdate,
+    ) -> Result<SubmittedItem, S7Error> {
+        self.update_item_with_progress(item_id, update, |_| {})
+            .await
+    }
+
+    /// Like [`Workshop::update_item`], calling `on_progress` whenever the upload
+    /// status or byte count changes.
+    pub async fn update_item_with_progress<F>(
+        &self,
+        item_id: u64,
+        update: ItemUpdate,
+        on_progress: F,
+    ) -> Result<SubmittedItem, S7Error>
+    where
+        F: Fn(UpdateProgress) + Send + 'static,
+    {
+        commands::update_item::update_item(self.app_id, item_id, update, on_progress).await
+    }
+
+    /// All tags used by the app's workshop items.
+    pub async fn tags(&self) -> Result<Vec<String>, S7Error> {
+        commands::discover_tags::discover_tags(self.app_id).await
+    }
+
+    /// Deletes an item the logged-in user owns. This cannot be undone.
+    pub async fn delete_item(&self, item_id: u64) -> Result<DeleteItemResult, S7Error> {
+        commands::delete_item::delete_item(self.app_id, item_id).await
+    }
+
+    /// Changes who can see an item the logged-in user owns.
+    pub async fn set_visibility(
+        &self,
+        item_id: u64,
+        visibility: UgcItemVisibility,
+    ) -> Result<SetVisibilityResult, S7Error> {
+        commands::set_visibility::set_visibility(self.app_id, item_id, visibility).await
+    }
+
+    /// Makes `dependency_ids` required items and `app_dependency_ids` required apps of
+    /// `item_id`, an item the logged-in user owns. Dependencies that lead back to
+    /// `item_id` are added with a warning.
+    pub async fn add_dependency(
+        &self,
+        item_id: u64,
+        dependency_ids: Vec<u64>,
+        app_dependency_ids: Vec<u32>,
+    ) -> Result<AddDependencyResult, S7Error> {
+        commands::add_dependency::add_dependency(
+            self.app_id,
+            item_id,
+            dependency_ids,
+            app_dependency_ids,
+        )
+        .await
+    }
+
+    pub async fn remove_dependency(
+        &self,
+        item_id: u64,
+        dependency_ids: Vec<u64>,
+        app_dependency_ids: Vec<u32>,
+    ) -> Result<RemoveDependencyResult, S7Error> {
+        commands::remove_dependency::remove_dependency(
+            self.app_id,
+            item_id,
+            dependency_ids,
+            app_dependency_ids,
+        )
+        .await
+    }
+
+    /// Every item `item_ids` require, directly or through other items, with a load
+    /// order, the cycles among them and the dependencies that are missing or banned.
+    pub async fn resolve_dependencies(
+        &self,
+        item_ids: Vec<u64>,
+        include_dot: bool,
+    ) -> Result<DependencyGraph, S7Error> {
+        commands::resolve_dependencies::resolve_dependencies(self.app_id, item_ids, include_dot)
+            .await
+    }
+
+    /// Creates a collection owned by the logged-in user holding `options.item_ids`.
+    pub async fn collection_create(
+        &self,
+        options: CollectionOptions,
+    ) -> Result<CollectionCreateResult, S7Error> {
+        commands::collection_create::collection_create(self.app_id, options).await
+    }
+
+    /// Creates a collection of the items the user is currently subscribed to.
+    /// `options.item_ids` is ignored.
+    pub async fn collection_from_subscriptions(
+        &self,
+        options: CollectionOptions,
+    ) -> Result<CollectionCreateResult, S7Error> {
+        commands::collection_create::collection_from_subscriptions(self.app_id, options).await
+    }
+
+    /// Adds `item_ids` to a collection the logged-in user owns, at the 1-based
+    /// `position` or at the end. Items already in it are moved there.
+    pub async fn collection_add(
+        &self,
+        collection_id: u64,
+        item_ids: Vec<u64>,
+        position: Option<usize>,
+    ) -> Result<CollectionEditResult, S7Error> {
+        commands::collection_edit::collection_add(self.app_id, collection_id, item_ids, position)
+            .await
+    }
+
+    pub async fn collection_remove(
+        &self,
+        collection_id: u64,
+        item_ids: Vec<u64>,
+    ) -> Result<CollectionEditResult, S7Error> {
+        commands::collection_edit::collection_remove(self.app_id, collection_id, item_ids).await
+    }
+}
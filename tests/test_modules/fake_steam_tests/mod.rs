@@ -0,0 +1,18 @@
+pub mod test_fake_collection_items;
+pub mod test_fake_collections;
+pub mod test_fake_dependencies;
+pub mod test_fake_downloads_and_subscriptions;
+pub mod test_fake_errors;
+pub mod test_fake_game_versions;
+pub mod test_fake_http_serve;
+pub mod test_fake_item_management;
+pub mod test_fake_publish;
+pub mod test_fake_publish_manifest;
+pub mod test_fake_resolve_dependencies;
+pub mod test_fake_search_workshop;
+pub mod test_fake_serve;
+pub mod test_fake_tag_rules;
+pub mod test_fake_update_item;
+pub mod test_fake_user_items;
+pub mod test_fake_votes_and_favorites;
+pub mod test_fake_workshop_items;
@@ -0,0 +1,49 @@
+use crate::test_modules::utils::{FAKE_APP_ID, assert_valid_json, run_fake_steam_command};
+
+#[test]
+fn test_fake_collection_items() {
+    let output = run_fake_steam_command(&[
+        "collection-items",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000010",
+    ]);
+    assert!(
+        output.status.success(),
+        "collection-items failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let value = assert_valid_json(&stdout);
+    assert_eq!(value["details"]["id"].as_u64(), Some(3000000010));
+    assert_eq!(value["details"]["title"].as_str(), Some("Armour Modpack"));
+
+    let ids: Vec<u64> = value["items"]
+        .as_array()
+        .expect("Expected 'items' array")
+        .iter()
+        .map(|item| item["published_file_id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(ids, vec![3000000001, 3000000003]);
+    assert_eq!(
+        value["items"][0]["key_value_tags"][0],
+        serde_json::json!(["game_version", "1.2"])
+    );
+}
+
+#[test]
+fn test_fake_collection_items_rejects_non_collection() {
+    let output = run_fake_steam_command(&[
+        "collection-items",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000001",
+    ]);
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Item is not a collection"));
+}
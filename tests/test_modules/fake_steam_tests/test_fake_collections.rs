@@ -0,0 +1,199 @@
+use std::io::Write;
+
+use crate::test_modules::utils::{
+    FAKE_APP_ID, assert_valid_json, run_fake_steam_command, run_fake_steam_serve_with,
+    run_fake_steam_serve_with_catalog,
+};
+
+#[test]
+fn test_fake_collection_create_keeps_order_without_duplicates() {
+    let output = run_fake_steam_command(&[
+        "collection-create",
+        "--app-id",
+        FAKE_APP_ID,
+        "--title",
+        "Tanks",
+        "--item-ids",
+        "3000000003,3000000001,3000000003",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(value["collection_id"].as_u64(), Some(3000000011));
+    assert_eq!(
+        value["items"],
+        serde_json::json!([3000000003u64, 3000000001u64])
+    );
+}
+
+#[test]
+fn test_fake_collection_from_subscriptions() {
+    let output = run_fake_steam_command(&[
+        "collection-from-subscriptions",
+        "--app-id",
+        FAKE_APP_ID,
+        "--title",
+        "My mods",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(value["items"], serde_json::json!([3000000001u64]));
+}
+
+#[test]
+fn test_fake_collection_create_requires_existing_items() {
+    let output = run_fake_steam_command(&[
+        "collection-create",
+        "--app-id",
+        FAKE_APP_ID,
+        "--title",
+        "Tanks",
+        "--item-ids",
+        "3000000001,42",
+    ]);
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[test]
+fn test_fake_collection_edit_errors() {
+    let cases: [(&str, &str, i32); 3] = [
+        // Owned by Bob.
+        ("collection-add", "3000000010", 9),
+        ("collection-add", "3000000001", 2),
+        ("collection-remove", "42", 4),
+    ];
+
+    for (command, collection_id, exit_code) in cases {
+        let output = run_fake_steam_command(&[
+            command,
+            "--app-id",
+            FAKE_APP_ID,
+            "--collection-id",
+            collection_id,
+            "--item-ids",
+            "3000000002",
+        ]);
+        assert_eq!(
+            output.status.code(),
+            Some(exit_code),
+            "{} {}",
+            command,
+            collection_id
+        );
+    }
+}
+
+#[test]
+fn test_fake_collection_reorder_and_remove_in_serve() {
+    // Requests run concurrently, so each one waits for the previous change to finish.
+    let output = run_fake_steam_serve_with(&[], |stdin| {
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":1,"method":"collection-create","params":{{"app_id":4000000,"title":"Tanks","item_ids":[3000000001,3000000002,3000000003]}}}}"#).unwrap();
+        stdin.wait_for_response(1);
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":2,"method":"collection-add","params":{{"app_id":4000000,"collection_id":3000000011,"item_ids":[3000000003],"position":1}}}}"#).unwrap();
+        stdin.wait_for_response(2);
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":3,"method":"collection-remove","params":{{"app_id":4000000,"collection_id":3000000011,"item_ids":[3000000002,42]}}}}"#).unwrap();
+        stdin.wait_for_response(3);
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":4,"method":"collection-items","params":{{"app_id":4000000,"item_id":3000000011}}}}"#).unwrap();
+    });
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let messages: Vec<_> = stdout.lines().map(assert_valid_json).collect();
+    let result = |id: u64| {
+        messages
+            .iter()
+            .find(|message| message["id"].as_u64() == Some(id))
+            .map(|message| message["result"].clone())
+            .unwrap_or_else(|| panic!("No response for request {}", id))
+    };
+
+    assert_eq!(
+        result(2)["items"],
+        serde_json::json!([3000000003u64, 3000000001u64, 3000000002u64])
+    );
+    assert_eq!(
+        result(3)["items"],
+        serde_json::json!([3000000003u64, 3000000001u64])
+    );
+    assert_eq!(
+        result(3)["warnings"][0].as_str(),
+        Some("42 is not in collection 3000000011")
+    );
+    let listed: Vec<_> = result(4)["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["published_file_id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(listed, vec![3000000003, 3000000001]);
+}
+
+#[test]
+fn test_fake_collection_edit_failing_midway() {
+    // Steam refuses to take 3400000003 out of the collection.
+    let items: Vec<_> = (3400000001u64..=3400000004)
+        .map(|id| serde_json::json!({ "published_file_id": id }))
+        .chain([serde_json::json!({
+            "published_file_id": 3400000010u64,
+            "file_type": "Collection",
+            "owner": 76561190000000001u64,
+            "children": [3400000001u64, 3400000002u64, 3400000003u64, 3400000004u64],
+            "failing_dependencies": [3400000003u64],
+        })])
+        .collect();
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("collections");
+    std::fs::create_dir_all(&dir).unwrap();
+    let catalog = dir.join("failing_catalog.json");
+    std::fs::write(
+        &catalog,
+        serde_json::json!({ "items": items, "local_user": 76561190000000001u64 }).to_string(),
+    )
+    .unwrap();
+
+    let output = run_fake_steam_serve_with_catalog(catalog.to_str().unwrap(), &[], |stdin| {
+        // Only the removed item is taken out, so the refused one is never touched.
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":1,"method":"collection-remove","params":{{"app_id":4000000,"collection_id":3400000010,"item_ids":[3400000002]}}}}"#).unwrap();
+        stdin.wait_for_response(1);
+        // Moving 3400000004 first takes out 3400000001, then fails on 3400000003.
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":2,"method":"collection-add","params":{{"app_id":4000000,"collection_id":3400000010,"item_ids":[3400000004],"position":1}}}}"#).unwrap();
+        stdin.wait_for_response(2);
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":3,"method":"collection-items","params":{{"app_id":4000000,"item_id":3400000010}}}}"#).unwrap();
+    });
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let messages: Vec<_> = stdout.lines().map(assert_valid_json).collect();
+    let response = |id: u64| {
+        messages
+            .iter()
+            .find(|message| message["id"].as_u64() == Some(id))
+            .unwrap_or_else(|| panic!("No response for request {}", id))
+    };
+
+    assert_eq!(
+        response(1)["result"]["items"],
+        serde_json::json!([3400000001u64, 3400000003u64, 3400000004u64])
+    );
+    assert_eq!(
+        response(2)["error"]["message"].as_str(),
+        Some(
+            "Failed to update collection 3400000010: removing item 3400000003 failed, after removing 3400000001; added 3400000001 back at the end: Steam API error: IOFailure"
+        )
+    );
+    let listed: Vec<_> = response(3)["result"]["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["published_file_id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(listed, vec![3400000003, 3400000004, 3400000001]);
+}
@@ -0,0 +1,256 @@
+use std::io::Write;
+
+use crate::test_modules::utils::{
+    FAKE_APP_ID, assert_valid_json, run_fake_steam_command, run_fake_steam_command_with,
+    run_fake_steam_serve_with,
+};
+
+// Writes a catalog with a local user's item 3300000001 and someone else's 3300000002.
+fn dependency_catalog(name: &str, other_item: serde_json::Value) -> String {
+    let catalog = serde_json::json!({
+        "items": [
+            {
+                "published_file_id": 3300000001u64,
+                "title": "Desert Tanks",
+                "owner": 76561190000000001u64,
+                "failing_app_dependencies": [1331581],
+            },
+            other_item,
+        ],
+        "local_user": 76561190000000001u64,
+    });
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("dependencies");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, catalog.to_string()).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[test]
+fn test_fake_add_dependency_warns_about_cycles() {
+    // Tank Maps Pack already requires Better Tanks.
+    let output = run_fake_steam_command(&[
+        "add-dependency",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000001",
+        "--dependency-ids",
+        "3000000002",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(value["added"], serde_json::json!([3000000002u64]));
+    assert_eq!(value["required_items"], serde_json::json!([3000000002u64]));
+    assert_eq!(
+        value["warnings"][0].as_str(),
+        Some(
+            "Adding 3000000002 creates a dependency cycle: 3000000001 -> 3000000002 -> 3000000001"
+        )
+    );
+}
+
+#[test]
+fn test_fake_add_dependency_validates_targets() {
+    let cases: [(&str, &str, i32); 3] = [
+        ("3000000003", "3000000001,42", 4),
+        ("3000000003", "3000000003", 2),
+        ("3000000002", "3000000003", 9),
+    ];
+
+    for (item_id, dependency_ids, exit_code) in cases {
+        let output = run_fake_steam_command(&[
+            "add-dependency",
+            "--app-id",
+            FAKE_APP_ID,
+            "--item-id",
+            item_id,
+            "--dependency-ids",
+            dependency_ids,
+        ]);
+        assert_eq!(
+            output.status.code(),
+            Some(exit_code),
+            "{} {}",
+            item_id,
+            dependency_ids
+        );
+    }
+}
+
+#[test]
+fn test_fake_app_dependencies() {
+    // Weapon Overhaul already requires app 1331580.
+    let output = run_fake_steam_command(&[
+        "add-dependency",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000003",
+        "--app-dependency-ids",
+        "1331580,1331581",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(value["added"], serde_json::json!([]));
+    assert_eq!(value["added_apps"], serde_json::json!([1331581]));
+    assert_eq!(
+        value["required_apps"],
+        serde_json::json!([1331580, 1331581])
+    );
+
+    let output = run_fake_steam_command(&[
+        "remove-dependency",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000003",
+        "--app-dependency-ids",
+        "1331580,42",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(value["removed_apps"], serde_json::json!([1331580]));
+    assert_eq!(value["required_apps"], serde_json::json!([]));
+    assert_eq!(
+        value["warnings"][0].as_str(),
+        Some("App 42 is not a dependency of 3000000003")
+    );
+}
+
+#[test]
+fn test_fake_add_and_remove_dependency_in_serve() {
+    // Requests run concurrently, so the removal waits for the addition to finish.
+    let output = run_fake_steam_serve_with(&[], |stdin| {
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":1,"method":"add-dependency","params":{{"app_id":4000000,"item_id":3000000003,"dependency_ids":[3000000001]}}}}"#).unwrap();
+        stdin.wait_for_response(1);
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":2,"method":"remove-dependency","params":{{"app_id":4000000,"item_id":3000000003,"dependency_ids":[3000000001,3000000002]}}}}"#).unwrap();
+    });
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let messages: Vec<_> = stdout.lines().map(assert_valid_json).collect();
+    let result = |id: u64| {
+        messages
+            .iter()
+            .find(|message| message["id"].as_u64() == Some(id))
+            .map(|message| message["result"].clone())
+            .unwrap_or_else(|| panic!("No response for request {}", id))
+    };
+
+    assert_eq!(
+        result(1)["required_items"],
+        serde_json::json!([3000000001u64])
+    );
+    assert_eq!(result(1)["warnings"], serde_json::json!([]));
+    assert_eq!(result(2)["removed"], serde_json::json!([3000000001u64]));
+    assert_eq!(result(2)["required_items"], serde_json::json!([]));
+    assert_eq!(
+        result(2)["warnings"][0].as_str(),
+        Some("3000000002 is not a dependency of 3000000003")
+    );
+}
+
+#[test]
+fn test_fake_add_dependency_checks_cycles_against_steam() {
+    // Desert Maps is cached before it starts requiring Desert Tanks.
+    let before = dependency_catalog(
+        "cycle_before.json",
+        serde_json::json!({ "published_file_id": 3300000002u64, "title": "Desert Maps" }),
+    );
+    let after = dependency_catalog(
+        "cycle_after.json",
+        serde_json::json!({
+            "published_file_id": 3300000002u64,
+            "title": "Desert Maps",
+            "children": [3300000001u64],
+        }),
+    );
+    let output = run_fake_steam_command_with(
+        &before,
+        &[
+            "workshop-items",
+            "--app-id",
+            FAKE_APP_ID,
+            "--item-ids",
+            "3300000002",
+        ],
+        &[],
+    );
+    assert!(output.status.success());
+
+    let output = run_fake_steam_command_with(
+        &after,
+        &[
+            "add-dependency",
+            "--app-id",
+            FAKE_APP_ID,
+            "--item-id",
+            "3300000001",
+            "--dependency-ids",
+            "3300000002",
+        ],
+        &[],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(
+        value["warnings"][0].as_str(),
+        Some(
+            "Adding 3300000002 creates a dependency cycle: 3300000001 -> 3300000002 -> 3300000001"
+        )
+    );
+}
+
+#[test]
+fn test_fake_add_dependency_reports_changes_before_an_app_failure() {
+    let catalog = dependency_catalog(
+        "app_failure.json",
+        serde_json::json!({ "published_file_id": 3300000002u64, "title": "Desert Maps" }),
+    );
+    let output = run_fake_steam_command_with(
+        &catalog,
+        &[
+            "add-dependency",
+            "--app-id",
+            FAKE_APP_ID,
+            "--item-id",
+            "3300000001",
+            "--dependency-ids",
+            "3300000002",
+            "--app-dependency-ids",
+            "1331580,1331581",
+        ],
+        &[],
+    );
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "Failed to add app 1331581 as a dependency of 3300000001, after adding 3300000002, after adding app 1331580"
+        ),
+        "{}",
+        stderr
+    );
+}
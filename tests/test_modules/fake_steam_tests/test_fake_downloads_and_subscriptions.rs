@@ -0,0 +1,75 @@
+use crate::test_modules::utils::{FAKE_APP_ID, assert_valid_json, run_fake_steam_command};
+
+#[test]
+fn test_fake_subscribed_items() {
+    let output = run_fake_steam_command(&["subscribed-items", "--app-id", FAKE_APP_ID]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let value = assert_valid_json(&stdout);
+    let items = value.as_array().expect("Expected JSON array");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["published_file_id"].as_u64(), Some(3000000001));
+}
+
+#[test]
+fn test_fake_check_item_download() {
+    let output = run_fake_steam_command(&[
+        "check-item-download",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000001",
+    ]);
+    assert!(output.status.success());
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(value["download_complete"].as_bool(), Some(true));
+    assert_eq!(value["total_bytes"].as_u64(), Some(2048));
+
+    let output = run_fake_steam_command(&[
+        "check-item-download",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000003",
+    ]);
+    assert!(output.status.success());
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(value["download_complete"].as_bool(), Some(false));
+}
+
+#[test]
+fn test_fake_subscribe_and_unsubscribe() {
+    let output = run_fake_steam_command(&[
+        "subscribe",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-ids",
+        "3000000002,3000000003",
+    ]);
+    assert!(output.status.success());
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    let results = value.as_array().expect("Expected JSON array");
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|r| r["success"].as_bool() == Some(true)));
+
+    let output = run_fake_steam_command(&[
+        "unsubscribe",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-ids",
+        "3000000001",
+    ]);
+    assert!(output.status.success());
+
+    let output = run_fake_steam_command(&[
+        "subscribe",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-ids",
+        "3000000999",
+    ]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to subscribe to item 3000000999"));
+}
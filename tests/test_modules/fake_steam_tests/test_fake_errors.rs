@@ -0,0 +1,64 @@
+use crate::test_modules::utils::{FAKE_APP_ID, assert_valid_json, run_fake_steam_command};
+
+#[test]
+fn test_fake_error_exit_codes() {
+    let output = run_fake_steam_command(&[
+        "download-workshop-item",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000003",
+    ]);
+    assert_eq!(output.status.code(), Some(5));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Error: Workshop item is not subscribed"));
+
+    let output =
+        run_fake_steam_command(&["search-workshop", "--app-id", FAKE_APP_ID, "--page", "0"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = run_fake_steam_command(&[
+        "collection-items",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000001",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = run_fake_steam_command(&["workshop-items", "--item-ids", "1"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_fake_json_errors() {
+    let output = run_fake_steam_command(&[
+        "download-workshop-item",
+        "--json-errors",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000003",
+    ]);
+    assert_eq!(output.status.code(), Some(5));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let line = stderr.lines().last().expect("Expected an error line");
+    let value = assert_valid_json(line);
+    assert_eq!(value["error"]["code"].as_str(), Some("not_subscribed"));
+    assert_eq!(
+        value["error"]["message"].as_str(),
+        Some("Workshop item is not subscribed")
+    );
+    assert_eq!(
+        value["error"]["details"]["item_id"].as_u64(),
+        Some(3000000003)
+    );
+
+    let output = run_fake_steam_command(&["--json-errors", "no-such-command"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let value = assert_valid_json(stderr.lines().last().unwrap());
+    assert_eq!(value["error"]["code"].as_str(), Some("invalid_argument"));
+    assert!(value["error"]["details"].is_null());
+}
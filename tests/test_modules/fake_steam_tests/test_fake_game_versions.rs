@@ -0,0 +1,146 @@
+use std::io::Write;
+
+use crate::test_modules::utils::{
+    FAKE_APP_ID, assert_valid_json, run_fake_steam_command, run_fake_steam_serve_with,
+};
+
+fn item_ids(value: &serde_json::Value) -> Vec<u64> {
+    value
+        .as_array()
+        .expect("Expected JSON array")
+        .iter()
+        .filter_map(|item| item["published_file_id"].as_u64())
+        .collect()
+}
+
+#[test]
+fn test_fake_compatible_versions() {
+    let output = run_fake_steam_command(&[
+        "workshop-items",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-ids",
+        "3000000001,3000000002,3000000003",
+    ]);
+    assert!(output.status.success());
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    let items = value.as_array().expect("Expected JSON array");
+    let versions = |id: u64| {
+        items
+            .iter()
+            .find(|item| item["published_file_id"].as_u64() == Some(id))
+            .map(|item| item["compatible_versions"].clone())
+            .expect("Item missing")
+    };
+    assert_eq!(versions(3000000001), serde_json::json!(["1.2.x"]));
+    assert_eq!(versions(3000000002), serde_json::json!(["1.1", "1.3"]));
+    assert_eq!(versions(3000000003), serde_json::json!([]));
+}
+
+#[test]
+fn test_fake_search_workshop_game_version() {
+    let search_page = |game_version: &str| {
+        let output = run_fake_steam_command(&[
+            "search-workshop",
+            "--app-id",
+            FAKE_APP_ID,
+            "--sort-by",
+            "recent",
+            "--game-version",
+            game_version,
+            "--page-info",
+        ]);
+        assert!(output.status.success());
+        assert_valid_json(&String::from_utf8_lossy(&output.stdout))
+    };
+    let search = |game_version: &str| item_ids(&search_page(game_version)["items"]);
+
+    assert_eq!(search("1.2.7"), [3000000001]);
+    assert_eq!(search("v1.3.0"), [3000000002]);
+    // A plain v1.3 tag is not a prefix: it covers 1.3 and 1.3.0, but not 1.3.1.
+    assert!(search("1.3.1").is_empty());
+    assert!(search("2.0").is_empty());
+
+    // Items left out for their version are listed rather than dropped.
+    let page = search_page("1.2.7");
+    assert_eq!(page["matching_results"].as_u64(), Some(1));
+    let filtered = page["filtered"]
+        .as_array()
+        .expect("Expected filtered items");
+    assert_eq!(filtered.len(), 2);
+    assert!(filtered.iter().all(|item| item["reason"] == "game-version"));
+}
+
+#[test]
+fn test_fake_subscribed_items_game_version() {
+    let subscribed = |game_version: &str| {
+        let output = run_fake_steam_command(&[
+            "subscribed-items",
+            "--app-id",
+            FAKE_APP_ID,
+            "--game-version",
+            game_version,
+        ]);
+        assert!(output.status.success());
+        item_ids(&assert_valid_json(&String::from_utf8_lossy(&output.stdout)))
+    };
+
+    assert_eq!(subscribed("1.2"), [3000000001]);
+    assert!(subscribed("1.3").is_empty());
+}
+
+#[test]
+fn test_fake_compat_report() {
+    let output = run_fake_steam_serve_with(&[], |stdin| {
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":1,"method":"subscribe","params":{{"app_id":4000000,"item_ids":[3000000002,3000000003]}}}}"#).unwrap();
+        stdin.wait_for_response(1);
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":2,"method":"compat-report","params":{{"app_id":4000000,"game_version":"1.3"}}}}"#).unwrap();
+    });
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let messages: Vec<_> = stdout.lines().map(assert_valid_json).collect();
+    let report = messages
+        .iter()
+        .find(|message| message["id"].as_u64() == Some(2))
+        .map(|message| &message["result"])
+        .expect("No compat-report response");
+
+    assert_eq!(report["game_version"].as_str(), Some("1.3"));
+    assert_eq!(report["checked"].as_u64(), Some(3));
+    let mut incompatible: Vec<(u64, serde_json::Value)> = report["incompatible"]
+        .as_array()
+        .expect("Expected incompatible items")
+        .iter()
+        .map(|item| {
+            (
+                item["published_file_id"].as_u64().unwrap(),
+                item["compatible_versions"].clone(),
+            )
+        })
+        .collect();
+    incompatible.sort_by_key(|(id, _)| *id);
+    assert_eq!(
+        incompatible,
+        [
+            (3000000001, serde_json::json!(["1.2.x"])),
+            (3000000003, serde_json::json!([])),
+        ]
+    );
+}
+
+#[test]
+fn test_fake_compat_report_rejects_wildcard_version() {
+    let output = run_fake_steam_command(&[
+        "compat-report",
+        "--app-id",
+        FAKE_APP_ID,
+        "--game-version",
+        "1.x",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid game version '1.x'"), "{}", stderr);
+}
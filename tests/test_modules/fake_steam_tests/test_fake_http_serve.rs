@@ -0,0 +1,319 @@
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+use crate::test_modules::utils::{
+    assert_valid_json, spawn_fake_http_server, spawn_fake_http_server_with,
+};
+
+fn request(address: &str, method: &str, path: &str, body: Option<&str>) -> (u16, Value) {
+    let headers = [
+        format!("Host: {}", address),
+        "Content-Type: application/json".to_string(),
+    ];
+    let (status, _, body) = send(address, method, path, &headers, body);
+    (status, assert_valid_json(&body))
+}
+
+/// Sends a request with exactly the given headers and returns the status, the response
+/// head and the body.
+fn send(
+    address: &str,
+    method: &str,
+    path: &str,
+    headers: &[String],
+    body: Option<&str>,
+) -> (u16, String, String) {
+    let mut stream = TcpStream::connect(address).expect("Failed to connect to server");
+    let body = body.unwrap_or_default();
+    let headers: String = headers
+        .iter()
+        .map(|header| format!("{}\r\n", header))
+        .collect();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\n{}Content-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        headers,
+        body.len(),
+        body
+    )
+    .expect("Failed to send request");
+
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .expect("Failed to read response");
+
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .expect("Malformed HTTP response");
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .expect("Missing status code");
+
+    (status, head.to_string(), body.to_string())
+}
+
+#[test]
+fn test_fake_http_serve_items_and_search() {
+    let server = spawn_fake_http_server();
+
+    let (status, item) = request(
+        &server.address,
+        "GET",
+        "/apps/4000000/items/3000000001",
+        None,
+    );
+    assert_eq!(status, 200);
+    assert_eq!(item["title"].as_str(), Some("Better Tanks"));
+    assert_eq!(item["creator_name"].as_str(), Some("Alice"));
+
+    let (status, items) = request(
+        &server.address,
+        "GET",
+        "/apps/4000000/items?ids=3000000001,3000000002",
+        None,
+    );
+    assert_eq!(status, 200);
+    assert_eq!(items.as_array().map(Vec::len), Some(2));
+
+    let (status, collection) = request(
+        &server.address,
+        "GET",
+        "/apps/4000000/items/3000000010?type=all",
+        None,
+    );
+    assert_eq!(status, 200);
+    assert_eq!(collection["file_type"].as_str(), Some("Collection"));
+
+    let (status, body) = request(
+        &server.address,
+        "GET",
+        "/apps/4000000/items/3000000010?type=items",
+        None,
+    );
+    assert_eq!(status, 404);
+    assert!(
+        body["error"]["message"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("Collection")
+    );
+
+    let (status, results) = request(&server.address, "GET", "/apps/4000000/search?q=tank", None);
+    assert_eq!(status, 200);
+    assert!(results.is_array());
+
+    let (status, favorites) = request(
+        &server.address,
+        "GET",
+        "/apps/4000000/user-items?list=favorited&order=title",
+        None,
+    );
+    assert_eq!(status, 200);
+    assert_eq!(favorites[0]["title"].as_str(), Some("Tank Maps Pack"));
+
+    let (status, vote) = request(
+        &server.address,
+        "PUT",
+        "/apps/4000000/items/3000000001/vote",
+        Some(r#"{"vote": "down"}"#),
+    );
+    assert_eq!(status, 200);
+    assert_eq!(vote["vote"].as_str(), Some("down"));
+
+    let (status, item) = request(
+        &server.address,
+        "GET",
+        "/apps/4000000/items/3000000001?user_state=true",
+        None,
+    );
+    assert_eq!(status, 200);
+    assert_eq!(item["user_state"]["vote"].as_str(), Some("down"));
+
+    let (status, collection) = request(
+        &server.address,
+        "GET",
+        "/apps/4000000/collections/3000000010",
+        None,
+    );
+    assert_eq!(status, 200);
+    assert!(collection.is_object());
+}
+
+#[test]
+fn test_fake_http_serve_subscriptions() {
+    let server = spawn_fake_http_server();
+
+    let (status, results) = request(
+        &server.address,
+        "POST",
+        "/apps/4000000/subscriptions",
+        Some(r#"{"item_ids":[3000000002]}"#),
+    );
+    assert_eq!(status, 200);
+    assert_eq!(results[0]["success"].as_bool(), Some(true));
+
+    // The server keeps one backend alive, so the subscription is visible to later requests.
+    let (status, subscribed) = request(&server.address, "GET", "/apps/4000000/subscriptions", None);
+    assert_eq!(status, 200);
+    assert_eq!(subscribed.as_array().map(Vec::len), Some(2));
+
+    let (status, _) = request(
+        &server.address,
+        "DELETE",
+        "/apps/4000000/subscriptions/3000000002",
+        None,
+    );
+    assert_eq!(status, 200);
+
+    let (_, subscribed) = request(&server.address, "GET", "/apps/4000000/subscriptions", None);
+    assert_eq!(subscribed.as_array().map(Vec::len), Some(1));
+
+    let (status, body) = request(&server.address, "GET", "/status", None);
+    assert_eq!(status, 200);
+    assert_eq!(body["initialized_apps"], serde_json::json!([4000000]));
+}
+
+#[test]
+fn test_fake_http_serve_errors() {
+    let server = spawn_fake_http_server();
+
+    let (status, body) = request(&server.address, "GET", "/apps/4000000/items/42", None);
+    assert_eq!(status, 404);
+    assert_eq!(body["error"]["code"].as_str(), Some("not_found"));
+
+    let (status, _) = request(&server.address, "GET", "/apps/not-a-number/items/42", None);
+    assert_eq!(status, 400);
+
+    let (status, _) = request(
+        &server.address,
+        "POST",
+        "/apps/4000000/subscriptions",
+        Some("not json"),
+    );
+    assert_eq!(status, 400);
+
+    let (status, _) = request(&server.address, "GET", "/no/such/route", None);
+    assert_eq!(status, 404);
+
+    let (status, body) = request(
+        &server.address,
+        "POST",
+        "/apps/4000000/items/3000000003/download",
+        None,
+    );
+    assert_eq!(status, 409);
+    assert_eq!(body["error"]["code"].as_str(), Some("not_subscribed"));
+    assert_eq!(
+        body["error"]["message"].as_str(),
+        Some("Workshop item is not subscribed")
+    );
+    assert_eq!(
+        body["error"]["details"]["item_id"].as_u64(),
+        Some(3000000003)
+    );
+}
+
+#[test]
+fn test_fake_http_serve_rejects_forged_requests() {
+    let server = spawn_fake_http_server_with(&["--allow-origin", "http://localhost:3000"]);
+    let host = format!("Host: {}", server.address);
+    let json = "Content-Type: application/json".to_string();
+
+    // A rebound DNS name still reaches 127.0.0.1, but names a different host.
+    let (status, _, body) = send(
+        &server.address,
+        "GET",
+        "/apps/4000000/subscriptions",
+        &["Host: attacker.example".to_string()],
+        None,
+    );
+    assert_eq!(status, 403);
+    assert!(body.contains("attacker.example"));
+
+    let (status, _, _) = send(
+        &server.address,
+        "POST",
+        "/apps/4000000/subscriptions",
+        &[
+            host.clone(),
+            "Origin: http://attacker.example".to_string(),
+            json.clone(),
+        ],
+        Some(r#"{"item_ids":[3000000002]}"#),
+    );
+    assert_eq!(status, 403);
+
+    // A plain HTML form can only send form or text bodies.
+    let (status, _, _) = send(
+        &server.address,
+        "POST",
+        "/apps/4000000/subscriptions",
+        &[host.clone(), "Content-Type: text/plain".to_string()],
+        Some(r#"{"item_ids":[3000000002]}"#),
+    );
+    assert_eq!(status, 415);
+
+    let (_, subscribed) = request(&server.address, "GET", "/apps/4000000/subscriptions", None);
+    assert_eq!(subscribed.as_array().map(Vec::len), Some(1));
+
+    let (status, head, _) = send(
+        &server.address,
+        "OPTIONS",
+        "/apps/4000000/subscriptions",
+        &[host.clone(), "Origin: http://localhost:3000".to_string()],
+        None,
+    );
+    assert_eq!(status, 204);
+    assert!(head.contains("Access-Control-Allow-Origin: http://localhost:3000"));
+
+    let (status, head, _) = send(
+        &server.address,
+        "GET",
+        "/apps/4000000/subscriptions",
+        &[host, "Origin: http://localhost:3000".to_string()],
+        None,
+    );
+    assert_eq!(status, 200);
+    assert!(head.contains("Access-Control-Allow-Origin: http://localhost:3000"));
+    assert!(head.contains("Vary: Origin"));
+}
+
+#[test]
+fn test_fake_http_serve_limits_headers() {
+    let server = spawn_fake_http_server();
+    let host = format!("Host: {}", server.address);
+
+    let long_header = format!("X-Padding: {}", "a".repeat(10 * 1024));
+    let (status, _, _) = send(
+        &server.address,
+        "GET",
+        "/status",
+        &[host.clone(), long_header],
+        None,
+    );
+    assert_eq!(status, 431);
+
+    let mut headers = vec![host.clone()];
+    headers.extend((0..100).map(|i| format!("X-Header-{}: {}", i, i)));
+    let (status, _, _) = send(&server.address, "GET", "/status", &headers, None);
+    assert_eq!(status, 431);
+
+    let long_path = format!("/apps/4000000/items?ids={}", "1,".repeat(5 * 1024));
+    let (status, _, _) = send(
+        &server.address,
+        "GET",
+        &long_path,
+        std::slice::from_ref(&host),
+        None,
+    );
+    assert_eq!(status, 414);
+
+    let (status, _, _) = send(&server.address, "GET", "/status", &[host], None);
+    assert_eq!(status, 200);
+}
@@ -0,0 +1,133 @@
+use crate::test_modules::utils::{
+    FAKE_APP_ID, assert_valid_json, run_fake_steam_command, run_fake_steam_serve,
+};
+
+#[test]
+fn test_fake_delete_item() {
+    let output = run_fake_steam_command(&[
+        "delete-item",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000003",
+        "--yes",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(value["item_id"].as_u64(), Some(3000000003));
+    assert_eq!(value["title"].as_str(), Some("Weapon Overhaul"));
+    assert_eq!(value["deleted"].as_bool(), Some(true));
+}
+
+#[test]
+fn test_fake_delete_item_requires_confirmation_and_ownership() {
+    let output = run_fake_steam_command(&[
+        "delete-item",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000003",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = run_fake_steam_command(&[
+        "delete-item",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000002",
+        "--yes",
+        "--json-errors",
+    ]);
+    assert_eq!(output.status.code(), Some(9));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let error = assert_valid_json(stderr.lines().last().expect("Expected an error line"));
+    assert_eq!(error["error"]["code"].as_str(), Some("not_owner"));
+    assert_eq!(
+        error["error"]["details"]["item_id"].as_u64(),
+        Some(3000000002)
+    );
+
+    let output = run_fake_steam_command(&[
+        "delete-item",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "42",
+        "--yes",
+    ]);
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[test]
+fn test_fake_set_visibility() {
+    let output = run_fake_steam_command(&[
+        "set-visibility",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000001",
+        "--visibility",
+        "friends-only",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(value["previous_visibility"].as_str(), Some("Public"));
+    assert_eq!(value["visibility"].as_str(), Some("FriendsOnly"));
+
+    let output = run_fake_steam_command(&[
+        "set-visibility",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000002",
+        "--visibility",
+        "private",
+    ]);
+    assert_eq!(output.status.code(), Some(9));
+
+    let output = run_fake_steam_command(&[
+        "set-visibility",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000001",
+        "--visibility",
+        "hidden",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_fake_delete_item_in_serve() {
+    let output = run_fake_steam_serve(&[
+        r#"{"jsonrpc":"2.0","id":1,"method":"delete-item","params":{"app_id":4000000,"item_id":3000000003}}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"delete-item","params":{"app_id":4000000,"item_id":3000000003,"yes":true}}"#,
+    ]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let messages: Vec<_> = stdout.lines().map(assert_valid_json).collect();
+    let response = |id: u64| {
+        messages
+            .iter()
+            .find(|message| message["id"].as_u64() == Some(id))
+            .unwrap_or_else(|| panic!("No response for request {}", id))
+    };
+
+    assert_eq!(
+        response(1)["error"]["data"]["code"].as_str(),
+        Some("invalid_argument")
+    );
+    assert_eq!(response(2)["result"]["deleted"].as_bool(), Some(true));
+}
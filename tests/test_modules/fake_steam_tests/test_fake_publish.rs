@@ -0,0 +1,143 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::test_modules::utils::{
+    FAKE_APP_ID, assert_valid_json, run_fake_steam_command, run_fake_steam_serve_with,
+};
+
+fn publish_fixture(name: &str) -> (PathBuf, PathBuf) {
+    let root = std::env::temp_dir().join(name);
+    let _ = std::fs::remove_dir_all(&root);
+
+    let content = root.join("content");
+    std::fs::create_dir_all(content.join("data")).unwrap();
+    std::fs::write(content.join("mod.json"), "{}").unwrap();
+    std::fs::write(content.join("data").join("units.txt"), "tank").unwrap();
+
+    let preview = root.join("preview.png");
+    std::fs::write(&preview, "png").unwrap();
+
+    (content, preview)
+}
+
+#[test]
+fn test_fake_publish() {
+    let (content, preview) = publish_fixture("s7forge_test_publish");
+
+    let output = run_fake_steam_command(&[
+        "publish",
+        "--app-id",
+        FAKE_APP_ID,
+        "--title",
+        "Heavy Tanks",
+        "--content-folder",
+        content.to_str().unwrap(),
+        "--preview-image",
+        preview.to_str().unwrap(),
+        "--tags",
+        "Units, Balance",
+        "--change-note",
+        "First release",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(value["published_file_id"].as_u64(), Some(3000000011));
+    assert_eq!(value["needs_legal_agreement"].as_bool(), Some(false));
+}
+
+#[test]
+fn test_fake_publish_is_visible_to_later_requests() {
+    let (content, preview) = publish_fixture("s7forge_test_publish_serve");
+    let publish = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "publish",
+        "params": {
+            "app_id": 4000000,
+            "title": "Desert Maps",
+            "description": "Sand everywhere",
+            "content_folder": content,
+            "preview_image": preview,
+            "visibility": "unlisted",
+            "tags": ["Maps"],
+        },
+    });
+
+    let output = run_fake_steam_serve_with(&[], |stdin| {
+        writeln!(stdin, "{}", publish).unwrap();
+        stdin.wait_for_response(1);
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":2,"method":"workshop-items","params":{{"app_id":4000000,"item_ids":[3000000011]}}}}"#).unwrap();
+    });
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let messages: Vec<_> = stdout.lines().map(assert_valid_json).collect();
+    let item = messages
+        .iter()
+        .find(|message| message["id"].as_u64() == Some(2))
+        .map(|message| &message["result"][0])
+        .expect("No workshop-items response");
+
+    assert_eq!(item["title"].as_str(), Some("Desert Maps"));
+    assert_eq!(item["creator_name"].as_str(), Some("Alice"));
+    assert_eq!(item["visibility"].as_str(), Some("Unlisted"));
+    assert_eq!(item["tags"], serde_json::json!(["Maps"]));
+    assert_eq!(item["file_size"].as_u64(), Some(6));
+}
+
+#[test]
+fn test_fake_publish_validates_before_uploading() {
+    let (content, preview) = publish_fixture("s7forge_test_publish_invalid");
+    let content = content.to_str().unwrap();
+    let preview = preview.to_str().unwrap();
+    let long_title = "x".repeat(129);
+
+    let cases: [&[&str]; 4] = [
+        &[
+            "--title",
+            "Tanks",
+            "--content-folder",
+            "/no/such/folder",
+            "--preview-image",
+            preview,
+        ],
+        &[
+            "--title",
+            "Tanks",
+            "--content-folder",
+            preview,
+            "--preview-image",
+            preview,
+        ],
+        &[
+            "--title",
+            &long_title,
+            "--content-folder",
+            content,
+            "--preview-image",
+            preview,
+        ],
+        &[
+            "--title",
+            "Tanks",
+            "--content-folder",
+            content,
+            "--preview-image",
+            preview,
+            "--visibility",
+            "secret",
+        ],
+    ];
+
+    for args in cases {
+        let mut command = vec!["publish", "--app-id", FAKE_APP_ID];
+        command.extend_from_slice(args);
+        let output = run_fake_steam_command(&command);
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+    }
+}
@@ -0,0 +1,182 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::test_modules::utils::{
+    assert_valid_json, run_fake_steam_command, run_fake_steam_serve_with,
+};
+
+fn manifest_fixture(name: &str, manifest: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(name);
+    let _ = std::fs::remove_dir_all(&root);
+
+    std::fs::create_dir_all(root.join("build").join("art")).unwrap();
+    std::fs::write(root.join("build").join("mod.json"), "{}").unwrap();
+    std::fs::write(root.join("build").join("art").join("tank.psd"), "layers").unwrap();
+    std::fs::write(root.join("preview.png"), "png").unwrap();
+    std::fs::write(root.join("DESCRIPTION.md"), "Heavier tanks").unwrap();
+    std::fs::write(root.join("workshop.toml"), manifest).unwrap();
+
+    root
+}
+
+fn manifest_text(root: &Path) -> String {
+    std::fs::read_to_string(root.join("workshop.toml")).unwrap()
+}
+
+const MANIFEST: &str = r#"# Released from CI
+app_id = 4000000
+title = "Heavy Tanks"
+description_file = "DESCRIPTION.md"
+tags = ["Units", "Balance"]
+visibility = "unlisted"
+preview_image = "preview.png"
+content_dir = "build"
+ignore = ["*.psd"]
+change_note = "Version {version} ({date})"
+"#;
+
+#[test]
+fn test_fake_publish_manifest_creates_then_updates() {
+    let root = manifest_fixture("s7forge_test_manifest_serve", MANIFEST);
+    let publish = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "publish-manifest",
+        "params": { "manifest": root, "version": "1.0.0" },
+    });
+
+    let output = run_fake_steam_serve_with(&[], |stdin| {
+        let mut request = publish.clone();
+        request["id"] = 1.into();
+        writeln!(stdin, "{}", request).unwrap();
+        stdin.wait_for_response(1);
+        request["id"] = 2.into();
+        writeln!(stdin, "{}", request).unwrap();
+        stdin.wait_for_response(2);
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":3,"method":"workshop-items","params":{{"app_id":4000000,"item_ids":[3000000011]}}}}"#).unwrap();
+    });
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let messages: Vec<_> = stdout.lines().map(assert_valid_json).collect();
+    let result = |id: u64| {
+        messages
+            .iter()
+            .find(|message| message["id"].as_u64() == Some(id))
+            .map(|message| message["result"].clone())
+            .unwrap_or_else(|| panic!("No response for request {}", id))
+    };
+
+    assert_eq!(result(1)["published_file_id"].as_u64(), Some(3000000011));
+    assert_eq!(result(1)["created"].as_bool(), Some(true));
+    assert_eq!(result(2)["published_file_id"].as_u64(), Some(3000000011));
+    assert_eq!(result(2)["created"].as_bool(), Some(false));
+
+    let item = &result(3)[0];
+    assert_eq!(item["title"].as_str(), Some("Heavy Tanks"));
+    assert_eq!(item["description"].as_str(), Some("Heavier tanks"));
+    assert_eq!(item["visibility"].as_str(), Some("Unlisted"));
+    // Only mod.json is uploaded, the ignored .psd is left out.
+    assert_eq!(item["file_size"].as_u64(), Some(2));
+
+    let manifest = manifest_text(&root);
+    assert!(
+        manifest
+            .starts_with("# Released from CI\napp_id = 4000000\npublished_file_id = 3000000011\n")
+    );
+}
+
+#[test]
+fn test_fake_publish_manifest_json() {
+    let root = manifest_fixture("s7forge_test_manifest_json", "");
+    std::fs::remove_file(root.join("workshop.toml")).unwrap();
+    let manifest = root.join("workshop.json");
+    std::fs::write(
+        &manifest,
+        r#"{"app_id": 4000000, "published_file_id": 3000000003, "title": "Weapon Overhaul", "preview_image": "preview.png", "content_dir": "build"}"#,
+    )
+    .unwrap();
+
+    let output = run_fake_steam_command(&["publish", "--manifest", manifest.to_str().unwrap()]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(value["published_file_id"].as_u64(), Some(3000000003));
+    assert_eq!(value["created"].as_bool(), Some(false));
+}
+
+#[test]
+fn test_fake_publish_manifest_validates_before_uploading() {
+    let cases = [
+        MANIFEST.replace("preview.png", "missing.png"),
+        MANIFEST.replace("unlisted", "secret"),
+        MANIFEST.replace("{date}", "{commit}"),
+        MANIFEST.replace("*.psd", "*"),
+        MANIFEST.replace(
+            "title = \"Heavy Tanks\"",
+            "title = \"Heavy Tanks\"\nauthor = \"Alice\"",
+        ),
+        MANIFEST.replace("app_id = 4000000", "app_id = \"tanks\""),
+    ];
+
+    for (index, manifest) in cases.iter().enumerate() {
+        let root = manifest_fixture(
+            &format!("s7forge_test_manifest_invalid_{}", index),
+            manifest,
+        );
+        let output = run_fake_steam_command(&[
+            "publish",
+            "--manifest",
+            root.to_str().unwrap(),
+            "--version",
+            "1.0.0",
+        ]);
+        assert_eq!(output.status.code(), Some(2), "{}", manifest);
+        assert_eq!(manifest_text(&root), *manifest);
+    }
+
+    // The change note needs a version, which is checked before the item is created.
+    let root = manifest_fixture("s7forge_test_manifest_no_version", MANIFEST);
+    let output = run_fake_steam_command(&["publish", "--manifest", root.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(manifest_text(&root), MANIFEST);
+
+    let output = run_fake_steam_command(&[
+        "publish",
+        "--manifest",
+        root.to_str().unwrap(),
+        "--title",
+        "Other",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_fake_publish_manifest_symlink_loop() {
+    let root = manifest_fixture("s7forge_test_manifest_symlink_loop", MANIFEST);
+    std::os::unix::fs::symlink(
+        root.join("build"),
+        root.join("build").join("art").join("up"),
+    )
+    .unwrap();
+
+    let output = run_fake_steam_command(&[
+        "publish",
+        "--manifest",
+        root.to_str().unwrap(),
+        "--version",
+        "1.0.0",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(value["created"].as_bool(), Some(true));
+}
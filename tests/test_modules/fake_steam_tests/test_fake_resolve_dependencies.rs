@@ -0,0 +1,142 @@
+use crate::test_modules::utils::{FAKE_APP_ID, assert_valid_json, run_fake_steam_command_with};
+
+// A separate catalog whose items form longer dependency chains and cycles.
+fn resolve_dependencies(args: &[&str]) -> std::process::Output {
+    let catalog = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/fake_dependency_catalog.json"
+    );
+    let mut command = vec!["resolve-dependencies", "--app-id", FAKE_APP_ID];
+    command.extend_from_slice(args);
+    run_fake_steam_command_with(catalog, &command, &[])
+}
+
+#[test]
+fn test_fake_resolve_dependencies() {
+    let output = resolve_dependencies(&["--item-ids", "3100000001,3100000005"]);
+    assert!(
+        output.status.success(),
+        "resolve-dependencies failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let graph = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(
+        graph["load_order"],
+        serde_json::json!([
+            3100000002u64,
+            3100000004u64,
+            3100000003u64,
+            3100000001u64,
+            3100000005u64,
+            3100000006u64
+        ])
+    );
+    assert_eq!(
+        graph["cycles"],
+        serde_json::json!([[3100000005u64, 3100000006u64]])
+    );
+    assert_eq!(graph["missing"], serde_json::json!([3100000099u64]));
+    assert_eq!(graph["banned"], serde_json::json!([3100000004u64]));
+    assert!(graph.get("dot").is_none());
+
+    let nodes = graph["nodes"].as_array().expect("Expected nodes");
+    assert_eq!(nodes.len(), 7);
+    let node = |id: u64| {
+        nodes
+            .iter()
+            .find(|node| node["published_file_id"].as_u64() == Some(id))
+            .unwrap_or_else(|| panic!("No node for {}", id))
+    };
+    assert_eq!(node(3100000002)["title"].as_str(), Some("Core Library"));
+    assert_eq!(node(3100000002)["creator_name"].as_str(), Some("Bob"));
+    assert_eq!(
+        node(3100000002)["required_by"],
+        serde_json::json!([3100000001u64, 3100000003u64, 3100000006u64])
+    );
+    assert_eq!(node(3100000004)["status"].as_str(), Some("banned"));
+    assert_eq!(node(3100000099)["status"].as_str(), Some("missing"));
+    assert!(node(3100000099)["title"].is_null());
+}
+
+#[test]
+fn test_fake_resolve_dependencies_dot() {
+    let output = resolve_dependencies(&["--item-ids", "3100000003", "--dot"]);
+    assert!(output.status.success());
+
+    let json = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(json["load_order"].as_array().map(Vec::len), Some(3));
+    let stdout = json["dot"].as_str().expect("dot should be a string");
+    assert!(stdout.starts_with("digraph dependencies {\n"), "{}", stdout);
+    assert!(
+        stdout.contains(r#""3100000003" [label="Unit Pack", shape=box];"#),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains(r#""3100000004" [label="Banned \"HD\" Textures (banned)", color=red];"#),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains(r#""3100000099" [label="3100000099 (missing)", style=dashed];"#),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains(r#""3100000003" -> "3100000099";"#),
+        "{}",
+        stdout
+    );
+    assert!(stdout.trim_end().ends_with('}'));
+}
+
+#[test]
+fn test_fake_resolve_dependencies_queries_in_batches() {
+    // One item requires 120 others, which form one long cycle. The 120 are found in the
+    // same round, more than fit in one query.
+    let dependencies: Vec<u64> = (3200000001..=3200000120).collect();
+    let mut items = vec![serde_json::json!({
+        "published_file_id": 3200000000u64,
+        "title": "Mega Pack",
+        "children": dependencies,
+    })];
+    for (position, id) in dependencies.iter().enumerate() {
+        let next = dependencies[(position + 1) % dependencies.len()];
+        items.push(serde_json::json!({ "published_file_id": id, "children": [next] }));
+    }
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("resolve-dependencies");
+    std::fs::create_dir_all(&dir).unwrap();
+    let catalog = dir.join("batches_catalog.json");
+    std::fs::write(&catalog, serde_json::json!({ "items": items }).to_string()).unwrap();
+
+    let output = run_fake_steam_command_with(
+        catalog.to_str().unwrap(),
+        &[
+            "resolve-dependencies",
+            "--app-id",
+            FAKE_APP_ID,
+            "--item-ids",
+            "3200000000",
+        ],
+        &[],
+    );
+    assert!(
+        output.status.success(),
+        "resolve-dependencies failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let graph = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    let mut load_order = dependencies.clone();
+    load_order.push(3200000000);
+    assert_eq!(graph["load_order"], serde_json::json!(load_order));
+    assert_eq!(graph["cycles"], serde_json::json!([dependencies]));
+    assert_eq!(graph["missing"], serde_json::json!([]));
+}
+
+#[test]
+fn test_fake_resolve_dependencies_requires_item_ids() {
+    let output = resolve_dependencies(&[]);
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,384 @@
+use std::sync::OnceLock;
+
+use crate::test_modules::utils::{
+    FAKE_APP_ID, assert_valid_json, run_fake_steam_command, run_fake_steam_command_with,
+};
+
+// Its own app ID keeps the cached search pages apart from the ones for the shared catalog.
+const PAGED_APP_ID: &str = "4000001";
+const PAGED_ITEM_COUNT: u64 = 120;
+
+fn search(args: &[&str]) -> serde_json::Value {
+    let mut full_args = vec!["search-workshop", "--app-id", FAKE_APP_ID];
+    full_args.extend_from_slice(args);
+
+    let output = run_fake_steam_command(&full_args);
+    assert!(
+        output.status.success(),
+        "search-workshop failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_valid_json(&String::from_utf8_lossy(&output.stdout))
+}
+
+// Searches with --page-info give the page output, whose items are read instead.
+fn search_ids(args: &[&str]) -> Vec<u64> {
+    let value = search(args);
+    value
+        .get("items")
+        .unwrap_or(&value)
+        .as_array()
+        .expect("Expected JSON array")
+        .iter()
+        .map(|item| item["published_file_id"].as_u64().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_fake_search_by_text() {
+    let ids = search_ids(&["--query", "tank", "--sort-by", "relevance"]);
+    assert_eq!(ids, vec![3000000001, 3000000002]);
+}
+
+#[test]
+fn test_fake_search_popular_order() {
+    let ids = search_ids(&["--sort-by", "popular", "--period", "one-week"]);
+    assert_eq!(ids, vec![3000000003, 3000000001, 3000000002]);
+}
+
+#[test]
+fn test_fake_search_most_subscribed_order() {
+    let ids = search_ids(&["--sort-by", "most-subscribed"]);
+    assert_eq!(ids, vec![3000000002, 3000000001, 3000000003]);
+}
+
+#[test]
+fn test_fake_search_vote_orders() {
+    assert_eq!(
+        search_ids(&["--sort-by", "top-rated"]),
+        vec![3000000003, 3000000001, 3000000002]
+    );
+    assert_eq!(
+        search_ids(&["--sort-by", "fewest-votes"]),
+        vec![3000000002, 3000000001, 3000000003]
+    );
+}
+
+#[test]
+fn test_fake_search_rejects_unknown_sort_and_period() {
+    for args in [
+        &["--sort-by", "most-popular"][..],
+        &["--sort-by", "popular", "--period", "one-month"][..],
+        &["--sort-by", "recent", "--period", "one-week"][..],
+    ] {
+        let mut full_args = vec!["search-workshop", "--app-id", FAKE_APP_ID];
+        full_args.extend_from_slice(args);
+        let output = run_fake_steam_command(&full_args);
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+    }
+}
+
+#[test]
+fn test_fake_search_created_date_range() {
+    // Better Tanks was created late on 2023-11-14, which an until date still covers.
+    assert_eq!(
+        search_ids(&["--sort-by", "recent", "--created-since", "2023-11-14"]),
+        vec![3000000002, 3000000001]
+    );
+    assert_eq!(
+        search_ids(&["--sort-by", "recent", "--created-until", "2023-11-14"]),
+        vec![3000000001, 3000000003]
+    );
+}
+
+#[test]
+fn test_fake_search_updated_date_range() {
+    let mut ids = search_ids(&[
+        "--sort-by",
+        "recent",
+        "--updated-since",
+        "1715000000",
+        "--updated-until",
+        "2024-12-31",
+    ]);
+    ids.sort();
+    assert_eq!(ids, vec![3000000001, 3000000003]);
+}
+
+#[test]
+fn test_fake_search_rejects_invalid_date_ranges() {
+    for args in [
+        &["--created-since", "2024-02-30"][..],
+        &["--created-since", "yesterday"][..],
+        &[
+            "--updated-since",
+            "2024-06-01",
+            "--updated-until",
+            "2024-01-01",
+        ][..],
+    ] {
+        let mut full_args = vec!["search-workshop", "--app-id", FAKE_APP_ID];
+        full_args.extend_from_slice(args);
+        let output = run_fake_steam_command(&full_args);
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+    }
+}
+
+#[test]
+fn test_fake_search_by_type() {
+    let output = run_fake_steam_command(&[
+        "search-workshop",
+        "--app-id",
+        FAKE_APP_ID,
+        "--sort-by",
+        "recent",
+        "--type",
+        "collections",
+        "--page-info",
+    ]);
+    assert!(output.status.success());
+
+    let page = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    let items = page["items"].as_array().expect("Expected items array");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["published_file_id"].as_u64(), Some(3000000010));
+    assert_eq!(items[0]["file_type"].as_str(), Some("Collection"));
+    assert_eq!(page["filtered"], serde_json::json!([]));
+}
+
+#[test]
+fn test_fake_search_required_tags() {
+    let ids = search_ids(&["--sort-by", "recent", "--tags", "Units,UI"]);
+    assert_eq!(ids, vec![3000000003]);
+}
+
+#[test]
+fn test_fake_search_match_any_tags() {
+    let mut ids = search_ids(&[
+        "--sort-by",
+        "recent",
+        "--tags",
+        "Maps,UI",
+        "--match-any-tags",
+    ]);
+    ids.sort();
+    assert_eq!(ids, vec![3000000002, 3000000003]);
+}
+
+#[test]
+fn test_fake_search_excluded_tags() {
+    let ids = search_ids(&[
+        "--sort-by",
+        "recent",
+        "--tags",
+        "Units",
+        "--exclude-tags",
+        "UI",
+    ]);
+    assert_eq!(ids, vec![3000000001]);
+}
+
+#[test]
+fn test_fake_search_tag_groups() {
+    let ids = search_ids(&[
+        "--sort-by",
+        "recent",
+        "--tag-group",
+        "Maps,UI",
+        "--tag-group",
+        "Units",
+    ]);
+    assert_eq!(ids, vec![3000000003]);
+
+    let mut ids = search_ids(&["--sort-by", "recent", "--tag-group", "Maps, mod"]);
+    ids.sort();
+    assert_eq!(ids, vec![3000000001, 3000000002]);
+
+    // Steam applies the groups, so they are part of its count.
+    let page = search(&[
+        "--sort-by",
+        "recent",
+        "--tag-group",
+        "Maps,UI",
+        "--tag-group",
+        "Units",
+        "--page-info",
+    ]);
+    assert_eq!(page["total_results"].as_u64(), Some(1));
+    assert_eq!(page["filtered"], serde_json::json!([]));
+}
+
+#[test]
+fn test_fake_search_reports_locally_filtered_items() {
+    let page = search(&[
+        "--sort-by",
+        "recent",
+        "--game-version",
+        "1.2",
+        "--page-info",
+    ]);
+    // Steam's count is from before the game version is checked.
+    assert_eq!(page["total_results"].as_u64(), Some(3));
+    assert_eq!(page["returned_results"].as_u64(), Some(3));
+    assert_eq!(page["matching_results"].as_u64(), Some(1));
+
+    let filtered = page["filtered"]
+        .as_array()
+        .expect("Expected filtered items");
+    let mut filtered_ids: Vec<u64> = filtered
+        .iter()
+        .map(|item| item["published_file_id"].as_u64().unwrap())
+        .collect();
+    filtered_ids.sort();
+    assert_eq!(filtered_ids, vec![3000000002, 3000000003]);
+    assert!(filtered.iter().all(|item| item["reason"] == "game-version"));
+
+    // Steam applies date ranges, so they are part of its count.
+    let page = search(&[
+        "--sort-by",
+        "recent",
+        "--created-until",
+        "2023-11-14",
+        "--page-info",
+    ]);
+    assert_eq!(page["total_results"].as_u64(), Some(2));
+    assert_eq!(page["filtered"], serde_json::json!([]));
+}
+
+#[test]
+fn test_fake_search_local_filters_need_page_info() {
+    for args in [
+        &["--type", "collections"][..],
+        &["--game-version", "1.2"][..],
+    ] {
+        let mut full_args = vec!["search-workshop", "--app-id", FAKE_APP_ID];
+        full_args.extend_from_slice(args);
+        let output = run_fake_steam_command(&full_args);
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+    }
+}
+
+#[test]
+fn test_fake_search_match_any_needs_tags() {
+    let output = run_fake_steam_command(&[
+        "search-workshop",
+        "--app-id",
+        FAKE_APP_ID,
+        "--match-any-tags",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_fake_search_page_past_end() {
+    let ids = search_ids(&["--sort-by", "recent", "--page", "2"]);
+    assert!(ids.is_empty(), "Expected no results past the last page");
+}
+
+// Written once, since the tests using it run in parallel.
+fn paged_catalog() -> &'static str {
+    static CATALOG: OnceLock<String> = OnceLock::new();
+    CATALOG.get_or_init(write_paged_catalog)
+}
+
+fn write_paged_catalog() -> String {
+    let items: Vec<_> = (1..=PAGED_ITEM_COUNT)
+        .map(|n| {
+            serde_json::json!({
+                "published_file_id": 3100000000 + n,
+                "title": format!("Paged item {}", n),
+                "owner": 76561190000000001u64,
+                "time_created": 1700000000 + n,
+            })
+        })
+        .collect();
+
+    let path = std::env::temp_dir().join("s7forge_test_paged_catalog.json");
+    std::fs::write(&path, serde_json::json!({ "items": items }).to_string()).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+fn paged_search(args: &[&str]) -> std::process::Output {
+    let catalog = paged_catalog();
+    let mut full_args = vec![
+        "search-workshop",
+        "--app-id",
+        PAGED_APP_ID,
+        "--sort-by",
+        "recent",
+    ];
+    full_args.extend_from_slice(args);
+    run_fake_steam_command_with(catalog, &full_args, &[])
+}
+
+#[test]
+fn test_fake_search_page_info() {
+    let output = paged_search(&["--page-info"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(value["total_results"].as_u64(), Some(PAGED_ITEM_COUNT));
+    assert_eq!(value["page"].as_u64(), Some(1));
+    assert_eq!(value["page_size"].as_u64(), Some(50));
+    assert_eq!(value["has_more"].as_bool(), Some(true));
+    assert_eq!(value["items"].as_array().map(Vec::len), Some(50));
+    assert_eq!(
+        value["items"][0]["published_file_id"].as_u64(),
+        Some(3100000120)
+    );
+}
+
+#[test]
+fn test_fake_search_max_pages() {
+    let output = paged_search(&["--page", "2", "--max-pages", "5", "--page-info"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    // Stops at the last page rather than asking for all five.
+    assert_eq!(value["page"].as_u64(), Some(3));
+    assert_eq!(value["has_more"].as_bool(), Some(false));
+    assert_eq!(value["items"].as_array().map(Vec::len), Some(70));
+}
+
+#[test]
+fn test_fake_search_all_pages() {
+    let output = paged_search(&["--all-pages"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    let ids: Vec<u64> = value
+        .as_array()
+        .expect("Expected JSON array")
+        .iter()
+        .map(|item| item["published_file_id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(ids.len() as u64, PAGED_ITEM_COUNT);
+    assert!(
+        ids.windows(2).all(|pair| pair[0] > pair[1]),
+        "Pages were not merged in order"
+    );
+}
+
+#[test]
+fn test_fake_search_rejects_conflicting_page_options() {
+    for args in [
+        &["--all-pages", "--max-pages", "2"][..],
+        &["--max-pages", "0"][..],
+    ] {
+        let output = paged_search(args);
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+    }
+}
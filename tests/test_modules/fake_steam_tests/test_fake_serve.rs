@@ -0,0 +1,166 @@
+use serde_json::Value;
+use std::io::Write;
+use std::time::Duration;
+
+use crate::test_modules::utils::{
+    assert_valid_json, run_fake_steam_serve, run_fake_steam_serve_with,
+};
+
+fn messages(output: &std::process::Output) -> Vec<Value> {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(assert_valid_json)
+        .collect()
+}
+
+fn response(messages: &[Value], id: u64) -> &Value {
+    messages
+        .iter()
+        .find(|message| message["id"].as_u64() == Some(id))
+        .unwrap_or_else(|| panic!("No response with id {}", id))
+}
+
+#[test]
+fn test_fake_serve_answers_each_request() {
+    let output = run_fake_steam_serve(&[
+        r#"{"jsonrpc":"2.0","id":1,"method":"workshop-items","params":{"app_id":4000000,"item_ids":[3000000001]}}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"subscribed-items","params":{"app_id":4000000}}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"search-workshop","params":{"app_id":4000000,"query":"tank"}}"#,
+    ]);
+    assert!(output.status.success());
+
+    let messages = messages(&output);
+    assert_eq!(messages.len(), 3);
+
+    let items = response(&messages, 1)["result"].as_array().unwrap();
+    assert_eq!(items[0]["title"].as_str(), Some("Better Tanks"));
+
+    let subscribed = response(&messages, 2)["result"].as_array().unwrap();
+    assert_eq!(subscribed.len(), 1);
+
+    assert!(response(&messages, 3)["result"].is_array());
+}
+
+#[test]
+fn test_fake_serve_download_notifications() {
+    let output = run_fake_steam_serve(&[
+        r#"{"jsonrpc":"2.0","id":7,"method":"download-workshop-item","params":{"app_id":4000000,"item_id":3000000001}}"#,
+    ]);
+    assert!(output.status.success());
+
+    let messages = messages(&output);
+    let methods: Vec<&str> = messages
+        .iter()
+        .filter_map(|message| message["method"].as_str())
+        .collect();
+    assert_eq!(methods, ["download-progress", "item-installed"]);
+    assert_eq!(messages[0]["params"]["total_bytes"].as_u64(), Some(2048));
+    assert_eq!(messages[1]["params"]["item_id"].as_u64(), Some(3000000001));
+    assert!(response(&messages, 7)["result"].is_string());
+}
+
+#[test]
+fn test_fake_serve_errors() {
+    let output = run_fake_steam_serve(&[
+        "not json",
+        r#"{"jsonrpc":"2.0","id":1,"method":"no-such-command"}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"workshop-items","params":{"item_ids":[1]}}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"download-workshop-item","params":{"app_id":4000000,"item_id":3000000003}}"#,
+        r#"{"jsonrpc":"2.0","method":"steam-library-paths"}"#,
+    ]);
+    assert!(output.status.success());
+
+    let messages = messages(&output);
+    assert_eq!(messages.len(), 4, "Notifications must not get a response");
+
+    let parse_error = messages
+        .iter()
+        .find(|message| message["id"].is_null())
+        .unwrap();
+    assert_eq!(parse_error["error"]["code"].as_i64(), Some(-32700));
+    assert_eq!(
+        response(&messages, 1)["error"]["code"].as_i64(),
+        Some(-32601)
+    );
+    assert_eq!(
+        response(&messages, 2)["error"]["code"].as_i64(),
+        Some(-32602)
+    );
+    let command_error = &response(&messages, 3)["error"];
+    assert_eq!(command_error["code"].as_i64(), Some(-32000));
+    assert_eq!(
+        command_error["data"]["code"].as_str(),
+        Some("not_subscribed")
+    );
+    assert_eq!(
+        command_error["data"]["details"]["item_id"].as_u64(),
+        Some(3000000003)
+    );
+}
+
+#[test]
+fn test_fake_serve_runs_one_worker_per_app() {
+    let output = run_fake_steam_serve_with(&[], |stdin| {
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":1,"method":"workshop-items","params":{{"app_id":4000000,"item_ids":[3000000001]}}}}"#).unwrap();
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":2,"method":"workshop-items","params":{{"app_id":4000001,"item_ids":[3000000002]}}}}"#).unwrap();
+        stdin.wait_for_response(1);
+        stdin.wait_for_response(2);
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":3,"method":"status"}}"#).unwrap();
+    });
+    assert!(output.status.success());
+
+    let messages = messages(&output);
+    assert!(response(&messages, 1)["result"].is_array());
+    assert!(response(&messages, 2)["result"].is_array());
+
+    let status = &response(&messages, 3)["result"];
+    assert_eq!(status["mode"].as_str(), Some("workers"));
+    assert_eq!(
+        status["initialized_apps"],
+        serde_json::json!([4000000, 4000001])
+    );
+    assert_eq!(status["workers"][0]["healthy"].as_bool(), Some(true));
+}
+
+#[test]
+fn test_fake_serve_evicts_idle_workers() {
+    let output = run_fake_steam_serve_with(&["--idle-timeout", "1"], |stdin| {
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":1,"method":"subscribed-items","params":{{"app_id":4000000}}}}"#).unwrap();
+        stdin.wait_for_response(1);
+
+        // Status requests don't keep the worker busy, so it goes after a second.
+        for id in 2.. {
+            writeln!(
+                stdin,
+                r#"{{"jsonrpc":"2.0","id":{},"method":"status"}}"#,
+                id
+            )
+            .unwrap();
+            let status = stdin.wait_for_response(id);
+            if status["result"]["initialized_apps"] == serde_json::json!([]) {
+                break;
+            }
+            assert!(id < 20, "Idle worker was never evicted");
+            std::thread::sleep(Duration::from_millis(500));
+        }
+    });
+    assert!(output.status.success());
+
+    let messages = messages(&output);
+    assert!(response(&messages, 1)["result"].is_array());
+}
+
+#[test]
+fn test_fake_serve_in_process_status() {
+    let output = run_fake_steam_serve_with(&["--in-process"], |stdin| {
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":1,"method":"subscribed-items","params":{{"app_id":4000000}}}}"#).unwrap();
+        stdin.wait_for_response(1);
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":2,"method":"status"}}"#).unwrap();
+    });
+    assert!(output.status.success());
+
+    let messages = messages(&output);
+    let status = &response(&messages, 2)["result"];
+    assert_eq!(status["mode"].as_str(), Some("in-process"));
+    assert_eq!(status["initialized_apps"], serde_json::json!([4000000]));
+}
@@ -0,0 +1,119 @@
+use crate::test_modules::utils::{FAKE_APP_ID, assert_valid_json, run_fake_steam_command_with};
+
+/// Writes `rules` to a file only this test process uses, so parallel and concurrent
+/// runs don't overwrite each other's rules.
+fn rules_file(name: &str, rules: &str) -> std::path::PathBuf {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("tag-rules");
+    std::fs::create_dir_all(&dir).expect("Failed to create tag rules directory");
+    let path = dir.join(format!("{}-{}.toml", name, std::process::id()));
+    std::fs::write(&path, rules).expect("Failed to write tag rules");
+    path
+}
+
+// user-items is never cached, so the items always reflect the rules of the run.
+fn published_items_with_rules(name: &str, rules: &str) -> std::process::Output {
+    let rules_path = rules_file(name, rules);
+
+    let catalog = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/fake_catalog.json"
+    );
+    run_fake_steam_command_with(
+        catalog,
+        &[
+            "user-items",
+            "--app-id",
+            FAKE_APP_ID,
+            "--list",
+            "published",
+            "--order",
+            "title",
+        ],
+        &[("S7FORGE_TAG_RULES", &rules_path.to_string_lossy())],
+    )
+}
+
+#[test]
+fn test_fake_tag_rules_per_app() {
+    let output = published_items_with_rules(
+        "per-app",
+        r#"
+[default]
+capitalize = false
+
+[apps.4000000]
+exclude = ["Units"]
+exclude_versions = false
+
+[apps.4000000.rename]
+mod = "Mod"
+"#,
+    );
+    assert!(
+        output.status.success(),
+        "user-items failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    let items = value.as_array().expect("Expected JSON array");
+    assert_eq!(items[0]["title"].as_str(), Some("Better Tanks"));
+    assert_eq!(
+        items[0]["tags"],
+        serde_json::json!(["Units", "mod", "v1.2.x"])
+    );
+    assert_eq!(
+        items[0]["display_tags"],
+        serde_json::json!(["Mod", "v1.2.x"])
+    );
+    assert_eq!(items[1]["title"].as_str(), Some("Weapon Overhaul"));
+    assert_eq!(items[1]["display_tags"], serde_json::json!(["UI"]));
+}
+
+#[test]
+fn test_fake_tag_rules_rejects_invalid_file() {
+    let output = published_items_with_rules("invalid", "[default]\nhide = [\"mod\"]\n");
+    assert_eq!(output.status.code(), Some(2));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid tag rules"), "{}", stderr);
+}
+
+// workshop-items reads through the item cache, which must not keep display tags from
+// an earlier run.
+#[test]
+fn test_fake_tag_rules_apply_to_cached_items() {
+    let rules_path = rules_file("cached", "[default]\ncapitalize = false\nexclude = []\n");
+    let catalog = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/fake_catalog.json"
+    );
+    let display_tags = |rules: &str| {
+        let output = run_fake_steam_command_with(
+            catalog,
+            &[
+                "workshop-items",
+                "--app-id",
+                FAKE_APP_ID,
+                "--item-ids",
+                "3000000001",
+            ],
+            &[("S7FORGE_TAG_RULES", rules)],
+        );
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_valid_json(&String::from_utf8_lossy(&output.stdout))[0]["display_tags"].clone()
+    };
+
+    assert_eq!(
+        display_tags("no_such_tag_rules.toml"),
+        serde_json::json!(["Units"])
+    );
+    assert_eq!(
+        display_tags(&rules_path.to_string_lossy()),
+        serde_json::json!(["Units", "mod"])
+    );
+}
@@ -0,0 +1,127 @@
+use std::io::Write;
+
+use crate::test_modules::utils::{
+    FAKE_APP_ID, assert_valid_json, run_fake_steam_command, run_fake_steam_serve_with,
+};
+
+fn content_fixture(name: &str) -> std::path::PathBuf {
+    let content = std::env::temp_dir().join(name);
+    let _ = std::fs::remove_dir_all(&content);
+    std::fs::create_dir_all(&content).unwrap();
+    std::fs::write(content.join("weapons.txt"), "railgun").unwrap();
+    content
+}
+
+#[test]
+fn test_fake_update_item_reports_progress() {
+    let content = content_fixture("s7forge_test_update_item");
+
+    let output = run_fake_steam_command(&[
+        "update-item",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000003",
+        "--content-folder",
+        content.to_str().unwrap(),
+        "--change-note",
+        "Add railgun",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<_> = stdout.lines().map(assert_valid_json).collect();
+    let (result, events) = lines.split_last().expect("No output");
+
+    assert!(events.len() >= 2);
+    assert!(
+        events
+            .iter()
+            .all(|event| event["event"].as_str() == Some("progress"))
+    );
+    assert!(events.iter().any(|event| {
+        event["status"].as_str() == Some("uploading-content")
+            && event["bytes_processed"].as_u64() == Some(7)
+            && event["bytes_total"].as_u64() == Some(7)
+    }));
+
+    assert_eq!(result["published_file_id"].as_u64(), Some(3000000003));
+}
+
+#[test]
+fn test_fake_update_item_rejects_invalid_updates() {
+    let output = run_fake_steam_command(&[
+        "update-item",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000003",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = run_fake_steam_command(&[
+        "update-item",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000003",
+        "--visibility",
+        "secret",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = run_fake_steam_command(&[
+        "update-item",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "42",
+        "--title",
+        "Missing",
+    ]);
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[test]
+fn test_fake_update_item_in_serve() {
+    let update = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "update-item",
+        "params": {
+            "app_id": 4000000,
+            "item_id": 3000000003u64,
+            "title": "Weapon Overhaul 2",
+            "tags": ["Units"],
+        },
+    });
+
+    let output = run_fake_steam_serve_with(&[], |stdin| {
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":0,"method":"workshop-items","params":{{"app_id":4000000,"item_ids":[3000000003]}}}}"#).unwrap();
+        stdin.wait_for_response(0);
+        writeln!(stdin, "{}", update).unwrap();
+        stdin.wait_for_response(1);
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":2,"method":"workshop-items","params":{{"app_id":4000000,"item_ids":[3000000003]}}}}"#).unwrap();
+    });
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let messages: Vec<_> = stdout.lines().map(assert_valid_json).collect();
+
+    assert!(messages.iter().any(|message| {
+        message["method"].as_str() == Some("update-progress")
+            && message["params"]["item_id"].as_u64() == Some(3000000003)
+    }));
+
+    let item = messages
+        .iter()
+        .find(|message| message["id"].as_u64() == Some(2))
+        .map(|message| &message["result"][0])
+        .expect("No workshop-items response");
+    assert_eq!(item["title"].as_str(), Some("Weapon Overhaul 2"));
+    assert_eq!(item["tags"], serde_json::json!(["Units"]));
+}
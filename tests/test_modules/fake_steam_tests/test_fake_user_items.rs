@@ -0,0 +1,69 @@
+use crate::test_modules::utils::{FAKE_APP_ID, assert_valid_json, run_fake_steam_command};
+
+fn user_item_ids(args: &[&str]) -> Vec<u64> {
+    let mut command = vec!["user-items", "--app-id", FAKE_APP_ID];
+    command.extend_from_slice(args);
+    let output = run_fake_steam_command(&command);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_valid_json(&String::from_utf8_lossy(&output.stdout))
+        .as_array()
+        .expect("Expected an array of items")
+        .iter()
+        .map(|item| item["published_file_id"].as_u64().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_fake_user_items_lists_and_orders() {
+    assert_eq!(
+        user_item_ids(&["--list", "published"]),
+        vec![3000000001, 3000000003]
+    );
+    assert_eq!(
+        user_item_ids(&["--list", "published", "--order", "oldest"]),
+        vec![3000000003, 3000000001]
+    );
+    assert_eq!(
+        user_item_ids(&["--list", "favorited", "--order", "title"]),
+        vec![3000000002, 3000000003]
+    );
+    assert_eq!(user_item_ids(&["--list", "voted-up"]), vec![3000000002]);
+    assert_eq!(user_item_ids(&["--list", "voted-down"]), vec![3000000003]);
+    assert_eq!(user_item_ids(&["--list", "subscribed"]), vec![3000000001]);
+    assert!(user_item_ids(&["--list", "published", "--page", "2"]).is_empty());
+}
+
+#[test]
+fn test_fake_user_items_for_other_users() {
+    // Bob, as SteamID64, SteamID and account ID.
+    for user in ["76561190000000002", "STEAM_0:0:314834433", "629668866"] {
+        assert_eq!(
+            user_item_ids(&["--list", "published", "--user", user]),
+            vec![3000000002],
+            "{}",
+            user
+        );
+    }
+}
+
+#[test]
+fn test_fake_user_items_rejects_invalid_options() {
+    let cases: [&[&str]; 4] = [
+        &["--list", "bookmarked"],
+        &["--list", "published", "--order", "random"],
+        &["--list", "published", "--order", "recently-subscribed"],
+        &["--list", "published", "--user", "STEAM_0:2:1"],
+    ];
+
+    for args in cases {
+        let mut command = vec!["user-items", "--app-id", FAKE_APP_ID];
+        command.extend_from_slice(args);
+        let output = run_fake_steam_command(&command);
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+    }
+}
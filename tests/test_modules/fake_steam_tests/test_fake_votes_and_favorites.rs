@@ -0,0 +1,127 @@
+use std::io::Write;
+
+use crate::test_modules::utils::{
+    FAKE_APP_ID, assert_valid_json, run_fake_steam_command, run_fake_steam_serve_with,
+};
+
+#[test]
+fn test_fake_get_vote() {
+    for (item_id, vote) in [
+        ("3000000002", serde_json::json!("up")),
+        ("3000000003", serde_json::json!("down")),
+        ("3000000001", serde_json::Value::Null),
+    ] {
+        let output =
+            run_fake_steam_command(&["get-vote", "--app-id", FAKE_APP_ID, "--item-id", item_id]);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+        assert_eq!(value["vote"], vote, "{}", item_id);
+    }
+}
+
+#[test]
+fn test_fake_vote_and_favorite_errors() {
+    let cases: [(&[&str], i32); 4] = [
+        (
+            &["vote", "--app-id", FAKE_APP_ID, "--item-id", "3000000001"],
+            2,
+        ),
+        (
+            &[
+                "vote",
+                "--app-id",
+                FAKE_APP_ID,
+                "--item-id",
+                "3000000001",
+                "--up",
+                "--down",
+            ],
+            2,
+        ),
+        (
+            &["vote", "--app-id", FAKE_APP_ID, "--item-id", "42", "--up"],
+            4,
+        ),
+        (
+            &[
+                "favorite",
+                "toggle",
+                "--app-id",
+                FAKE_APP_ID,
+                "--item-id",
+                "3000000001",
+            ],
+            2,
+        ),
+    ];
+
+    for (args, exit_code) in cases {
+        let output = run_fake_steam_command(args);
+        assert_eq!(output.status.code(), Some(exit_code), "{:?}", args);
+    }
+}
+
+#[test]
+fn test_fake_favorite_add_is_idempotent() {
+    let output = run_fake_steam_command(&[
+        "favorite",
+        "add",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-id",
+        "3000000002",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(value["favorited"].as_bool(), Some(true));
+    assert_eq!(value["changed"].as_bool(), Some(false));
+}
+
+#[test]
+fn test_fake_vote_and_favorite_show_in_user_state() {
+    // Requests run concurrently, so the lookup waits for both changes to finish.
+    let output = run_fake_steam_serve_with(&[], |stdin| {
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":1,"method":"vote","params":{{"app_id":4000000,"item_id":3000000003,"vote":"up"}}}}"#).unwrap();
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":2,"method":"favorite","params":{{"app_id":4000000,"item_id":3000000003,"action":"remove"}}}}"#).unwrap();
+        stdin.wait_for_response(1);
+        stdin.wait_for_response(2);
+        writeln!(stdin, r#"{{"jsonrpc":"2.0","id":3,"method":"workshop-items","params":{{"app_id":4000000,"item_ids":[3000000003,3000000001],"with_user_state":true}}}}"#).unwrap();
+    });
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let messages: Vec<_> = stdout.lines().map(assert_valid_json).collect();
+    let result = |id: u64| {
+        messages
+            .iter()
+            .find(|message| message["id"].as_u64() == Some(id))
+            .map(|message| message["result"].clone())
+            .unwrap_or_else(|| panic!("No response for request {}", id))
+    };
+
+    assert_eq!(result(1)["vote"].as_str(), Some("up"));
+    assert_eq!(result(2)["changed"].as_bool(), Some(true));
+
+    let items = result(3);
+    // The down vote from the catalog was replaced.
+    assert_eq!(items[0]["num_upvotes"].as_u64(), Some(100));
+    assert_eq!(items[0]["num_downvotes"].as_u64(), Some(4));
+    assert_eq!(
+        items[0]["user_state"],
+        serde_json::json!({"vote": "up", "favorited": false})
+    );
+    assert_eq!(
+        items[1]["user_state"],
+        serde_json::json!({"vote": null, "favorited": false})
+    );
+}
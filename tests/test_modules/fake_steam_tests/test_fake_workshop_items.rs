@@ -0,0 +1,147 @@
+use crate::test_modules::utils::{FAKE_APP_ID, assert_valid_json, run_fake_steam_command};
+
+#[test]
+fn test_fake_workshop_items() {
+    let output = run_fake_steam_command(&[
+        "workshop-items",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-ids",
+        "3000000003,3000000001,3000000999",
+    ]);
+    assert!(
+        output.status.success(),
+        "workshop-items failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let value = assert_valid_json(&stdout);
+    let items = value.as_array().expect("Expected JSON array");
+    assert_eq!(items.len(), 2, "Unknown item IDs should not be returned");
+
+    assert_eq!(items[0]["published_file_id"].as_u64(), Some(3000000003));
+    assert_eq!(items[0]["title"].as_str(), Some("Weapon Overhaul"));
+    assert_eq!(items[0]["creator_name"].as_str(), Some("Alice"));
+    assert_eq!(items[0]["time_created"].as_u64(), Some(1690000000000));
+
+    assert_eq!(items[1]["published_file_id"].as_u64(), Some(3000000001));
+    assert_eq!(
+        items[1]["tags"],
+        serde_json::json!(["Units", "mod", "v1.2.x"])
+    );
+    assert_eq!(
+        items[1]["display_tags"],
+        serde_json::json!(["Units"]),
+        "Filtered tags such as 'mod' and version tags should be hidden"
+    );
+    assert_eq!(items[0]["display_tags"], serde_json::json!(["Units", "UI"]));
+}
+
+#[test]
+fn test_fake_workshop_items_details() {
+    let output = run_fake_steam_command(&[
+        "workshop-items",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-ids",
+        "3000000001,3000000002",
+    ]);
+    assert!(output.status.success());
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    let items = value.as_array().expect("Expected JSON array");
+
+    assert_eq!(
+        items[0]["metadata"].as_str(),
+        Some(r#"{"min_game_version":"1.2"}"#)
+    );
+    assert_eq!(
+        items[0]["key_value_tags"],
+        serde_json::json!([["game_version", "1.2"], ["faction", "allies"]])
+    );
+    let previews = items[0]["additional_previews"]
+        .as_array()
+        .expect("Expected previews");
+    assert_eq!(previews.len(), 2);
+    assert_eq!(previews[0]["preview_type"].as_str(), Some("image"));
+    assert_eq!(previews[1]["preview_type"].as_str(), Some("youtube-video"));
+    assert_eq!(previews[1]["url_or_video_id"].as_str(), Some("dQw4w9WgXcQ"));
+
+    assert!(items[1]["metadata"].is_null());
+    assert_eq!(items[1]["key_value_tags"], serde_json::json!([]));
+    assert_eq!(items[1]["additional_previews"], serde_json::json!([]));
+}
+
+fn workshop_items_of_type(item_type: &str) -> serde_json::Value {
+    let output = run_fake_steam_command(&[
+        "workshop-items",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-ids",
+        "3000000001,3000000010,3000000999",
+        "--type",
+        item_type,
+    ]);
+    assert!(
+        output.status.success(),
+        "workshop-items failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_valid_json(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn ids(items: &serde_json::Value) -> Vec<u64> {
+    items
+        .as_array()
+        .expect("Expected JSON array")
+        .iter()
+        .map(|item| item["published_file_id"].as_u64().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_fake_workshop_items_of_type() {
+    let result = workshop_items_of_type("collections");
+    assert_eq!(ids(&result["items"]), vec![3000000010]);
+    assert_eq!(ids(&result["filtered"]), vec![3000000001]);
+    assert_eq!(
+        result["filtered"][0]["file_type"].as_str(),
+        Some("Community")
+    );
+    assert_eq!(result["not_found"], serde_json::json!([3000000999u64]));
+
+    let result = workshop_items_of_type("all");
+    assert_eq!(ids(&result["items"]), vec![3000000001, 3000000010]);
+    assert_eq!(ids(&result["filtered"]), Vec::<u64>::new());
+}
+
+#[test]
+fn test_fake_workshop_items_without_type_returns_every_type() {
+    let output = run_fake_steam_command(&[
+        "workshop-items",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-ids",
+        "3000000010,3000000002",
+    ]);
+    assert!(output.status.success());
+
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(ids(&value), vec![3000000010, 3000000002]);
+}
+
+#[test]
+fn test_fake_workshop_items_rejects_unknown_type() {
+    let output = run_fake_steam_command(&[
+        "workshop-items",
+        "--app-id",
+        FAKE_APP_ID,
+        "--item-ids",
+        "3000000001",
+        "--type",
+        "maps",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+}
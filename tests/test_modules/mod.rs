@@ -1,7 +1,12 @@
+#[cfg(feature = "fake-steam")]
+pub mod fake_steam_tests;
 pub mod steam_tests;
 pub mod test_clear_cache;
 pub mod test_cli_help;
 pub mod test_discover_tags_help;
+pub mod test_library_api;
+pub mod test_library_folders_vdf;
+pub mod test_steam_install_paths;
 pub mod test_steam_library_paths;
 pub mod test_workshop_path_non_steam;
 pub mod utils;
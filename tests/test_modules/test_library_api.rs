@@ -0,0 +1,32 @@
+use s7forge::{S7Error, SearchQuery, UGCQueryType, UGCType};
+
+#[test]
+fn test_library_search_query_defaults() {
+    let query = SearchQuery::default();
+    assert_eq!(query.sort_by, UGCQueryType::RankedByTextSearch);
+    assert_eq!(query.page, 1);
+    assert!(query.text.is_empty());
+    assert!(query.period.is_none());
+    assert!(query.tags.required.is_empty());
+    assert_eq!(query.item_type, UGCType::Items);
+}
+
+#[test]
+fn test_library_error_round_trips_through_body() {
+    let error = S7Error::not_subscribed(42);
+    let body = serde_json::to_value(error.body()).unwrap();
+    assert_eq!(body["code"].as_str(), Some("not_subscribed"));
+    assert_eq!(error.exit_code(), 5);
+
+    let rebuilt = S7Error::from_body(
+        body["code"].as_str().unwrap(),
+        body["message"].as_str().unwrap().to_string(),
+        &body["details"],
+    );
+    assert_eq!(rebuilt, error);
+}
+
+#[test]
+fn test_library_workshop_path_unknown_app() {
+    assert_eq!(s7forge::workshop_path(4000000), None);
+}
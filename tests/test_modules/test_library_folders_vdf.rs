@@ -0,0 +1,122 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::test_modules::utils::{assert_valid_json, run_command_with_env};
+
+fn fake_steam_root(name: &str) -> (PathBuf, PathBuf) {
+    let steam_root = std::env::temp_dir().join(name);
+    let library = steam_root.join("library");
+    fs::create_dir_all(steam_root.join("steamapps")).expect("Failed to create fake Steam root");
+    fs::create_dir_all(library.join("steamapps/workshop/content/548430"))
+        .expect("Failed to create fake workshop folder");
+
+    let library_str = library.to_string_lossy().replace('\\', "\\\\");
+    let vdf = format!(
+        r#"// written by the Steam client
+"libraryfolders"
+{{
+	"0"
+	{{
+		"path"		"{library_str}"
+		"label"		"Games"
+		"contentid"		"4839201857461234"
+		"totalsize"		"512110190592"
+		"update_clean_bytes_tally"		"0"
+		"nested"
+		{{
+			"path"		"not-a-library"
+		}}
+		"apps"
+		{{
+			"548430"		"1234567"
+			"228980"		"394201"
+		}}
+	}}
+	"1"
+	{{
+		"path"		"/never/used" [$X360]
+	}}
+}}
+"#
+    );
+    fs::write(steam_root.join("steamapps/libraryfolders.vdf"), vdf)
+        .expect("Failed to write libraryfolders.vdf");
+
+    (steam_root, library)
+}
+
+#[test]
+fn test_steam_library_paths_from_vdf() {
+    let (steam_root, library) = fake_steam_root("s7forge_test_library_paths");
+    let steam_root_str = steam_root.to_string_lossy().to_string();
+
+    let output = run_command_with_env(&["steam-library-paths"], &[("STEAM_ROOT", &steam_root_str)]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let value = assert_valid_json(&stdout);
+    let paths = value.as_array().expect("Expected JSON array");
+    assert_eq!(
+        paths.first().and_then(|p| p.as_str()),
+        Some(library.to_string_lossy().as_ref()),
+        "First library should come from the fake libraryfolders.vdf"
+    );
+    assert!(
+        !paths.iter().any(|p| p.as_str() == Some("not-a-library")),
+        "Nested 'path' keys must not be treated as libraries"
+    );
+    assert!(
+        !paths.iter().any(|p| p.as_str() == Some("/never/used")),
+        "Entries behind a false conditional must be skipped"
+    );
+}
+
+#[test]
+fn test_steam_library_paths_detailed() {
+    let (steam_root, _) = fake_steam_root("s7forge_test_library_detailed");
+    let steam_root_str = steam_root.to_string_lossy().to_string();
+
+    let output = run_command_with_env(
+        &["steam-library-paths", "--detailed"],
+        &[("STEAM_ROOT", &steam_root_str)],
+    );
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let value = assert_valid_json(&stdout);
+    let library = &value.as_array().expect("Expected JSON array")[0];
+
+    assert_eq!(library.get("label").and_then(|v| v.as_str()), Some("Games"));
+    assert_eq!(
+        library.get("contentid").and_then(|v| v.as_str()),
+        Some("4839201857461234")
+    );
+    assert_eq!(
+        library.get("totalsize").and_then(|v| v.as_u64()),
+        Some(512110190592)
+    );
+
+    let apps = library
+        .get("apps")
+        .and_then(|v| v.as_object())
+        .expect("Missing 'apps' object");
+    assert_eq!(apps.get("548430").and_then(|v| v.as_u64()), Some(1234567));
+    assert_eq!(apps.get("228980").and_then(|v| v.as_u64()), Some(394201));
+}
+
+#[test]
+fn test_workshop_path_from_vdf() {
+    let (steam_root, library) = fake_steam_root("s7forge_test_workshop_path");
+    let steam_root_str = steam_root.to_string_lossy().to_string();
+
+    let output = run_command_with_env(
+        &["workshop-path", "--app-id", "548430"],
+        &[("STEAM_ROOT", &steam_root_str)],
+    );
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let value = assert_valid_json(&stdout);
+    let expected = library.join("steamapps/workshop/content/548430");
+    assert_eq!(value.as_str(), Some(expected.to_string_lossy().as_ref()));
+}
@@ -0,0 +1,32 @@
+use std::fs;
+
+use crate::test_modules::utils::{assert_valid_json, run_command_with_env};
+
+#[test]
+fn test_steam_install_paths_env_override() {
+    let steam_root = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("steam-install-paths");
+    let _ = fs::remove_dir_all(&steam_root);
+    fs::create_dir_all(steam_root.join("steamapps")).expect("Failed to create fake Steam root");
+
+    let steam_root_str = steam_root.to_string_lossy().to_string();
+    let output = run_command_with_env(&["steam-install-paths"], &[("STEAM_ROOT", &steam_root_str)]);
+    let _ = fs::remove_dir_all(&steam_root);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let value = assert_valid_json(&stdout);
+    let paths = value.as_array().expect("Expected JSON array");
+    assert!(!paths.is_empty(), "Expected at least one install path");
+
+    let first = &paths[0];
+    assert_eq!(
+        first.get("path").and_then(|p| p.as_str()),
+        Some(steam_root_str.as_str()),
+        "STEAM_ROOT should be reported first"
+    );
+    assert_eq!(
+        first.get("source").and_then(|s| s.as_str()),
+        Some("env-override"),
+        "STEAM_ROOT path should report its source"
+    );
+}
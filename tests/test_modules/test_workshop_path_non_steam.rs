@@ -13,3 +13,61 @@ fn test_workshop_path_non_steam() {
         assert!(stderr.contains("Error:"));
     }
 }
+
+// STEAM_ROOT is checked before the platform defaults, so its broken libraryfolders.vdf
+// comes first and must be skipped rather than end the lookup.
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn test_workshop_path_skips_unreadable_library_file() {
+    use crate::test_modules::utils::run_command_with_env;
+    use std::fs;
+
+    let root = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("workshop-path-roots");
+    let _ = fs::remove_dir_all(&root);
+    let broken_root = root.join("broken");
+    let xdg_data_home = root.join("xdg");
+    let library = root.join("library");
+    let workshop = library
+        .join("steamapps")
+        .join("workshop")
+        .join("content")
+        .join("4000000");
+
+    fs::create_dir_all(broken_root.join("steamapps")).unwrap();
+    fs::write(
+        broken_root.join("steamapps").join("libraryfolders.vdf"),
+        "\"libraryfolders\" {",
+    )
+    .unwrap();
+    fs::create_dir_all(xdg_data_home.join("Steam").join("steamapps")).unwrap();
+    fs::write(
+        xdg_data_home
+            .join("Steam")
+            .join("steamapps")
+            .join("libraryfolders.vdf"),
+        format!(
+            "\"libraryfolders\"\n{{\n\t\"0\"\n\t{{\n\t\t\"path\"\t\t\"{}\"\n\t}}\n}}\n",
+            library.display()
+        ),
+    )
+    .unwrap();
+    fs::create_dir_all(&workshop).unwrap();
+
+    let output = run_command_with_env(
+        &["workshop-path", "--app-id", "4000000"],
+        &[
+            ("STEAM_ROOT", &broken_root.to_string_lossy()),
+            ("HOME", &root.join("home").to_string_lossy()),
+            ("XDG_DATA_HOME", &xdg_data_home.to_string_lossy()),
+        ],
+    );
+    let _ = fs::remove_dir_all(&root);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let value = assert_valid_json(&String::from_utf8_lossy(&output.stdout));
+    assert_eq!(value.as_str(), Some(workshop.to_string_lossy().as_ref()));
+}
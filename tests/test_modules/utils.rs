@@ -42,6 +42,73 @@ pub fn run_command(args: &[&str]) -> std::process::Output {
         .expect("Failed to execute command")
 }
 
+pub fn run_command_with_env(args: &[&str], envs: &[(&str, &str)]) -> std::process::Output {
+    Command::new("cargo")
+        .arg("run")
+        .arg("--")
+        .args(args)
+        .envs(envs.iter().copied())
+        .output()
+        .expect("Failed to execute command")
+}
+
+#[cfg(feature = "fake-steam")]
+pub const FAKE_APP_ID: &str = "4000000";
+
+// The fake backend is only built with the `fake-steam` feature.
+#[cfg(feature = "fake-steam")]
+const FAKE_STEAM_RUN: [&str; 4] = ["run", "--features", "fake-steam", "--"];
+
+/// A cache directory for the running test alone, emptied the first time the test asks
+/// for it, so tests don't see items other tests cached.
+#[cfg(feature = "fake-steam")]
+pub fn fake_cache_dir() -> String {
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+
+    static PREPARED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+    let test_name = std::thread::current()
+        .name()
+        .unwrap_or("shared")
+        .replace("::", "-");
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("fake-cache")
+        .join(&test_name);
+
+    let mut prepared = PREPARED.lock().unwrap();
+    if prepared.get_or_insert_with(HashSet::new).insert(test_name) {
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("Failed to create test cache directory");
+    }
+    dir.to_string_lossy().into_owned()
+}
+
+#[cfg(feature = "fake-steam")]
+pub fn run_fake_steam_command(args: &[&str]) -> std::process::Output {
+    let catalog = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/fake_catalog.json"
+    );
+    run_fake_steam_command_with(catalog, args, &[])
+}
+
+#[cfg(feature = "fake-steam")]
+pub fn run_fake_steam_command_with(
+    catalog: &str,
+    args: &[&str],
+    envs: &[(&str, &str)],
+) -> std::process::Output {
+    Command::new("cargo")
+        .args(FAKE_STEAM_RUN)
+        .args(args)
+        .env("S7FORGE_FAKE_STEAM_CATALOG", catalog)
+        .env("S7FORGE_CACHE_DIR", fake_cache_dir())
+        .envs(envs.iter().copied())
+        .output()
+        .expect("Failed to execute command")
+}
+
 pub fn run_search_workshop_command(args: &[&str]) -> std::process::Output {
     let clear_cache_output = run_command(&["clear-cache"]);
     if !clear_cache_output.status.success() {
@@ -103,3 +170,189 @@ pub fn is_item_subscribed(app_id: u32, item_id: u64) -> Result<bool, String> {
         Err(_) => Err("Failed to parse subscribed items JSON".to_string()),
     }
 }
+
+#[cfg(feature = "fake-steam")]
+pub fn run_fake_steam_serve(requests: &[&str]) -> std::process::Output {
+    use std::io::Write;
+
+    run_fake_steam_serve_with(&[], |stdin| {
+        for request in requests {
+            writeln!(stdin, "{}", request).expect("Failed to write request");
+        }
+    })
+}
+
+/// A running `serve` process. Requests are written to it like to its stdin, and
+/// `wait_for_response` blocks until the response to a request has been printed, so a
+/// test can order requests that would otherwise run concurrently.
+#[cfg(feature = "fake-steam")]
+pub struct FakeServeSession {
+    stdin: std::process::ChildStdin,
+    lines: std::sync::Arc<(std::sync::Mutex<Vec<String>>, std::sync::Condvar)>,
+}
+
+#[cfg(feature = "fake-steam")]
+impl FakeServeSession {
+    pub fn wait_for_response(&mut self, id: u64) -> serde_json::Value {
+        use std::io::Write;
+
+        self.stdin.flush().expect("Failed to flush requests");
+
+        let (lines, printed) = &*self.lines;
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(60);
+        let mut lines = lines.lock().unwrap();
+        loop {
+            let response = lines
+                .iter()
+                .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+                .find(|message| message["id"].as_u64() == Some(id));
+            if let Some(response) = response {
+                return response;
+            }
+
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            assert!(!remaining.is_zero(), "No response to request {}", id);
+            lines = printed.wait_timeout(lines, remaining).unwrap().0;
+        }
+    }
+}
+
+#[cfg(feature = "fake-steam")]
+impl std::io::Write for FakeServeSession {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.stdin.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.stdin.flush()
+    }
+}
+
+#[cfg(feature = "fake-steam")]
+pub fn run_fake_steam_serve_with<F>(args: &[&str], write_requests: F) -> std::process::Output
+where
+    F: FnOnce(&mut FakeServeSession),
+{
+    let catalog = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/fake_catalog.json"
+    );
+    run_fake_steam_serve_with_catalog(catalog, args, write_requests)
+}
+
+#[cfg(feature = "fake-steam")]
+pub fn run_fake_steam_serve_with_catalog<F>(
+    catalog: &str,
+    args: &[&str],
+    write_requests: F,
+) -> std::process::Output
+where
+    F: FnOnce(&mut FakeServeSession),
+{
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+    use std::sync::{Arc, Condvar, Mutex};
+
+    let mut child = Command::new("cargo")
+        .args(FAKE_STEAM_RUN)
+        .arg("serve")
+        .args(args)
+        .env("S7FORGE_FAKE_STEAM_CATALOG", catalog)
+        .env("S7FORGE_CACHE_DIR", fake_cache_dir())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+
+    let lines = Arc::new((Mutex::new(Vec::new()), Condvar::new()));
+    let stdout = child.stdout.take().expect("Failed to open stdout");
+    let reader = {
+        let lines = lines.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                let (lines, printed) = &*lines;
+                lines.lock().unwrap().push(line);
+                printed.notify_all();
+            }
+        })
+    };
+
+    let mut session = FakeServeSession {
+        stdin: child.stdin.take().expect("Failed to open stdin"),
+        lines: lines.clone(),
+    };
+    write_requests(&mut session);
+    drop(session);
+
+    let mut output = child
+        .wait_with_output()
+        .expect("Failed to wait for command");
+    reader.join().expect("Failed to read stdout");
+    output.stdout = lines
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|line| format!("{}\n", line))
+        .collect::<String>()
+        .into_bytes();
+    output
+}
+
+#[cfg(feature = "fake-steam")]
+pub struct FakeHttpServer {
+    pub address: String,
+    child: std::process::Child,
+}
+
+#[cfg(feature = "fake-steam")]
+impl Drop for FakeHttpServer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(feature = "fake-steam")]
+pub fn spawn_fake_http_server() -> FakeHttpServer {
+    spawn_fake_http_server_with(&[])
+}
+
+#[cfg(feature = "fake-steam")]
+pub fn spawn_fake_http_server_with(args: &[&str]) -> FakeHttpServer {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let catalog = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/fake_catalog.json"
+    );
+    let mut child = Command::new("cargo")
+        .args(FAKE_STEAM_RUN)
+        .args(["http-serve", "--port", "0"])
+        .args(args)
+        .env("S7FORGE_FAKE_STEAM_CATALOG", catalog)
+        .env("S7FORGE_CACHE_DIR", fake_cache_dir())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to execute command");
+
+    let stdout = child.stdout.take().expect("Failed to open stdout");
+    let mut line = String::new();
+    BufReader::new(stdout)
+        .read_line(&mut line)
+        .expect("Failed to read listening address");
+
+    let address = line
+        .trim()
+        .strip_prefix("Listening on http://")
+        .unwrap_or_else(|| panic!("Unexpected server output: {}", line))
+        .to_string();
+
+    FakeHttpServer { address, child }
+}